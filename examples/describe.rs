@@ -0,0 +1,25 @@
+//! 打印消息线上格式示例
+//!
+//! 用法: cargo run --example describe [消息类型，如 0x052D]
+//! 不带参数时打印全部消息。
+
+use tdx_rust::protocol::layout::{describe, layouts};
+use tdx_rust::MessageType;
+
+fn main() {
+    match std::env::args().nth(1) {
+        Some(arg) => {
+            let value = u16::from_str_radix(arg.trim_start_matches("0x"), 16)
+                .expect("消息类型应为十六进制数");
+            match MessageType::from_u16(value) {
+                Some(msg_type) => print!("{}", describe(msg_type)),
+                None => eprintln!("未知消息类型: 0x{:04X}", value),
+            }
+        }
+        None => {
+            for l in layouts() {
+                println!("{}", describe(l.msg_type));
+            }
+        }
+    }
+}
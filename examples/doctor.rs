@@ -0,0 +1,126 @@
+//! 服务器诊断示例
+//!
+//! 对每个服务器依次检查 DNS 解析、各端口 TCP 连通性、握手、心跳延迟和北京市场支持，
+//! 打印发现的问题。服务器列表取自 `TDX_HOSTS`，未设置时使用默认服务器列表。
+//!
+//! 用法: cargo run --example doctor
+
+use std::time::{Duration, Instant};
+use tdx_rust::dial::{ALTERNATE_PORTS, DEFAULT_HOSTS};
+use tdx_rust::*;
+use tokio::net::{lookup_host, TcpStream};
+use tokio::time;
+
+const STEP_TIMEOUT: Duration = Duration::from_secs(3);
+
+async fn diagnose(host: &str) -> Vec<String> {
+    let mut findings = Vec::new();
+
+    // DNS 解析
+    match lookup_host((host, 0)).await {
+        Ok(mut addrs) => {
+            if addrs.next().is_none() {
+                findings.push("DNS 未解析出地址".to_string());
+            }
+        }
+        Err(e) => {
+            findings.push(format!("DNS 解析失败: {}", e));
+            return findings;
+        }
+    }
+
+    // TCP 连通性
+    let mut open = Vec::new();
+    for &port in ALTERNATE_PORTS {
+        match time::timeout(STEP_TIMEOUT, TcpStream::connect((host, port))).await {
+            Ok(Ok(_)) => open.push(port),
+            Ok(Err(e)) => findings.push(format!("端口 {} 连接失败: {}", port, e)),
+            Err(_) => findings.push(format!("端口 {} 连接超时（可能被防火墙拦截）", port)),
+        }
+    }
+    let Some(&port) = open.first() else {
+        findings.push("所有端口均不可用".to_string());
+        return findings;
+    };
+
+    // 握手
+    let client = match Client::builder()
+        .connect_timeout(STEP_TIMEOUT)
+        .read_timeout(STEP_TIMEOUT)
+        .reconnect(false)
+        .connect(&format!("{}:{}", host, port))
+        .await
+    {
+        Ok(client) => client,
+        Err(e) => {
+            findings.push(format!("端口 {} 握手失败: {}", port, e));
+            return findings;
+        }
+    };
+
+    // 心跳延迟
+    let start = Instant::now();
+    match client.send_heartbeat().await {
+        Ok(()) => {
+            let elapsed = start.elapsed();
+            if elapsed > Duration::from_millis(500) {
+                findings.push(format!("心跳延迟较高: {:?}", elapsed));
+            }
+        }
+        Err(e) => findings.push(format!("心跳失败: {}", e)),
+    }
+
+    // 北京市场支持
+    match client.get_count(Exchange::BJ).await {
+        Ok(0) => findings.push("不支持北京市场（代码数量为0）".to_string()),
+        Ok(_) => {}
+        Err(e) => findings.push(format!("不支持北京市场: {}", e)),
+    }
+
+    // 行情数据
+    match client.get_quote(&["sh000001".to_string()]).await {
+        Ok(quotes) if quotes.is_empty() => findings.push("行情请求无数据".to_string()),
+        Ok(_) => {}
+        Err(e) => findings.push(format!("行情请求失败: {}", e)),
+    }
+
+    findings
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    let env_hosts = std::env::var(client::ENV_HOSTS).unwrap_or_default();
+    let mut hosts: Vec<&str> = env_hosts
+        .split(',')
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .collect();
+    if hosts.is_empty() {
+        hosts = DEFAULT_HOSTS.to_vec();
+    }
+
+    let tasks: Vec<_> = hosts
+        .iter()
+        .map(|host| {
+            let host = host.to_string();
+            tokio::spawn(async move {
+                let findings = diagnose(&host).await;
+                (host, findings)
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        let Ok((host, findings)) = task.await else {
+            continue;
+        };
+        if findings.is_empty() {
+            println!("[{}] ✓ 正常", host);
+        } else {
+            println!("[{}]", host);
+            for f in findings {
+                println!("  ✗ {}", f);
+            }
+        }
+    }
+}
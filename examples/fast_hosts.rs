@@ -29,7 +29,7 @@ async fn main() {
         println!(
             "{}. {} - {:.2}ms",
             i + 1,
-            result.host,
+            result.addr(),
             result.duration.as_secs_f64() * 1000.0
         );
     }
@@ -37,8 +37,8 @@ async fn main() {
 
     // 使用最快的服务器连接
     if let Some(fastest) = results.first() {
-        println!("\n使用最快的服务器连接: {}", fastest.host);
-        match dial(&fastest.host).await {
+        println!("\n使用最快的服务器连接: {}", fastest.addr());
+        match dial(&fastest.addr()).await {
             Ok(client) => {
                 println!("连接成功！");
                 // 测试获取股票数量
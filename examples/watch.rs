@@ -0,0 +1,59 @@
+//! 行情监控示例：在终端中刷新显示指定股票的行情
+//!
+//! 用法: cargo run --example watch -- 000001 600519 300750
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tdx_rust::*;
+
+fn render(codes: &[String], latest: &BTreeMap<String, QuoteInfo>) {
+    // 清屏并移动光标到左上角
+    print!("\x1B[2J\x1B[H");
+    println!(
+        "{:<10} {:>10} {:>10} {:>8} {:>10} {:>10} {:>12}",
+        "代码", "现价", "涨跌", "涨幅%", "买一", "卖一", "成交量(手)"
+    );
+    for code in codes {
+        let Some(q) = latest.get(&add_prefix(code)) else {
+            println!("{:<10} {:>10}", code, "-");
+            continue;
+        };
+        let change = q.k.close.to_yuan() - q.k.last.to_yuan();
+        let pct = if q.k.last.0 != 0 {
+            change / q.k.last.to_yuan() * 100.0
+        } else {
+            0.0
+        };
+        println!(
+            "{:<10} {:>10.2} {:>+10.2} {:>+8.2} {:>10.2} {:>10.2} {:>12}",
+            code,
+            q.k.close.to_yuan(),
+            change,
+            pct,
+            q.buy_level[0].price.to_yuan(),
+            q.sell_level[0].price.to_yuan(),
+            q.total_hand
+        );
+    }
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() -> Result<(), ClientError> {
+    let codes: Vec<String> = std::env::args().skip(1).collect();
+    if codes.is_empty() {
+        eprintln!("用法: watch <代码>...");
+        return Ok(());
+    }
+
+    let client = Arc::new(Client::from_env().await?);
+    let mut rx = ShardedSubscriber::new(vec![client], codes.clone(), QUOTE_MAX_CODES).spawn();
+
+    let mut latest = BTreeMap::new();
+    while let Some(quotes) = rx.recv().await {
+        for q in quotes {
+            latest.insert(format!("{}{}", q.exchange.as_str(), q.code), q);
+        }
+        render(&codes, &latest);
+    }
+    Ok(())
+}
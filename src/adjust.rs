@@ -0,0 +1,97 @@
+//! K线复权
+//!
+//! 根据除权除息数据（gbbq 类别1）计算复权因子并调整K线价格。
+
+use crate::protocol::{Gbbq, Kline, Price};
+
+/// 复权方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Adjustment {
+    /// 不复权
+    #[default]
+    None,
+    /// 前复权（最新价格不变，调整历史价格）
+    Forward,
+    /// 后复权（最早价格不变，调整之后的价格）
+    Backward,
+}
+
+/// 北京时间的天序号
+fn day(ts: i64) -> i64 {
+    (ts + 8 * 3600).div_euclid(86400)
+}
+
+/// 计算每根K线的复权因子
+///
+/// klines 需按时间升序排列；只使用除权除息（类别1）记录。
+/// 除权日第一根K线的前收盘价为 `P`，除权参考价为
+/// `(P - 分红/10 + 配股价×配股/10) / (1 + 送转股/10 + 配股/10)`，
+/// 两者之比即为该次除权的调整比例。
+pub fn factors(klines: &[Kline], gbbq: &[Gbbq], adjustment: Adjustment) -> Vec<f64> {
+    let mut factors = vec![1.0; klines.len()];
+    if adjustment == Adjustment::None || klines.is_empty() {
+        return factors;
+    }
+
+    let mut events: Vec<&Gbbq> = gbbq.iter().filter(|g| g.is_xrxd()).collect();
+    events.sort_by_key(|g| g.time);
+
+    // 每根K线自身的调整比例（除权日之前一根收盘价 → 除权参考价）
+    let mut ratios = vec![1.0; klines.len()];
+    for g in events {
+        let event_day = day(g.time);
+        let Some(i) = klines.iter().position(|k| day(k.time) >= event_day) else {
+            continue;
+        };
+        if i == 0 {
+            continue;
+        }
+        let prev_close = klines[i - 1].close.to_yuan();
+        if prev_close <= 0.0 {
+            continue;
+        }
+        let base = 1.0 + g.c3 / 10.0 + g.c4 / 10.0;
+        let reference = (prev_close - g.c1 / 10.0 + g.c2 * g.c4 / 10.0) / base;
+        if reference > 0.0 {
+            ratios[i] *= reference / prev_close;
+        }
+    }
+
+    match adjustment {
+        Adjustment::None => {}
+        Adjustment::Forward => {
+            let mut acc = 1.0;
+            for i in (0..klines.len()).rev() {
+                factors[i] = acc;
+                acc *= ratios[i];
+            }
+        }
+        Adjustment::Backward => {
+            let mut acc = 1.0;
+            for i in 0..klines.len() {
+                acc /= ratios[i];
+                factors[i] = acc;
+            }
+        }
+    }
+    factors
+}
+
+fn scale(price: Price, factor: f64) -> Price {
+    Price((price.0 as f64 * factor).round() as i64)
+}
+
+/// 按复权方式调整K线价格（klines 需按时间升序排列）
+pub fn adjust(klines: &mut [Kline], gbbq: &[Gbbq], adjustment: Adjustment) {
+    if adjustment == Adjustment::None {
+        return;
+    }
+    let factors = factors(klines, gbbq, adjustment);
+    for (k, f) in klines.iter_mut().zip(factors) {
+        k.last = scale(k.last, f);
+        k.open = scale(k.open, f);
+        k.high = scale(k.high, f);
+        k.low = scale(k.low, f);
+        k.close = scale(k.close, f);
+    }
+}
@@ -0,0 +1,122 @@
+//! 多股票K线时间对齐
+//!
+//! 将多只股票的K线按时间对齐为矩阵（行为时间，列为股票），
+//! 是横截面因子计算前的标准预处理步骤。
+
+use crate::protocol::Kline;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// 对齐字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// 收盘价（元）
+    Close,
+    /// 开盘价（元）
+    Open,
+    /// 成交量
+    Volume,
+    /// 成交额（元）
+    Amount,
+}
+
+impl Field {
+    fn value(self, k: &Kline) -> f64 {
+        match self {
+            Field::Close => k.close.to_yuan(),
+            Field::Open => k.open.to_yuan(),
+            Field::Volume => k.volume as f64,
+            Field::Amount => k.amount.to_yuan(),
+        }
+    }
+}
+
+/// 缺失值处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillPolicy {
+    /// 使用该股票上一个有效值填充（首个有效值之前为 NaN）
+    ForwardFill,
+    /// 丢弃任一股票缺失的时间点
+    Drop,
+    /// 缺失处填 NaN
+    Nan,
+}
+
+/// 对齐后的矩阵
+#[derive(Debug, Clone, Default)]
+pub struct Matrix {
+    /// 时间（升序）
+    pub times: Vec<i64>,
+    /// 股票代码（列顺序）
+    pub symbols: Vec<String>,
+    /// 数据，`values[行][列]`
+    pub values: Vec<Vec<f64>>,
+}
+
+impl Matrix {
+    /// 行数（时间点数量）
+    pub fn rows(&self) -> usize {
+        self.times.len()
+    }
+
+    /// 列数（股票数量）
+    pub fn cols(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// 某个时间点的横截面数据
+    pub fn row(&self, i: usize) -> &[f64] {
+        &self.values[i]
+    }
+
+    /// 某只股票的时间序列
+    pub fn column(&self, symbol: &str) -> Option<Vec<f64>> {
+        let j = self.symbols.iter().position(|s| s == symbol)?;
+        Some(self.values.iter().map(|row| row[j]).collect())
+    }
+}
+
+/// 按时间对齐多只股票的K线
+///
+/// 时间轴为所有股票K线时间的并集（`Drop` 时为交集）。
+pub fn join(series: &BTreeMap<String, Vec<Kline>>, field: Field, policy: FillPolicy) -> Matrix {
+    let symbols: Vec<String> = series.keys().cloned().collect();
+    let times: BTreeSet<i64> = series.values().flatten().map(|k| k.time).collect();
+    let lookup: Vec<HashMap<i64, f64>> = series
+        .values()
+        .map(|klines| klines.iter().map(|k| (k.time, field.value(k))).collect())
+        .collect();
+
+    let mut matrix = Matrix {
+        symbols,
+        ..Default::default()
+    };
+    let mut last = vec![f64::NAN; lookup.len()];
+
+    for time in times {
+        let mut row = Vec::with_capacity(lookup.len());
+        let mut complete = true;
+        for (j, values) in lookup.iter().enumerate() {
+            let value = match values.get(&time) {
+                Some(&v) => {
+                    last[j] = v;
+                    v
+                }
+                None => {
+                    complete = false;
+                    match policy {
+                        FillPolicy::ForwardFill => last[j],
+                        FillPolicy::Drop | FillPolicy::Nan => f64::NAN,
+                    }
+                }
+            };
+            row.push(value);
+        }
+
+        if policy == FillPolicy::Drop && !complete {
+            continue;
+        }
+        matrix.times.push(time);
+        matrix.values.push(row);
+    }
+    matrix
+}
@@ -0,0 +1,514 @@
+//! 紧凑二进制归档格式（K线/分时成交）
+//!
+//! 文件布局（小端序）：
+//!
+//! ```text
+//! +--------+---------+------+-------------+-------+--------------+-----------+
+//! | Magic  | Version | Kind | RecordSize  | Count | IndexStride  | IndexLen  |
+//! | (4字节) | (1字节)  | (1字节)| (2字节)     | (4字节)| (4字节)       | (4字节)    |
+//! +--------+---------+------+-------------+-------+--------------+-----------+
+//! | Index: 时间戳(i64) × IndexLen                                            |
+//! +-------------------------------------------------------------------------+
+//! | Records: 定长记录 × Count                                                 |
+//! +-------------------------------------------------------------------------+
+//! ```
+//!
+//! 记录按时间升序存放且定长，索引每隔 `IndexStride` 条记录保存一次时间戳，
+//! 可以只读取头部和索引就定位到任意时间范围（见 [`ArchiveReader`]）。
+
+use crate::protocol::{Amount, Kline, Price, Trade, TradeStatus};
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// 文件魔数
+pub const MAGIC: &[u8; 4] = b"TDXA";
+
+/// 当前格式版本
+pub const VERSION: u8 = 1;
+
+/// 文件头长度
+pub const HEADER_LEN: usize = 20;
+
+/// 默认索引间隔（每多少条记录保存一个索引项）
+pub const DEFAULT_INDEX_STRIDE: u32 = 256;
+
+/// 归档错误
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("IO错误: {0}")]
+    Io(#[from] io::Error),
+    #[error("无效的归档文件: {0}")]
+    InvalidFormat(String),
+    #[error("记录类型不匹配: 期望 {expected}, 得到 {actual}")]
+    KindMismatch { expected: u8, actual: u8 },
+    #[error("记录未按时间升序排列（第 {0} 条）")]
+    Unsorted(usize),
+    #[error("字段超出可存储范围: {0}")]
+    OutOfRange(&'static str),
+}
+
+/// 可归档的定长记录
+pub trait ArchiveRecord: Sized {
+    /// 记录类型标识
+    const KIND: u8;
+    /// 单条记录字节数
+    const SIZE: usize;
+
+    /// 记录时间（Unix时间戳，秒）
+    fn time(&self) -> i64;
+
+    /// 编码到缓冲区（追加 `SIZE` 字节）
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), ArchiveError>;
+
+    /// 从 `SIZE` 字节解码
+    fn decode(bytes: &[u8]) -> Self;
+}
+
+fn price_to_i32(price: Price, field: &'static str) -> Result<i32, ArchiveError> {
+    i32::try_from(price.0).map_err(|_| ArchiveError::OutOfRange(field))
+}
+
+fn read_i32(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_i64(bytes: &[u8], offset: usize) -> i64 {
+    i64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+impl ArchiveRecord for Kline {
+    const KIND: u8 = 1;
+    // time(8) + last/open/high/low/close(4×5) + order(4) + volume(8) + amount(8) + up/down(4×2)
+    const SIZE: usize = 56;
+
+    fn time(&self) -> i64 {
+        self.time
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), ArchiveError> {
+        buf.extend_from_slice(&self.time.to_le_bytes());
+        buf.extend_from_slice(&price_to_i32(self.last, "last")?.to_le_bytes());
+        buf.extend_from_slice(&price_to_i32(self.open, "open")?.to_le_bytes());
+        buf.extend_from_slice(&price_to_i32(self.high, "high")?.to_le_bytes());
+        buf.extend_from_slice(&price_to_i32(self.low, "low")?.to_le_bytes());
+        buf.extend_from_slice(&price_to_i32(self.close, "close")?.to_le_bytes());
+        buf.extend_from_slice(&self.order.to_le_bytes());
+        buf.extend_from_slice(&self.volume.to_le_bytes());
+        let amount =
+            i64::try_from(self.amount.0).map_err(|_| ArchiveError::OutOfRange("amount"))?;
+        buf.extend_from_slice(&amount.to_le_bytes());
+        buf.extend_from_slice(&self.up_count.to_le_bytes());
+        buf.extend_from_slice(&self.down_count.to_le_bytes());
+        Ok(())
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        Kline {
+            time: read_i64(bytes, 0),
+            last: Price(read_i32(bytes, 8) as i64),
+            open: Price(read_i32(bytes, 12) as i64),
+            high: Price(read_i32(bytes, 16) as i64),
+            low: Price(read_i32(bytes, 20) as i64),
+            close: Price(read_i32(bytes, 24) as i64),
+            order: read_i32(bytes, 28),
+            volume: read_i64(bytes, 32),
+            amount: Amount(read_i64(bytes, 40) as i128),
+            up_count: read_i32(bytes, 48),
+            down_count: read_i32(bytes, 52),
+        }
+    }
+}
+
+impl ArchiveRecord for Trade {
+    const KIND: u8 = 2;
+    // time(8) + price(4) + volume(4) + status(1) + number(4)
+    const SIZE: usize = 21;
+
+    fn time(&self) -> i64 {
+        self.time
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), ArchiveError> {
+        buf.extend_from_slice(&self.time.to_le_bytes());
+        buf.extend_from_slice(&price_to_i32(self.price, "price")?.to_le_bytes());
+        buf.extend_from_slice(&self.volume.to_le_bytes());
+        let status =
+            u8::try_from(self.status.value()).map_err(|_| ArchiveError::OutOfRange("status"))?;
+        buf.push(status);
+        buf.extend_from_slice(&self.number.to_le_bytes());
+        Ok(())
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        let status = TradeStatus::from_value(bytes[16] as i32);
+        Trade {
+            time: read_i64(bytes, 0),
+            price: Price(read_i32(bytes, 8) as i64),
+            volume: read_i32(bytes, 12),
+            status,
+            number: read_i32(bytes, 17),
+        }
+    }
+}
+
+/// 归档文件头
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveHeader {
+    pub kind: u8,
+    pub record_size: u16,
+    pub count: u32,
+    pub index_stride: u32,
+    pub index_len: u32,
+}
+
+impl ArchiveHeader {
+    /// 从字节数组解析文件头
+    pub fn decode(bytes: &[u8]) -> Result<Self, ArchiveError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(ArchiveError::InvalidFormat("文件头长度不足".to_string()));
+        }
+        if &bytes[0..4] != MAGIC {
+            return Err(ArchiveError::InvalidFormat("魔数不匹配".to_string()));
+        }
+        if bytes[4] != VERSION {
+            return Err(ArchiveError::InvalidFormat(format!(
+                "不支持的版本: {}",
+                bytes[4]
+            )));
+        }
+        let header = Self {
+            kind: bytes[5],
+            record_size: u16::from_le_bytes([bytes[6], bytes[7]]),
+            count: u32::from_le_bytes(bytes[8..12].try_into().unwrap()),
+            index_stride: u32::from_le_bytes(bytes[12..16].try_into().unwrap()),
+            index_len: u32::from_le_bytes(bytes[16..20].try_into().unwrap()),
+        };
+        if header.index_stride == 0 {
+            return Err(ArchiveError::InvalidFormat("索引间隔为0".to_string()));
+        }
+        Ok(header)
+    }
+
+    /// 编码为字节数组
+    pub fn encode(&self) -> [u8; HEADER_LEN] {
+        let mut buf = [0u8; HEADER_LEN];
+        buf[0..4].copy_from_slice(MAGIC);
+        buf[4] = VERSION;
+        buf[5] = self.kind;
+        buf[6..8].copy_from_slice(&self.record_size.to_le_bytes());
+        buf[8..12].copy_from_slice(&self.count.to_le_bytes());
+        buf[12..16].copy_from_slice(&self.index_stride.to_le_bytes());
+        buf[16..20].copy_from_slice(&self.index_len.to_le_bytes());
+        buf
+    }
+
+    /// 记录区在文件中的起始偏移
+    pub fn data_offset(&self) -> usize {
+        HEADER_LEN + self.index_len as usize * 8
+    }
+
+    /// 文件总长度
+    pub fn total_len(&self) -> usize {
+        self.data_offset() + self.count as usize * self.record_size as usize
+    }
+
+    /// 校验记录类型
+    pub fn check<R: ArchiveRecord>(&self) -> Result<(), ArchiveError> {
+        if self.kind != R::KIND {
+            return Err(ArchiveError::KindMismatch {
+                expected: R::KIND,
+                actual: self.kind,
+            });
+        }
+        if self.record_size as usize != R::SIZE {
+            return Err(ArchiveError::InvalidFormat(format!(
+                "记录长度不匹配: 期望 {}, 得到 {}",
+                R::SIZE,
+                self.record_size
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 归档编解码
+pub struct Archive;
+
+impl Archive {
+    /// 编码记录为归档字节（记录需按时间升序）
+    pub fn encode<R: ArchiveRecord>(records: &[R]) -> Result<Vec<u8>, ArchiveError> {
+        Self::encode_with_stride(records, DEFAULT_INDEX_STRIDE)
+    }
+
+    /// 使用指定索引间隔编码
+    pub fn encode_with_stride<R: ArchiveRecord>(
+        records: &[R],
+        index_stride: u32,
+    ) -> Result<Vec<u8>, ArchiveError> {
+        let index_stride = index_stride.max(1);
+        let count = u32::try_from(records.len()).map_err(|_| ArchiveError::OutOfRange("count"))?;
+
+        for (i, pair) in records.windows(2).enumerate() {
+            if pair[1].time() < pair[0].time() {
+                return Err(ArchiveError::Unsorted(i + 1));
+            }
+        }
+
+        let index: Vec<i64> = records
+            .iter()
+            .step_by(index_stride as usize)
+            .map(|r| r.time())
+            .collect();
+
+        let header = ArchiveHeader {
+            kind: R::KIND,
+            record_size: R::SIZE as u16,
+            count,
+            index_stride,
+            index_len: index.len() as u32,
+        };
+
+        let mut buf = Vec::with_capacity(header.total_len());
+        buf.extend_from_slice(&header.encode());
+        for time in &index {
+            buf.extend_from_slice(&time.to_le_bytes());
+        }
+        for record in records {
+            record.encode(&mut buf)?;
+        }
+        Ok(buf)
+    }
+
+    /// 从归档字节解码全部记录
+    pub fn decode<R: ArchiveRecord>(bytes: &[u8]) -> Result<Vec<R>, ArchiveError> {
+        let header = ArchiveHeader::decode(bytes)?;
+        header.check::<R>()?;
+        if bytes.len() < header.total_len() {
+            return Err(ArchiveError::InvalidFormat("记录区长度不足".to_string()));
+        }
+
+        Ok(bytes[header.data_offset()..header.total_len()]
+            .chunks_exact(R::SIZE)
+            .map(R::decode)
+            .collect())
+    }
+
+    /// 写入归档文件
+    pub fn write_file<R: ArchiveRecord, P: AsRef<Path>>(
+        path: P,
+        records: &[R],
+    ) -> Result<(), ArchiveError> {
+        let bytes = Self::encode(records)?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&bytes)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// 迭代器每次从文件读取的记录数
+const ITER_BLOCK: usize = 256;
+
+/// 从文件的指定位置读满 buf，不改变文件的读写位置
+#[cfg(unix)]
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    use std::os::unix::fs::FileExt;
+    file.read_exact_at(buf, offset)
+}
+
+/// 从文件的指定位置读满 buf（Windows 上 `seek_read` 会移动读写位置，本模块的读取都不依赖它）
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buf.is_empty() {
+        match file.seek_read(buf, offset) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => {
+                buf = &mut buf[n..];
+                offset += n as u64;
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// 归档文件读取器
+///
+/// 打开时只读取文件头和索引，记录按需从文件中定位读取，
+/// 不会把整个文件载入内存。所有读取都按位置进行，[`ArchiveReader::get`]、
+/// [`ArchiveReader::lower_bound`] 和多个迭代器可以交替使用。
+pub struct ArchiveReader<R: ArchiveRecord> {
+    file: File,
+    header: ArchiveHeader,
+    index: Vec<i64>,
+    _marker: PhantomData<R>,
+}
+
+impl<R: ArchiveRecord> ArchiveReader<R> {
+    /// 打开归档文件
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ArchiveError> {
+        let mut file = File::open(path)?;
+
+        let mut head = [0u8; HEADER_LEN];
+        file.read_exact(&mut head)?;
+        let header = ArchiveHeader::decode(&head)?;
+        header.check::<R>()?;
+
+        if (file.metadata()?.len() as usize) < header.total_len() {
+            return Err(ArchiveError::InvalidFormat("记录区长度不足".to_string()));
+        }
+
+        let mut index_bytes = vec![0u8; header.index_len as usize * 8];
+        file.read_exact(&mut index_bytes)?;
+        let index = index_bytes
+            .chunks_exact(8)
+            .map(|b| read_i64(b, 0))
+            .collect();
+
+        Ok(Self {
+            file,
+            header,
+            index,
+            _marker: PhantomData,
+        })
+    }
+
+    /// 文件头
+    pub fn header(&self) -> &ArchiveHeader {
+        &self.header
+    }
+
+    /// 记录数量
+    pub fn len(&self) -> usize {
+        self.header.count as usize
+    }
+
+    /// 是否没有记录
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn record_offset(&self, i: usize) -> u64 {
+        (self.header.data_offset() + i * R::SIZE) as u64
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), ArchiveError> {
+        read_exact_at(&self.file, buf, offset)?;
+        Ok(())
+    }
+
+    /// 读取第 i 条记录
+    pub fn get(&self, i: usize) -> Result<Option<R>, ArchiveError> {
+        if i >= self.len() {
+            return Ok(None);
+        }
+        let mut buf = vec![0u8; R::SIZE];
+        self.read_at(self.record_offset(i), &mut buf)?;
+        Ok(Some(R::decode(&buf)))
+    }
+
+    /// 读取第 i 条记录的时间（记录的前8字节）
+    fn time_at(&self, i: usize) -> Result<i64, ArchiveError> {
+        let mut buf = [0u8; 8];
+        self.read_at(self.record_offset(i), &mut buf)?;
+        Ok(i64::from_le_bytes(buf))
+    }
+
+    /// 第一条时间 >= `time` 的记录位置（没有则返回 `len()`）
+    ///
+    /// 先用索引定位到所在区块，再在区块内二分查找。
+    pub fn lower_bound(&self, time: i64) -> Result<usize, ArchiveError> {
+        let stride = self.header.index_stride as usize;
+        let block = self.index.partition_point(|&t| t < time);
+        if block == 0 {
+            return Ok(0);
+        }
+
+        let mut lo = (block - 1) * stride + 1;
+        let mut hi = (block * stride).min(self.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.time_at(mid)? < time {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        Ok(lo)
+    }
+
+    /// 从头开始顺序迭代
+    pub fn iter(&self) -> Result<ArchiveIter<'_, R>, ArchiveError> {
+        self.iter_from(0)
+    }
+
+    /// 从第 `start` 条记录开始顺序迭代
+    pub fn iter_from(&self, start: usize) -> Result<ArchiveIter<'_, R>, ArchiveError> {
+        let start = start.min(self.len());
+        Ok(ArchiveIter {
+            reader: self,
+            next: start,
+            block: Vec::new(),
+            block_start: start,
+        })
+    }
+
+    /// 读取时间范围 [start_time, end_time] 内的记录
+    pub fn range(&self, start_time: i64, end_time: i64) -> Result<Vec<R>, ArchiveError> {
+        let start = self.lower_bound(start_time)?;
+        let mut list = Vec::new();
+        for record in self.iter_from(start)? {
+            let record = record?;
+            if record.time() > end_time {
+                break;
+            }
+            list.push(record);
+        }
+        Ok(list)
+    }
+}
+
+/// 归档记录顺序迭代器
+///
+/// 自己记录读取位置，每次按位置读取一批记录，不与读取器或其他迭代器共享文件位置。
+pub struct ArchiveIter<'a, R: ArchiveRecord> {
+    reader: &'a ArchiveReader<R>,
+    next: usize,
+    // 已读取的一批记录及其第一条的序号
+    block: Vec<u8>,
+    block_start: usize,
+}
+
+impl<R: ArchiveRecord> Iterator for ArchiveIter<'_, R> {
+    type Item = Result<R, ArchiveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.reader.len();
+        if self.next >= len {
+            return None;
+        }
+        if self.next >= self.block_start + self.block.len() / R::SIZE {
+            let n = ITER_BLOCK.min(len - self.next);
+            self.block.resize(n * R::SIZE, 0);
+            self.block_start = self.next;
+            let offset = self.reader.record_offset(self.next);
+            if let Err(e) = self.reader.read_at(offset, &mut self.block) {
+                self.block.clear();
+                self.next = len;
+                return Some(Err(e));
+            }
+        }
+        let at = (self.next - self.block_start) * R::SIZE;
+        self.next += 1;
+        Some(Ok(R::decode(&self.block[at..at + R::SIZE])))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.reader.len() - self.next;
+        (remaining, Some(remaining))
+    }
+}
@@ -0,0 +1,84 @@
+//! 行情请求合并
+//!
+//! 多个组件各自轮询行情时，往往在同一时刻请求重叠的代码。[`QuoteBatcher`] 把一个时间窗口内的
+//! 所有请求合并成一次 [`Client::get_quote_batch`]（代码去重），再把结果按各自请求的代码分发回去。
+
+use crate::client::{duplicate_error, Client, ClientError};
+use crate::protocol::{add_prefix, QuoteInfo};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{self, Instant};
+
+/// 默认的合并窗口
+pub const DEFAULT_BATCH_WINDOW: Duration = Duration::from_millis(5);
+
+type Request = (
+    Vec<String>,
+    oneshot::Sender<Result<Vec<QuoteInfo>, ClientError>>,
+);
+
+/// 行情请求合并器
+///
+/// 可以克隆后交给多个任务使用；全部克隆被丢弃后后台任务在处理完已收到的请求后退出。
+#[derive(Clone)]
+pub struct QuoteBatcher {
+    tx: mpsc::UnboundedSender<Request>,
+}
+
+impl QuoteBatcher {
+    /// 收到第一个请求后再等待 window，期间的请求合并发送
+    pub fn new(client: Arc<Client>, window: Duration) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(run(client, window, rx));
+        Self { tx }
+    }
+
+    /// 获取行情信息，结果按输入顺序排列（服务器未返回的代码被跳过），与 [`Client::get_quote_batch`] 一致
+    pub async fn get_quote(&self, codes: &[String]) -> Result<Vec<QuoteInfo>, ClientError> {
+        let (reply, rx) = oneshot::channel();
+        self.tx
+            .send((codes.to_vec(), reply))
+            .map_err(|_| ClientError::Disconnected)?;
+        rx.await.map_err(|_| ClientError::Disconnected)?
+    }
+}
+
+async fn run(client: Arc<Client>, window: Duration, mut rx: mpsc::UnboundedReceiver<Request>) {
+    while let Some(first) = rx.recv().await {
+        let mut batch = vec![first];
+        let deadline = Instant::now() + window;
+        while let Ok(Some(req)) = time::timeout_at(deadline, rx.recv()).await {
+            batch.push(req);
+        }
+
+        let mut seen = HashSet::new();
+        let codes: Vec<String> = batch
+            .iter()
+            .flat_map(|(codes, _)| codes.iter().map(|c| add_prefix(c)))
+            .filter(|c| seen.insert(c.clone()))
+            .collect();
+
+        match client.get_quote_batch(&codes).await {
+            Ok(quotes) => {
+                let by_code: HashMap<String, QuoteInfo> = quotes
+                    .into_iter()
+                    .map(|q| (format!("{}{}", q.exchange.as_str(), q.code), q))
+                    .collect();
+                for (codes, reply) in batch {
+                    let list = codes
+                        .iter()
+                        .filter_map(|c| by_code.get(&add_prefix(c)).cloned())
+                        .collect();
+                    let _ = reply.send(Ok(list));
+                }
+            }
+            Err(e) => {
+                for (_, reply) in batch {
+                    let _ = reply.send(Err(duplicate_error(&e)));
+                }
+            }
+        }
+    }
+}
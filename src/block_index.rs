@@ -0,0 +1,136 @@
+//! 股票所属板块索引
+//!
+//! 由板块成分（板块 → 代码）建立倒排索引（代码 → 板块），用于按股票查所属板块；
+//! 刷新成分时与上一次比较，给出股票调入/调出板块的变化。索引可以保存为 JSON 文件，
+//! 下次启动直接加载。
+//!
+//! 本库目前不下载板块文件，板块成分需要由调用方提供。
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+
+/// 板块索引错误
+#[derive(Debug, thiserror::Error)]
+pub enum BlockIndexError {
+    #[error("IO错误: {0}")]
+    Io(#[from] io::Error),
+    #[error("序列化错误: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+/// 板块成分变化
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockChange {
+    /// 股票调入板块
+    Joined { code: String, block: String },
+    /// 股票调出板块
+    Left { code: String, block: String },
+}
+
+/// 板块倒排索引
+#[derive(Debug, Clone, Default)]
+pub struct BlockIndex {
+    blocks: BTreeMap<String, BTreeSet<String>>,
+    by_code: HashMap<String, Vec<String>>,
+}
+
+impl From<BTreeMap<String, BTreeSet<String>>> for BlockIndex {
+    fn from(blocks: BTreeMap<String, BTreeSet<String>>) -> Self {
+        let mut by_code: HashMap<String, Vec<String>> = HashMap::new();
+        for (block, codes) in &blocks {
+            for code in codes {
+                by_code.entry(code.clone()).or_default().push(block.clone());
+            }
+        }
+        Self { blocks, by_code }
+    }
+}
+
+impl BlockIndex {
+    /// 由 (板块名称, 成分代码) 建立索引
+    pub fn new<I, C>(blocks: I) -> Self
+    where
+        I: IntoIterator<Item = (String, C)>,
+        C: IntoIterator<Item = String>,
+    {
+        let mut map: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for (block, codes) in blocks {
+            map.entry(block).or_default().extend(codes);
+        }
+        map.into()
+    }
+
+    /// 板块数量
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// 股票所属的板块（按板块名称排序）
+    pub fn blocks_of(&self, code: &str) -> &[String] {
+        self.by_code.get(code).map_or(&[], Vec::as_slice)
+    }
+
+    /// 板块的成分代码
+    pub fn codes_of(&self, block: &str) -> Option<&BTreeSet<String>> {
+        self.blocks.get(block)
+    }
+
+    /// 全部板块名称
+    pub fn block_names(&self) -> impl Iterator<Item = &str> {
+        self.blocks.keys().map(String::as_str)
+    }
+
+    /// 用新的板块成分替换当前索引，返回变化（按板块、代码排序）
+    pub fn update(&mut self, new: BlockIndex) -> Vec<BlockChange> {
+        let empty = BTreeSet::new();
+        let names: BTreeSet<&String> = self.blocks.keys().chain(new.blocks.keys()).collect();
+        let mut changes = Vec::new();
+        for block in names {
+            let old_codes = self.blocks.get(block).unwrap_or(&empty);
+            let new_codes = new.blocks.get(block).unwrap_or(&empty);
+            let mut diff: Vec<(&String, BlockChange)> = Vec::new();
+            for code in old_codes.difference(new_codes) {
+                diff.push((
+                    code,
+                    BlockChange::Left {
+                        code: code.clone(),
+                        block: block.clone(),
+                    },
+                ));
+            }
+            for code in new_codes.difference(old_codes) {
+                diff.push((
+                    code,
+                    BlockChange::Joined {
+                        code: code.clone(),
+                        block: block.clone(),
+                    },
+                ));
+            }
+            diff.sort_by(|a, b| a.0.cmp(b.0));
+            changes.extend(diff.into_iter().map(|(_, c)| c));
+        }
+        *self = new;
+        changes
+    }
+
+    /// 保存为 JSON 文件（板块 → 成分代码）
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), BlockIndexError> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, &self.blocks)?;
+        Ok(())
+    }
+
+    /// 从 [`BlockIndex::save`] 保存的文件加载
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BlockIndexError> {
+        let reader = BufReader::new(File::open(path)?);
+        let blocks: BTreeMap<String, BTreeSet<String>> = serde_json::from_reader(reader)?;
+        Ok(blocks.into())
+    }
+}
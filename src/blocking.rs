@@ -0,0 +1,189 @@
+//! 同步客户端（需要开启 `blocking` 特性）
+//!
+//! 内部持有一个单线程 tokio 运行时，每个方法都与 [`crate::Client`] 的同名异步方法一一对应，
+//! 适合脚本和不使用 async 的程序。不要在 tokio 运行时内部调用。
+
+use crate::adjust::Adjustment;
+use crate::client::{ClientBuilder, ClientError, RequestOptions};
+use crate::freshness::DataFreshness;
+use crate::protocol::*;
+use chrono::NaiveDate;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+
+/// 同步 TDX 客户端
+pub struct Client {
+    rt: Runtime,
+    inner: crate::Client,
+}
+
+fn runtime() -> Result<Runtime, ClientError> {
+    Ok(Builder::new_current_thread().enable_all().build()?)
+}
+
+macro_rules! blocking_methods {
+    ($(fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            pub fn $name(&self $(, $arg: $ty)*) -> Result<$ret, ClientError> {
+                self.rt.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+impl Client {
+    /// 连接到指定地址
+    pub fn connect(addr: &str) -> Result<Self, ClientError> {
+        let rt = runtime()?;
+        let inner = rt.block_on(crate::Client::connect(addr))?;
+        Ok(Self { rt, inner })
+    }
+
+    /// 按构建器的选项连接到指定地址
+    pub fn connect_with(builder: ClientBuilder, addr: &str) -> Result<Self, ClientError> {
+        let rt = runtime()?;
+        let inner = rt.block_on(builder.connect(addr))?;
+        Ok(Self { rt, inner })
+    }
+
+    /// 根据环境变量创建客户端，见 [`crate::Client::from_env`]
+    pub fn from_env() -> Result<Self, ClientError> {
+        let rt = runtime()?;
+        let inner = rt.block_on(crate::Client::from_env())?;
+        Ok(Self { rt, inner })
+    }
+
+    /// 内部的异步客户端
+    pub fn inner(&self) -> &crate::Client {
+        &self.inner
+    }
+
+    /// 设置超时时间
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.inner.set_timeout(timeout);
+    }
+
+    /// 设置默认请求选项
+    pub fn set_request_options(&mut self, options: RequestOptions) {
+        self.inner.set_request_options(options);
+    }
+
+    /// 限制每秒发送的请求数，0 表示不限制
+    pub fn set_rate_limit(&mut self, reqs_per_sec: f64) {
+        self.inner.set_rate_limit(reqs_per_sec);
+    }
+
+    /// 设置帧观察回调（见 [`crate::Client::set_frame_observer`]）
+    pub fn set_frame_observer(
+        &mut self,
+        observer: impl Fn(&RequestFrame, &ResponseFrame) + Send + Sync + 'static,
+    ) {
+        self.inner.set_frame_observer(observer);
+    }
+
+    /// 设置连接断开时是否自动重连
+    pub fn set_reconnect(&mut self, reconnect: bool) {
+        self.inner.set_reconnect(reconnect);
+    }
+
+    /// 订阅连接生命周期事件（用 `blocking_recv` 接收，见 [`crate::events`]）
+    pub fn subscribe_events(
+        &self,
+    ) -> tokio::sync::broadcast::Receiver<crate::events::LifecycleEvent> {
+        self.inner.subscribe_events()
+    }
+
+    /// 获取K线数据，直到满足条件（见 [`crate::Client::get_kline_all_util`]）
+    pub fn get_kline_all_util<F>(
+        &self,
+        kline_type: KlineType,
+        code: &str,
+        util_fn: F,
+    ) -> Result<KlineResponse, ClientError>
+    where
+        F: Fn(&Kline) -> bool,
+    {
+        self.rt
+            .block_on(self.inner.get_kline_all_util(kline_type, code, util_fn))
+    }
+
+    blocking_methods! {
+        fn send_frame(&self, frame: RequestFrame) -> ResponseFrame;
+        fn send_frame_with(&self, frame: RequestFrame, options: &RequestOptions) -> ResponseFrame;
+        fn send_heartbeat(&self) -> ();
+        fn send_raw(&self, msg_type: u16, payload: Vec<u8>) -> Vec<u8>;
+
+        fn get_count(&self, exchange: Exchange) -> u16;
+        fn get_code(&self, exchange: Exchange, start: u16) -> CodeResponse;
+        fn get_code_all(&self, exchange: Exchange) -> CodeResponse;
+        fn get_code_all_from(&self, exchange: Exchange, from_start: u16) -> CodeResponse;
+        fn get_market_stocks(&self, exchange: Exchange) -> Vec<StockCode>;
+        fn get_market_etfs(&self, exchange: Exchange) -> Vec<StockCode>;
+        fn get_market_funds(&self, exchange: Exchange) -> Vec<StockCode>;
+        fn get_market_indexes(&self, exchange: Exchange) -> Vec<StockCode>;
+        fn get_sz_stocks(&self) -> Vec<StockCode>;
+        fn get_sh_stocks(&self) -> Vec<StockCode>;
+        fn get_bj_stocks(&self) -> Vec<StockCode>;
+        fn get_all_stocks(&self) -> Vec<StockCode>;
+        fn get_sz_etfs(&self) -> Vec<StockCode>;
+        fn get_sh_etfs(&self) -> Vec<StockCode>;
+        fn get_bj_etfs(&self) -> Vec<StockCode>;
+        fn get_all_etfs(&self) -> Vec<StockCode>;
+        fn get_sz_indexes(&self) -> Vec<StockCode>;
+        fn get_sh_indexes(&self) -> Vec<StockCode>;
+        fn get_bj_indexes(&self) -> Vec<StockCode>;
+        fn get_all_indexes(&self) -> Vec<StockCode>;
+
+        fn get_quote(&self, codes: &[String]) -> Vec<QuoteInfo>;
+        fn get_quote_batch(&self, codes: &[String]) -> Vec<QuoteInfo>;
+        fn get_fund_quote(&self, codes: &[String]) -> Vec<QuoteInfo>;
+        fn get_quote_fresh(&self, codes: &[String]) -> (Vec<QuoteInfo>, DataFreshness);
+        fn snapshot_consistent(&self, codes: &[String], max_skew: Duration) -> Vec<QuoteInfo>;
+        fn get_quote_ordered(&self, codes: &[String]) -> OrderedQuotes;
+
+        fn get_kline(&self, kline_type: KlineType, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_kline_all(&self, kline_type: KlineType, code: &str) -> KlineResponse;
+        fn get_kline_all_from(&self, kline_type: KlineType, code: &str, from_start: u16) -> KlineResponse;
+        fn get_kline_all_during(&self, kline_type: KlineType, code: &str, start_time: u64, end_time: u64) -> KlineResponse;
+        fn get_kline_range(&self, kline_type: KlineType, code: &str, from: NaiveDate, to: NaiveDate) -> KlineResponse;
+        fn get_kline_minute(&self, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_kline_5minute(&self, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_kline_15minute(&self, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_kline_30minute(&self, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_kline_60minute(&self, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_kline_day(&self, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_kline_day_all(&self, code: &str) -> KlineResponse;
+        fn get_kline_day_all_from(&self, code: &str, from_start: u16) -> KlineResponse;
+        fn get_kline_week(&self, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_kline_week_all(&self, code: &str) -> KlineResponse;
+        fn get_kline_week_all_from(&self, code: &str, from_start: u16) -> KlineResponse;
+        fn get_kline_month(&self, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_kline_month_all(&self, code: &str) -> KlineResponse;
+        fn get_kline_month_all_from(&self, code: &str, from_start: u16) -> KlineResponse;
+        fn get_kline_quarter(&self, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_kline_year(&self, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_kline_adjusted(&self, kline_type: KlineType, code: &str, adjustment: Adjustment) -> KlineResponse;
+
+        fn get_index(&self, kline_type: KlineType, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_etf_kline(&self, kline_type: KlineType, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_index_all(&self, kline_type: KlineType, code: &str) -> KlineResponse;
+        fn get_index_all_from(&self, kline_type: KlineType, code: &str, from_start: u16) -> KlineResponse;
+        fn get_index_day(&self, code: &str, start: u16, count: u16) -> KlineResponse;
+        fn get_index_day_all(&self, code: &str) -> KlineResponse;
+        fn get_index_day_all_from(&self, code: &str, from_start: u16) -> KlineResponse;
+
+        fn get_minute(&self, code: &str) -> MinuteResponse;
+        fn get_history_minute(&self, date: &str, code: &str) -> MinuteResponse;
+        fn get_trade(&self, code: &str, start: u16, count: u16) -> TradeResponse;
+        fn get_trade_all(&self, code: &str) -> TradeResponse;
+        fn get_tick_detail(&self, code: &str, start: u16, count: u16) -> TickDetailResponse;
+        fn get_trade_all_from(&self, code: &str, from_start: u16) -> TradeResponse;
+        fn get_history_trade(&self, date: &str, code: &str, start: u16, count: u16) -> TradeResponse;
+        fn get_history_trade_day(&self, date: &str, code: &str) -> TradeResponse;
+        fn get_history_trade_day_from(&self, date: &str, code: &str, from_start: u16) -> TradeResponse;
+        fn get_call_auction(&self, code: &str) -> CallAuctionResponse;
+        fn get_gbbq(&self, code: &str) -> GbbqResponse;
+        fn get_gbbq_cached(&self, code: &str) -> Arc<Vec<Gbbq>>;
+    }
+}
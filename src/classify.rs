@@ -0,0 +1,97 @@
+//! 逐笔成交方向推断
+//!
+//! 服务器给出的成交状态经常为中性，这里结合同期盘口推断每笔成交的主动方向：
+//!
+//! 1. 服务器标记为集合竞价，或在 09:30 之前和 15:00 及之后的成交视为集合竞价；
+//! 2. 有同期快照时，成交价不低于卖一为主动买，不高于买一为主动卖，介于两者之间为盘口内成交；
+//! 3. 否则使用服务器状态（买/卖）；
+//! 4. 再否则使用 tick 规则：比上一笔高为买、低为卖、持平沿用上一笔的方向。
+
+use crate::protocol::{Price, QuoteInfo, Trade, TradeStatus};
+use chrono::{FixedOffset, TimeZone, Timelike};
+use serde::Serialize;
+
+/// 推断的成交方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum InferredSide {
+    /// 主动买入
+    Buy,
+    /// 主动卖出
+    Sell,
+    /// 买一卖一之间成交
+    Cross,
+    /// 集合竞价
+    Auction,
+    /// 无法判断
+    Unknown,
+}
+
+/// 是否为集合竞价时段的成交（北京时间 09:30 之前或 15:00 及之后）
+pub fn is_auction_time(time: i64) -> bool {
+    let beijing_offset = FixedOffset::east_opt(8 * 3600).unwrap();
+    let dt = beijing_offset.timestamp_opt(time, 0).unwrap();
+    let minutes = dt.hour() * 60 + dt.minute();
+    !(9 * 60 + 30..15 * 60).contains(&minutes)
+}
+
+/// 根据盘口判断方向（盘口无效时返回 None）
+fn side_from_quote(price: Price, quote: &QuoteInfo) -> Option<InferredSide> {
+    let bid = quote.buy_level[0].price;
+    let ask = quote.sell_level[0].price;
+    if bid.0 == 0 || ask.0 == 0 || bid >= ask {
+        return None;
+    }
+    Some(if price >= ask {
+        InferredSide::Buy
+    } else if price <= bid {
+        InferredSide::Sell
+    } else {
+        InferredSide::Cross
+    })
+}
+
+/// 推断一组成交的方向
+///
+/// trades 按时间升序；quotes 为按时间升序的 (本地接收时间, 快照)。返回结果与 trades 一一对应。
+///
+/// 分时成交的时间只精确到分钟（HH:MM:00），每笔成交使用其所在分钟结束前收到的最后一次快照，
+/// 而不是 HH:MM:00 之前的快照（可能已经过去将近一分钟）。同一分钟内的快照可能晚于成交，
+/// 盘口在这一分钟内变化较大时推断结果仍可能有偏差。
+pub fn classify(trades: &[Trade], quotes: &[(i64, QuoteInfo)]) -> Vec<InferredSide> {
+    let mut out = Vec::with_capacity(trades.len());
+    let mut q = 0;
+    let mut prev_price: Option<Price> = None;
+    let mut prev_side = InferredSide::Unknown;
+
+    for t in trades {
+        let minute_end = (t.time.div_euclid(60) + 1) * 60;
+        while q < quotes.len() && quotes[q].0 < minute_end {
+            q += 1;
+        }
+        let quote = q.checked_sub(1).map(|i| &quotes[i].1);
+
+        let side = if t.status == TradeStatus::Auction || is_auction_time(t.time) {
+            InferredSide::Auction
+        } else if let Some(side) = quote.and_then(|quote| side_from_quote(t.price, quote)) {
+            side
+        } else if t.status == TradeStatus::Buy {
+            InferredSide::Buy
+        } else if t.status == TradeStatus::Sell {
+            InferredSide::Sell
+        } else {
+            match prev_price {
+                Some(p) if t.price > p => InferredSide::Buy,
+                Some(p) if t.price < p => InferredSide::Sell,
+                Some(_) if matches!(prev_side, InferredSide::Buy | InferredSide::Sell) => prev_side,
+                _ => InferredSide::Unknown,
+            }
+        };
+
+        if side != InferredSide::Auction {
+            prev_price = Some(t.price);
+            prev_side = side;
+        }
+        out.push(side);
+    }
+    out
+}
@@ -1,15 +1,26 @@
 //! TDX 客户端实现（异步）
 
+use crate::adjust::Adjustment;
+use crate::clock::{Clock, SystemClock};
+use crate::events::{emit, LifecycleEvent};
+use crate::freshness::{server_time_skew, DataFreshness};
 use crate::protocol::*;
-use chrono::{FixedOffset, Utc};
-use log::debug;
+use crate::rate_limit::RateLimiter;
+use chrono::{FixedOffset, NaiveDate, TimeZone};
+use log::{debug, info, warn};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
 use std::io;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::task::Poll;
 use std::time::Duration;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
-use tokio::sync::Mutex;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{lookup_host, TcpSocket, TcpStream};
+use tokio::sync::{broadcast, oneshot, Mutex};
+use tokio::task::JoinHandle;
 use tokio::time;
 
 /// 客户端错误
@@ -27,134 +38,837 @@ pub enum ClientError {
     Disconnected,
     #[error("不支持的市场: {0}")]
     UnsupportedMarket(String),
+    #[error("行情快照的服务器时间相差 {0}ms，超过上限")]
+    InconsistentSnapshot(i64),
+    /// 分页偏移（u16）已到上限，仍未翻到请求范围的起点
+    #[error("K线范围超出分页偏移上限，无法取到更早的K线")]
+    KlineRangeTooLong,
+    #[error("服务器错误(0x{code:02X}): {message}")]
+    Server {
+        /// 响应控制码
+        code: u8,
+        /// 服务器返回的错误信息
+        message: String,
+    },
     #[error("其他错误: {0}")]
     Other(String),
 }
 
+/// [`Client::snapshot_consistent`] 获取行情的最多次数
+pub const SNAPSHOT_ATTEMPTS: u32 = 3;
+
+/// 请求选项：超时时间和重试策略
+///
+/// 超时和连接断开的请求会重试，第 n 次重试前等待 `backoff * 2^(n-1)`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestOptions {
+    pub timeout: Duration,
+    pub max_retries: u32,
+    pub backoff: Duration,
+}
+
+impl Default for RequestOptions {
+    /// 超时10秒，不重试
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            max_retries: 0,
+            backoff: Duration::from_millis(200),
+        }
+    }
+}
+
+impl RequestOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// 第 attempt 次重试前的等待时间（attempt 从1开始）
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        self.backoff.saturating_mul(
+            1u32.checked_shl(attempt.saturating_sub(1))
+                .unwrap_or(u32::MAX),
+        )
+    }
+}
+
 /// TDX 客户端（异步）
+///
+/// 请求可以并发发送：写入时短暂加锁，响应由后台读取任务按消息ID分发给各个请求方。
 pub struct Client {
-    stream: Arc<Mutex<TcpStream>>,
+    conn: Mutex<Connection>,
     msg_id: AtomicU32,
+    options: RequestOptions,
+    connect_options: ConnectOptions,
+    gbbq_cache: std::sync::Mutex<HashMap<String, Arc<Vec<Gbbq>>>>,
+    clock: Arc<dyn Clock>,
+    addr: std::sync::Mutex<String>,
+    server_info: std::sync::Mutex<Option<ServerInfo>>,
+    reconnect: bool,
+    rate_limit: Option<RateLimiter>,
+    observer: Option<FrameObserver>,
+    events: broadcast::Sender<LifecycleEvent>,
+}
+
+/// 帧观察回调，见 [`Client::set_frame_observer`]
+pub type FrameObserver = Arc<dyn Fn(&RequestFrame, &ResponseFrame) + Send + Sync>;
+
+type Reply = oneshot::Sender<Result<Incoming, ClientError>>;
+
+/// 读取到的响应
+enum Incoming {
+    Frame(ResponseFrame),
+    /// 本库未定义的消息类型（见 [`Client::send_raw`]），只保留解压后的数据
+    Raw {
+        msg_type: u16,
+        control: u8,
+        data: Vec<u8>,
+    },
+}
+
+impl Incoming {
+    /// 服务器返回错误时，数据域为 GBK 编码的错误信息，不能按正常响应解码
+    fn check(self) -> Result<Self, ClientError> {
+        let (control, data) = match &self {
+            Incoming::Frame(frame) => (frame.control, &frame.data),
+            Incoming::Raw { control, data, .. } => (*control, data),
+        };
+        if ResponseKind::from_control(control) == ResponseKind::Error {
+            return Err(ClientError::Server {
+                code: control,
+                message: gbk_to_utf8(data).trim().to_string(),
+            });
+        }
+        Ok(self)
+    }
+
+    fn into_frame(self) -> Result<ResponseFrame, ClientError> {
+        match self {
+            Incoming::Frame(frame) => Ok(frame),
+            Incoming::Raw { msg_type, .. } => Err(ClientError::Protocol(
+                FrameError::UnknownMessageType(msg_type),
+            )),
+        }
+    }
+
+    fn into_data(self) -> Vec<u8> {
+        match self {
+            Incoming::Frame(frame) => frame.data,
+            Incoming::Raw { data, .. } => data,
+        }
+    }
+}
+
+/// 等待响应的请求
+#[derive(Default)]
+struct Pending {
+    waiters: HashMap<u32, Reply>,
+    /// 读取任务已退出，不再接受新的请求
+    closed: bool,
+}
+
+/// 单个 TCP 连接
+struct Connection {
+    /// 连接序号，每次重连加1，用于判断连接是否已被其他请求替换
+    id: u64,
+    writer: OwnedWriteHalf,
+    pending: Arc<std::sync::Mutex<Pending>>,
+    reader: JoinHandle<()>,
+}
+
+impl Connection {
+    fn start(
+        id: u64,
+        stream: TcpStream,
+        addr: String,
+        events: broadcast::Sender<LifecycleEvent>,
+    ) -> Self {
+        let (reader, writer) = stream.into_split();
+        let pending = Arc::new(std::sync::Mutex::new(Pending::default()));
+        let reader = tokio::spawn(dispatch(reader, pending.clone(), addr, events));
+        Self {
+            id,
+            writer,
+            pending,
+            reader,
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// 读取任务：按消息ID把响应交给对应的请求方，连接出错时通知所有等待中的请求
+async fn dispatch(
+    mut reader: OwnedReadHalf,
+    pending: Arc<std::sync::Mutex<Pending>>,
+    addr: String,
+    events: broadcast::Sender<LifecycleEvent>,
+) {
+    let err = loop {
+        match read_received(&mut reader).await {
+            // 单帧解压失败或长度不符时整帧已经读出，只通知该请求，继续读取后续响应
+            Ok((msg_id, response)) => {
+                let waiter = pending.lock().unwrap().waiters.remove(&msg_id);
+                match waiter {
+                    Some(tx) => {
+                        let _ = tx.send(response);
+                    }
+                    None => debug!("丢弃无人等待的响应: 消息ID={}", msg_id),
+                }
+            }
+            Err(e) => break e,
+        }
+    };
+
+    debug!("读取任务退出: {}", err);
+    let mut pending = pending.lock().unwrap();
+    pending.closed = true;
+    // 先于通知等待中的请求发出，保证关闭事件在随后的重连事件之前
+    emit(
+        &events,
+        LifecycleEvent::Closed {
+            addr,
+            reason: err.to_string(),
+        },
+    );
+    for (_, tx) in pending.waiters.drain() {
+        let _ = tx.send(Err(duplicate_error(&err)));
+    }
+}
+
+/// 复制错误交给每个等待中的请求（IO错误保留类型，便于判断是否需要重连）
+pub(crate) fn duplicate_error(err: &ClientError) -> ClientError {
+    match err {
+        ClientError::Io(e) => ClientError::Io(io::Error::new(e.kind(), e.to_string())),
+        ClientError::Disconnected => ClientError::Disconnected,
+        ClientError::Server { code, message } => ClientError::Server {
+            code: *code,
+            message: message.clone(),
+        },
+        e => ClientError::Other(e.to_string()),
+    }
+}
+
+/// 读取一个响应帧
+async fn read_frame<R: AsyncRead + Unpin>(stream: &mut R) -> Result<ResponseFrame, ClientError> {
+    read_incoming(stream).await?.into_frame()
+}
+
+/// 读取一个响应，未定义的消息类型同样读出数据，交给等待的请求处理
+async fn read_incoming<R: AsyncRead + Unpin>(stream: &mut R) -> Result<Incoming, ClientError> {
+    read_received(stream).await?.1
+}
+
+/// 读取一个响应帧，返回消息ID和该帧的解码结果
+///
+/// 外层错误（IO错误、前缀不符）表示数据流已无法继续读取；
+/// 内层错误（解压失败、长度不符）只影响这一帧，帧已完整读出，可以继续读取下一帧。
+async fn read_received<R: AsyncRead + Unpin>(
+    stream: &mut R,
+) -> Result<(u32, Result<Incoming, ClientError>), ClientError> {
+    let mut header = [0u8; 16];
+    stream.read_exact(&mut header).await?;
+
+    // 前缀是大端序：B1CB7400
+    let prefix = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    if prefix != PREFIX_RESP {
+        return Err(ClientError::Protocol(FrameError::InvalidPrefix));
+    }
+
+    let msg_id = bytes_to_u32_le(&header[5..9]);
+    let msg_type_val = bytes_to_u16_le(&header[10..12]);
+    let zip_length = bytes_to_u16_le(&header[12..14]);
+    let length = bytes_to_u16_le(&header[14..16]);
+
+    let mut compressed_data = vec![0u8; zip_length as usize];
+    stream.read_exact(&mut compressed_data).await?;
+
+    let Some(msg_type) = MessageType::from_u16(msg_type_val) else {
+        debug!(
+            "接收响应: 类型=0x{:04X}, 压缩长度={}, 长度={}",
+            msg_type_val, zip_length, length
+        );
+        let response = inflate(compressed_data, zip_length, length)
+            .map(|data| Incoming::Raw {
+                msg_type: msg_type_val,
+                control: header[4],
+                data,
+            })
+            .map_err(ClientError::from);
+        return Ok((msg_id, response));
+    };
+
+    debug!(
+        "接收响应: 类型={:?}, 压缩长度={}, 长度={}",
+        msg_type, zip_length, length
+    );
+
+    let mut response = ResponseFrame::new(
+        prefix,
+        header[4],
+        msg_id,
+        header[9],
+        msg_type,
+        zip_length,
+        length,
+        compressed_data,
+    );
+
+    let response = response
+        .decompress()
+        .map(|_| Incoming::Frame(response))
+        .map_err(ClientError::from);
+    Ok((msg_id, response))
+}
+
+async fn write_frame<W: AsyncWrite + Unpin>(
+    stream: &mut W,
+    data: &[u8],
+) -> Result<(), ClientError> {
+    debug!("发送请求帧 ({} 字节): {:02X?}", data.len(), data);
+
+    stream.write_all(data).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// 建立连接的选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ConnectOptions {
+    connect_timeout: Option<Duration>,
+    keepalive: bool,
+    nodelay: bool,
+    local_addr: Option<SocketAddr>,
+    handshake: bool,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: None,
+            keepalive: false,
+            nodelay: true,
+            local_addr: None,
+            handshake: true,
+        }
+    }
+}
+
+async fn connect_socket(addr: SocketAddr, options: &ConnectOptions) -> io::Result<TcpStream> {
+    let socket = if addr.is_ipv4() {
+        TcpSocket::new_v4()?
+    } else {
+        TcpSocket::new_v6()?
+    };
+    socket.set_keepalive(options.keepalive)?;
+    socket.set_nodelay(options.nodelay)?;
+    if let Some(local) = options.local_addr {
+        socket.bind(local)?;
+    }
+    socket.connect(addr).await
+}
+
+/// 建立 TCP 连接并完成握手（解析出多个地址时依次尝试）
+///
+/// 返回连接和握手响应中的服务器信息（未握手或无法解析时为 None）。
+async fn open_stream(
+    addr: &str,
+    options: &ConnectOptions,
     timeout: Duration,
+) -> Result<(TcpStream, Option<ServerInfo>), ClientError> {
+    let mut last_err = io::Error::new(io::ErrorKind::NotFound, format!("无法解析地址: {}", addr));
+    let mut connected = None;
+    for resolved in lookup_host(addr).await? {
+        let attempt = connect_socket(resolved, options);
+        let res = match options.connect_timeout {
+            Some(t) => time::timeout(t, attempt)
+                .await
+                .unwrap_or_else(|_| Err(io::ErrorKind::TimedOut.into())),
+            None => attempt.await,
+        };
+        match res {
+            Ok(stream) => {
+                connected = Some(stream);
+                break;
+            }
+            Err(e) => last_err = e,
+        }
+    }
+    let mut stream = connected.ok_or(last_err)?;
+
+    let mut info = None;
+    if options.handshake {
+        let data = Connect::request(1).encode();
+        write_frame(&mut stream, &data).await?;
+        let resp = match time::timeout(timeout, read_frame(&mut stream)).await {
+            Ok(res) => res?,
+            Err(_) => return Err(ClientError::Timeout),
+        };
+        info = Connect::decode_info(&resp.data).ok();
+    }
+    Ok((stream, info))
+}
+
+/// 客户端构建器，见 [`Client::builder`]
+#[derive(Debug, Clone, Default)]
+pub struct ClientBuilder {
+    connect: ConnectOptions,
+    request: RequestOptions,
+    reconnect: Option<bool>,
+    rate_limit: Option<f64>,
+    events: Option<broadcast::Sender<LifecycleEvent>>,
+    clock: Option<Arc<dyn Clock>>,
+}
+
+impl ClientBuilder {
+    /// TCP 连接超时（默认不限制，由系统决定）
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// 读取响应超时（默认10秒）
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.request.timeout = timeout;
+        self
+    }
+
+    /// 是否开启 TCP keepalive（默认关闭）
+    pub fn keepalive(mut self, keepalive: bool) -> Self {
+        self.connect.keepalive = keepalive;
+        self
+    }
+
+    /// 是否开启 TCP_NODELAY（默认开启）
+    pub fn nodelay(mut self, nodelay: bool) -> Self {
+        self.connect.nodelay = nodelay;
+        self
+    }
+
+    /// 绑定本地地址（多网卡或出口受限的环境）
+    pub fn local_addr(mut self, addr: SocketAddr) -> Self {
+        self.connect.local_addr = Some(addr);
+        self
+    }
+
+    /// 连接后是否发送连接请求帧（默认发送；部分代理会自行完成握手）
+    pub fn handshake(mut self, handshake: bool) -> Self {
+        self.connect.handshake = handshake;
+        self
+    }
+
+    /// 默认请求选项
+    pub fn request_options(mut self, options: RequestOptions) -> Self {
+        self.request = options;
+        self
+    }
+
+    /// 连接断开时是否自动重连（默认开启）
+    pub fn reconnect(mut self, reconnect: bool) -> Self {
+        self.reconnect = Some(reconnect);
+        self
+    }
+
+    /// 每秒最多发送的请求数，见 [`Client::set_rate_limit`]
+    pub fn rate_limit(mut self, reqs_per_sec: f64) -> Self {
+        self.rate_limit = Some(reqs_per_sec);
+        self
+    }
+
+    /// 把生命周期事件发送到指定的通道（多个客户端可以共用），见 [`crate::events`]
+    ///
+    /// 未指定时客户端自行创建通道，连接成功后才能通过 [`Client::subscribe_events`] 订阅。
+    pub fn events(mut self, events: broadcast::Sender<LifecycleEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// 使用指定的时钟（默认为系统时钟），见 [`Client::set_clock`]
+    ///
+    /// 客户端放进 `Arc` 共享之后无法再调用 `set_clock`，需要在构建时指定。
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// 连接到指定地址（未指定端口时使用7709）
+    pub async fn connect(self, addr: &str) -> Result<Client, ClientError> {
+        let addr = with_default_port(addr);
+        let (stream, info) = open_stream(&addr, &self.connect, self.request.timeout).await?;
+        if let Some(notice) = info.as_ref().and_then(|i| i.announcement.as_deref()) {
+            info!("服务器公告({}): {}", addr, notice);
+        }
+
+        let events = self.events.unwrap_or_else(crate::events::channel);
+        emit(&events, LifecycleEvent::Connected { addr: addr.clone() });
+        Ok(Client {
+            conn: Mutex::new(Connection::start(0, stream, addr.clone(), events.clone())),
+            msg_id: AtomicU32::new(0),
+            options: self.request,
+            connect_options: self.connect,
+            gbbq_cache: std::sync::Mutex::new(HashMap::new()),
+            clock: self.clock.unwrap_or_else(|| Arc::new(SystemClock)),
+            addr: std::sync::Mutex::new(addr),
+            server_info: std::sync::Mutex::new(info),
+            reconnect: self.reconnect.unwrap_or(true),
+            rate_limit: self.rate_limit.filter(|&r| r > 0.0).map(RateLimiter::new),
+            observer: None,
+            events,
+        })
+    }
+}
+
+/// 并发等待一组 future，结果按输入顺序返回
+async fn join_all<F: Future>(futures: Vec<F>) -> Vec<F::Output> {
+    let mut futures: Vec<_> = futures.into_iter().map(Box::pin).collect();
+    let mut outputs: Vec<Option<F::Output>> = futures.iter().map(|_| None).collect();
+    std::future::poll_fn(|cx| {
+        let mut done = true;
+        for (fut, out) in futures.iter_mut().zip(outputs.iter_mut()) {
+            if out.is_none() {
+                match fut.as_mut().poll(cx) {
+                    Poll::Ready(v) => *out = Some(v),
+                    Poll::Pending => done = false,
+                }
+            }
+        }
+        if done {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await;
+    outputs.into_iter().map(Option::unwrap).collect()
+}
+
+/// 拼接分页结果
+///
+/// 分页请求从最新的数据开始，pages 中越靠后的页越旧；每页内部按时间升序。
+/// 一次分配后按从旧到新的顺序拷贝，避免每页都把已有结果整体后移。
+pub(crate) fn concat_pages<T>(pages: Vec<Vec<T>>) -> Vec<T> {
+    let mut list = Vec::with_capacity(pages.iter().map(Vec::len).sum());
+    for page in pages.into_iter().rev() {
+        list.extend(page);
+    }
+    list
+}
+
+/// 环境变量：服务器地址列表，逗号分隔（为空时使用默认服务器列表）
+pub const ENV_HOSTS: &str = "TDX_HOSTS";
+
+/// 环境变量：请求超时时间（毫秒）
+pub const ENV_TIMEOUT_MS: &str = "TDX_TIMEOUT_MS";
+
+/// 是否为连接断开类错误（对端关闭、连接被重置等），这类错误可以通过重连恢复
+fn is_disconnect(err: &ClientError) -> bool {
+    match err {
+        ClientError::Disconnected => true,
+        ClientError::Io(e) => matches!(
+            e.kind(),
+            io::ErrorKind::UnexpectedEof
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::BrokenPipe
+                | io::ErrorKind::NotConnected
+        ),
+        _ => false,
+    }
+}
+
+/// 是否为可以重试的错误
+pub(crate) fn is_retryable(err: &ClientError) -> bool {
+    matches!(err, ClientError::Timeout) || is_disconnect(err)
+}
+
+static REQUEST_SEQ: AtomicU64 = AtomicU64::new(1);
+
+tokio::task_local! {
+    static REQUEST_ID: u64;
+}
+
+/// 当前逻辑请求的追踪ID（不在 [`traced`] 范围内时为 None）
+///
+/// 追踪ID与协议中的消息ID无关：同一次调用的重试、重连和服务器切换都使用同一个追踪ID，
+/// 日志中以 `#ID` 标出，便于跨连接追踪一次调用。
+pub fn current_request_id() -> Option<u64> {
+    REQUEST_ID.try_with(|id| *id).ok()
+}
+
+/// 在同一个追踪ID下执行 future（已在追踪范围内时沿用外层的ID）
+///
+/// 客户端的每次请求会自动分配追踪ID；需要把多次请求归为一次逻辑调用时用它包裹。
+pub async fn traced<F: Future>(f: F) -> F::Output {
+    if current_request_id().is_some() {
+        f.await
+    } else {
+        let id = REQUEST_SEQ.fetch_add(1, Ordering::Relaxed);
+        REQUEST_ID.scope(id, f).await
+    }
+}
+
+/// 补全默认端口（未指定端口时使用7709）
+pub(crate) fn with_default_port(addr: &str) -> String {
+    if addr.contains(':') {
+        addr.to_string()
+    } else {
+        format!("{}:7709", addr)
+    }
 }
 
 impl Client {
     /// 连接到指定地址
     pub async fn connect(addr: &str) -> Result<Self, ClientError> {
-        let addr = if addr.contains(':') {
-            addr.to_string()
-        } else {
-            format!("{}:7709", addr)
-        };
+        Self::builder().connect(addr).await
+    }
 
-        let stream = TcpStream::connect(&addr).await?;
-        stream.set_nodelay(true)?;
+    /// 创建客户端构建器，用于配置连接选项
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
 
-        let client = Self {
-            stream: Arc::new(Mutex::new(stream)),
-            msg_id: AtomicU32::new(0),
-            timeout: Duration::from_secs(10),
+    /// 根据环境变量创建客户端
+    ///
+    /// - `TDX_HOSTS`: 服务器地址列表，逗号分隔，依次尝试连接（未设置时使用默认服务器列表）
+    /// - `TDX_TIMEOUT_MS`: 请求超时时间（毫秒，未设置时为10秒）
+    pub async fn from_env() -> Result<Self, ClientError> {
+        let timeout = match std::env::var(ENV_TIMEOUT_MS) {
+            Ok(val) => Some(
+                val.trim()
+                    .parse::<u64>()
+                    .map(Duration::from_millis)
+                    .map_err(|_| {
+                        ClientError::Other(format!("无效的 {}: {}", ENV_TIMEOUT_MS, val))
+                    })?,
+            ),
+            Err(_) => None,
         };
 
-        client.send_connect().await?;
+        let hosts = std::env::var(ENV_HOSTS).unwrap_or_default();
+        let hosts: Vec<&str> = hosts
+            .split(',')
+            .map(str::trim)
+            .filter(|h| !h.is_empty())
+            .collect();
+
+        let mut client = crate::dial::dial_hosts_range(&hosts).await?;
+        if let Some(timeout) = timeout {
+            client.set_timeout(timeout);
+        }
         Ok(client)
     }
 
-    /// 发送连接请求并读取响应
-    async fn send_connect(&self) -> Result<(), ClientError> {
-        let frame = Connect::request(1);
-        let data = frame.encode();
-        let mut stream = self.stream.lock().await;
-        self.write_all_locked(&mut stream, &data).await?;
-        let _response = self.read_response_locked(&mut stream).await?;
-        Ok(())
+    /// 重新建立连接：先尝试原地址，失败后依次尝试默认服务器列表
+    ///
+    /// failed: 出错的连接序号，连接已被其他请求替换时直接返回。
+    async fn reconnect(&self, failed: u64) -> Result<(), ClientError> {
+        let mut conn = self.conn.lock().await;
+        if conn.id != failed {
+            return Ok(());
+        }
+
+        let current = self.addr();
+        let candidates = std::iter::once(current.clone()).chain(
+            crate::dial::DEFAULT_HOSTS
+                .iter()
+                .map(|h| with_default_port(h))
+                .filter(|h| *h != current),
+        );
+
+        let mut last_err = ClientError::Disconnected;
+        for addr in candidates {
+            let timeout = self.options.timeout;
+            let attempt = open_stream(&addr, &self.connect_options, timeout);
+            match time::timeout(timeout, attempt).await {
+                Ok(Ok((stream, info))) => {
+                    debug!("已重新连接到 {}", addr);
+                    self.update_server_info(&addr, info);
+                    *conn =
+                        Connection::start(failed + 1, stream, addr.clone(), self.events.clone());
+                    let event = if addr == current {
+                        LifecycleEvent::Reconnected { addr: addr.clone() }
+                    } else {
+                        LifecycleEvent::HostSwitched {
+                            from: current.clone(),
+                            to: addr.clone(),
+                        }
+                    };
+                    *self.addr.lock().unwrap() = addr;
+                    emit(&self.events, event);
+                    return Ok(());
+                }
+                Ok(Err(e)) => last_err = e,
+                Err(_) => last_err = ClientError::Timeout,
+            }
+        }
+        Err(last_err)
     }
 
-    async fn write_all_locked(
+    /// 在当前连接上发送请求并等待响应，同时返回所用连接的序号
+    async fn request(
         &self,
-        stream: &mut TcpStream,
+        msg_id: u32,
         data: &[u8],
-    ) -> Result<(), ClientError> {
-        debug!("发送请求帧 ({} 字节): {:02X?}", data.len(), data);
+        timeout: Duration,
+    ) -> (u64, Result<Incoming, ClientError>) {
+        let (tx, rx) = oneshot::channel();
+        let (conn_id, pending) = {
+            let mut conn = self.conn.lock().await;
+            {
+                let mut pending = conn.pending.lock().unwrap();
+                if pending.closed {
+                    return (conn.id, Err(ClientError::Disconnected));
+                }
+                pending.waiters.insert(msg_id, tx);
+            }
+            if let Err(e) = write_frame(&mut conn.writer, data).await {
+                conn.pending.lock().unwrap().waiters.remove(&msg_id);
+                return (conn.id, Err(e));
+            }
+            (conn.id, conn.pending.clone())
+        };
 
-        stream.write_all(data).await?;
-        stream.flush().await?;
-        Ok(())
+        let res = match time::timeout(timeout, rx).await {
+            Ok(Ok(res)) => res,
+            // 连接已被替换，等待中的请求随之丢弃
+            Ok(Err(_)) => Err(ClientError::Disconnected),
+            Err(_) => {
+                pending.lock().unwrap().waiters.remove(&msg_id);
+                Err(ClientError::Timeout)
+            }
+        };
+        (conn_id, res)
     }
 
-    async fn read_response_locked(
+    /// 发送帧并等待响应（使用客户端的默认请求选项）
+    ///
+    /// 可以在多个任务中并发调用，响应按消息ID匹配。
+    /// 连接断开（EOF、连接被重置等）时自动重连并重发一次请求，见 [`Client::set_reconnect`]。
+    pub async fn send_frame(&self, frame: RequestFrame) -> Result<ResponseFrame, ClientError> {
+        self.send_frame_with(frame, &self.options).await
+    }
+
+    /// 按指定的请求选项发送帧并等待响应
+    pub async fn send_frame_with(
         &self,
-        stream: &mut TcpStream,
+        frame: RequestFrame,
+        options: &RequestOptions,
     ) -> Result<ResponseFrame, ClientError> {
-        let timeout = self.timeout;
-        let fut = async {
-            let mut header = [0u8; 16];
-            stream.read_exact(&mut header).await?;
-
-            // 前缀是大端序：B1CB7400
-            let prefix = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
-            if prefix != PREFIX_RESP {
-                return Err(ClientError::Protocol(FrameError::InvalidPrefix));
+        traced(async move {
+            let req = current_request_id().unwrap_or_default();
+            let mut frame = frame;
+            let mut attempt = 0;
+            loop {
+                // 每次尝试使用新的消息ID，避免迟到的响应被当作重试的结果
+                frame.msg_id = self.next_msg_id();
+                debug!(
+                    "#{} 发送请求: 类型={:?}, 消息ID={}",
+                    req, frame.msg_type, frame.msg_id
+                );
+                match self.send_once(&frame, options.timeout).await {
+                    Err(e) if attempt < options.max_retries && is_retryable(&e) => {
+                        attempt += 1;
+                        let delay = options.backoff_for(attempt);
+                        warn!(
+                            "#{} 请求失败: {}，{:?} 后第{}次重试",
+                            req, e, delay, attempt
+                        );
+                        time::sleep(delay).await;
+                    }
+                    res => return res,
+                }
             }
+        })
+        .await
+    }
 
-            let msg_type_val = bytes_to_u16_le(&header[10..12]);
-            let zip_length = bytes_to_u16_le(&header[12..14]);
-            let length = bytes_to_u16_le(&header[14..16]);
-
-            let msg_type = MessageType::from_u16(msg_type_val).ok_or_else(|| {
-                ClientError::Protocol(FrameError::UnknownMessageType(msg_type_val))
-            })?;
+    /// 按限速等待发送下一个请求
+    async fn acquire(&self) {
+        if let Some(limiter) = &self.rate_limit {
+            limiter.acquire().await;
+        }
+    }
 
-            let mut compressed_data = vec![0u8; zip_length as usize];
-            stream.read_exact(&mut compressed_data).await?;
+    /// 按速率限制发送请求并等待响应，连接断开时重连并重发一次
+    ///
+    /// 服务器返回错误时得到 [`ClientError::Server`]，连接仍可继续使用。
+    async fn exchange(
+        &self,
+        msg_id: u32,
+        data: &[u8],
+        timeout: Duration,
+    ) -> Result<Incoming, ClientError> {
+        self.acquire().await;
+        match self.request(msg_id, data, timeout).await {
+            (conn_id, Err(e)) if self.reconnect && is_disconnect(&e) => {
+                warn!(
+                    "#{} 连接 {} 已断开: {}，正在重连",
+                    current_request_id().unwrap_or_default(),
+                    self.addr(),
+                    e
+                );
+                self.reconnect(conn_id).await?;
+                // 重发同样计入限速
+                self.acquire().await;
+                self.request(msg_id, data, timeout).await.1?.check()
+            }
+            (_, res) => res?.check(),
+        }
+    }
 
+    /// 发送任意类型值的请求，返回解压后的响应数据（不解码）
+    ///
+    /// 用于试验本库尚未支持的消息类型：payload 为类型字段之后的数据域。
+    /// 使用默认的超时，连接断开时同样会重连，但失败后不重试；不经过帧观察者。
+    pub async fn send_raw(&self, msg_type: u16, payload: Vec<u8>) -> Result<Vec<u8>, ClientError> {
+        traced(async move {
+            let msg_id = self.next_msg_id();
             debug!(
-                "接收响应: 类型={:?}, 压缩长度={}, 长度={}",
-                msg_type, zip_length, length
-            );
-
-            let mut response = ResponseFrame::new(
-                prefix,
-                header[4],
-                bytes_to_u32_le(&header[5..9]),
-                header[9],
+                "#{} 发送原始请求: 类型=0x{:04X}, 消息ID={}",
+                current_request_id().unwrap_or_default(),
                 msg_type,
-                zip_length,
-                length,
-                compressed_data,
+                msg_id
             );
-
-            response.decompress()?;
-            Ok(response)
-        };
-
-        match time::timeout(timeout, fut).await {
-            Ok(res) => res,
-            Err(_) => Err(ClientError::Timeout),
-        }
+            let data = RequestFrame::encode_raw(msg_id, msg_type, &payload);
+            let resp = self.exchange(msg_id, &data, self.options.timeout).await?;
+            Ok(resp.into_data())
+        })
+        .await
     }
 
-    /// 发送帧并等待响应
-    pub async fn send_frame(&self, frame: RequestFrame) -> Result<ResponseFrame, ClientError> {
-        let msg_id = self.next_msg_id();
-
-        let mut frame = frame;
-        frame.msg_id = msg_id;
-
+    async fn send_once(
+        &self,
+        frame: &RequestFrame,
+        timeout: Duration,
+    ) -> Result<ResponseFrame, ClientError> {
         let data = frame.encode();
-        let mut stream = self.stream.lock().await;
-
-        self.write_all_locked(&mut stream, &data).await?;
-        let response = self.read_response_locked(&mut stream).await?;
-
-        if response.msg_id != msg_id {
-            return Err(ClientError::Other(format!(
-                "消息ID不匹配: 期望 {}, 得到 {}",
-                msg_id, response.msg_id
-            )));
+        let res = self
+            .exchange(frame.msg_id, &data, timeout)
+            .await
+            .and_then(Incoming::into_frame);
+        if let (Some(observer), Ok(resp)) = (&self.observer, &res) {
+            observer(frame, resp);
         }
-
-        Ok(response)
+        res
     }
 
     /// 获取股票数量
@@ -236,6 +950,14 @@ impl Client {
         self.filter_market_codes(exchange, is_etf).await
     }
 
+    /// 获取指定市场的场内基金代码（ETF、LOF、封闭式基金等）
+    pub async fn get_market_funds(
+        &self,
+        exchange: Exchange,
+    ) -> Result<Vec<StockCode>, ClientError> {
+        self.filter_market_codes(exchange, is_fund).await
+    }
+
     /// 获取指定市场的指数代码
     pub async fn get_market_indexes(
         &self,
@@ -371,17 +1093,112 @@ impl Client {
     }
 
     /// 获取行情信息（五档报价）
+    ///
+    /// 超过 [`QUOTE_MAX_CODES`] 个代码时自动分批请求，见 [`Client::get_quote_batch`]。
     pub async fn get_quote(&self, codes: &[String]) -> Result<Vec<QuoteInfo>, ClientError> {
+        if codes.len() > QUOTE_MAX_CODES {
+            return self.get_quote_batch(codes).await;
+        }
+        self.get_quote_once(codes).await
+    }
+
+    /// 获取基金行情信息，价格按基金多一位小数的精度换算
+    ///
+    /// 换算规则尚未用真实的基金行情响应验证，[`Client::get_quote`] 不会自动对基金代码使用，
+    /// 需要时显式调用这个方法，见 [`QuoteInfo::with_fund_precision`]。
+    pub async fn get_fund_quote(&self, codes: &[String]) -> Result<Vec<QuoteInfo>, ClientError> {
+        let quotes = self.get_quote(codes).await?;
+        Ok(quotes
+            .into_iter()
+            .map(QuoteInfo::with_fund_precision)
+            .collect())
+    }
+
+    /// 单个请求获取行情信息
+    async fn get_quote_once(&self, codes: &[String]) -> Result<Vec<QuoteInfo>, ClientError> {
         let frame = Quote::request(self.next_msg_id(), codes)?;
         let response = self.send_frame(frame).await?;
         let quotes = Quote::decode_response(response.data())?;
         Ok(quotes)
     }
 
-    /// 发送心跳
+    /// 获取行情信息，同时给出数据的新鲜度（服务器时间取最新的一条行情）
+    pub async fn get_quote_fresh(
+        &self,
+        codes: &[String],
+    ) -> Result<(Vec<QuoteInfo>, DataFreshness), ClientError> {
+        let quotes = self.get_quote(codes).await?;
+        let freshness = DataFreshness::of_quotes(&quotes, self.clock.now());
+        Ok((quotes, freshness))
+    }
+
+    /// 获取服务器时间相差不超过 max_skew 的一组行情
+    ///
+    /// 代码较多时分批请求（见 [`Client::get_quote`]），各批的服务器时间可能相差较大。
+    /// 偏差超过 max_skew 时重新获取，最多 [`SNAPSHOT_ATTEMPTS`] 次，仍然超过时返回
+    /// [`ClientError::InconsistentSnapshot`]。服务器时间为各股票最后一次更新的时间，
+    /// 长时间无成交的股票会使偏差一直偏大，这类代码不宜放在同一个快照中。
+    pub async fn snapshot_consistent(
+        &self,
+        codes: &[String],
+        max_skew: Duration,
+    ) -> Result<Vec<QuoteInfo>, ClientError> {
+        let max_skew = chrono::Duration::from_std(max_skew).unwrap_or(chrono::Duration::MAX);
+        let mut skew = chrono::Duration::zero();
+        for attempt in 1..=SNAPSHOT_ATTEMPTS {
+            let quotes = self.get_quote(codes).await?;
+            skew = server_time_skew(&quotes).unwrap_or_else(chrono::Duration::zero);
+            if skew <= max_skew {
+                return Ok(quotes);
+            }
+            debug!(
+                "行情快照服务器时间相差 {}ms，第{}次获取",
+                skew.num_milliseconds(),
+                attempt
+            );
+        }
+        Err(ClientError::InconsistentSnapshot(skew.num_milliseconds()))
+    }
+
+    /// 批量获取行情信息，代码数量不受单次请求上限限制
+    ///
+    /// 按 [`QUOTE_MAX_CODES`] 分批并发请求，结果按输入顺序排列（服务器未返回的代码被跳过）。
+    pub async fn get_quote_batch(&self, codes: &[String]) -> Result<Vec<QuoteInfo>, ClientError> {
+        let chunks: Vec<&[String]> = codes.chunks(QUOTE_MAX_CODES).collect();
+        let requests = chunks
+            .iter()
+            .map(|chunk| self.get_quote_once(chunk))
+            .collect();
+        let results = traced(join_all(requests)).await;
+
+        let mut quotes = Vec::with_capacity(codes.len());
+        for (chunk, res) in chunks.into_iter().zip(results) {
+            quotes.extend(Quote::align(chunk, res?).list.into_iter().flatten());
+        }
+        Ok(quotes)
+    }
+
+    /// 获取行情信息，结果按请求顺序排列
+    ///
+    /// 服务器未返回的代码对应位置为 None，并记录在 `missing` 中。
+    pub async fn get_quote_ordered(&self, codes: &[String]) -> Result<OrderedQuotes, ClientError> {
+        let quotes = self.get_quote(codes).await?;
+        Ok(Quote::align(codes, quotes))
+    }
+
+    /// 发送心跳，失败时发出 [`LifecycleEvent::HeartbeatMissed`]
     pub async fn send_heartbeat(&self) -> Result<(), ClientError> {
         let frame = Heartbeat::request(self.next_msg_id());
-        let _response = self.send_frame(frame).await?;
+        if let Err(e) = self.send_frame(frame).await {
+            emit(
+                &self.events,
+                LifecycleEvent::HeartbeatMissed {
+                    addr: self.addr(),
+                    error: e.to_string(),
+                },
+            );
+            return Err(e);
+        }
         Ok(())
     }
 
@@ -398,9 +1215,15 @@ impl Client {
         let code = add_prefix(code);
         let frame = KlineMsg::request(self.next_msg_id(), kline_type, &code, start, count)?;
         let response = self.send_frame(frame).await?;
+        // 基金的价格精度规则尚未用真实响应验证，这里不按代码切换，需要时用 get_etf_kline
+        let kind = if is_index(&code) {
+            InstrumentKind::Index
+        } else {
+            InstrumentKind::Stock
+        };
         let cache = KlineCache {
             kline_type: kline_type as u8,
-            is_index: is_index(&code),
+            kind,
         };
         let klines = KlineMsg::decode_response(response.data(), cache)?;
         Ok(klines)
@@ -422,20 +1245,15 @@ impl Client {
         code: &str,
         from_start: u16,
     ) -> Result<KlineResponse, ClientError> {
-        let mut all_klines = KlineResponse {
-            count: 0,
-            list: Vec::new(),
-        };
+        let mut pages = Vec::new();
+        let mut count = 0;
         let batch_size = 800u16;
         let mut start = from_start;
 
         loop {
             let resp = self.get_kline(kline_type, code, start, batch_size).await?;
-            all_klines.count += resp.count;
-            // 新数据在前，旧数据在后
-            let mut new_list = resp.list;
-            new_list.append(&mut all_klines.list);
-            all_klines.list = new_list;
+            count += resp.count;
+            pages.push(resp.list);
 
             if resp.count < batch_size {
                 break;
@@ -443,7 +1261,10 @@ impl Client {
             start += batch_size;
         }
 
-        Ok(all_klines)
+        Ok(KlineResponse {
+            count,
+            list: concat_pages(pages),
+        })
     }
 
     /// 获取所有K线数据（支持自定义过滤）
@@ -458,10 +1279,8 @@ impl Client {
     where
         F: Fn(&Kline) -> bool,
     {
-        let mut all_klines = KlineResponse {
-            count: 0,
-            list: Vec::new(),
-        };
+        let mut pages = Vec::new();
+        let mut count = 0;
         let batch_size = 800u16;
         let mut start = 0;
 
@@ -483,18 +1302,14 @@ impl Client {
             }
 
             if fully_match {
-                // 全部满足，将整个列表加到结果的前面
-                let mut new_list = resp.list;
-                new_list.append(&mut all_klines.list);
-                all_klines.list = new_list;
-                all_klines.count += len as u16;
+                // 全部满足，保留整页
+                count += len as u16;
+                pages.push(resp.list);
             } else {
-                // 部分满足，截取满足的部分
-                // split_offAt cut_index, valid parts are [cut_index..len]
-                let mut valid_part = resp.list.split_off(cut_index);
-                all_klines.count += valid_part.len() as u16;
-                valid_part.append(&mut all_klines.list);
-                all_klines.list = valid_part;
+                // 部分满足，截取满足的部分 [cut_index..len]
+                let valid_part = resp.list.split_off(cut_index);
+                count += valid_part.len() as u16;
+                pages.push(valid_part);
 
                 // 既然已经遇到不满足的了，更旧的数据肯定也不满足，退出循环
                 break 'outer;
@@ -506,7 +1321,10 @@ impl Client {
             start += batch_size;
         }
 
-        Ok(all_klines)
+        Ok(KlineResponse {
+            count,
+            list: concat_pages(pages),
+        })
     }
 
     /// 获取所有K线数据（支持时间范围）
@@ -530,6 +1348,70 @@ impl Client {
         Ok(resp)
     }
 
+    /// 获取日期范围内的K线（北京时间，包含 from 和 to 两天）
+    ///
+    /// 协议只支持按偏移分页，这里从最新一页开始向前翻页，直到越过 from。
+    /// 下载过程中产生新K线时后面的页整体后移，重叠的K线按时间去重，不会重复或遗漏。
+    /// 分页偏移到达上限仍未越过 from 时返回 [`ClientError::KlineRangeTooLong`]，不返回部分结果。
+    /// 结果超过 65535 根时 `count` 为 65535，以 `list.len()` 为准。
+    pub async fn get_kline_range(
+        &self,
+        kline_type: KlineType,
+        code: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<KlineResponse, ClientError> {
+        let beijing_offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        let day_start = |date: NaiveDate| {
+            beijing_offset
+                .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+                .unwrap()
+                .timestamp()
+        };
+        let list = self
+            .kline_pages_between(kline_type, code, day_start(from), day_start(to) + 24 * 3600)
+            .await?;
+        Ok(KlineResponse {
+            count: u16::try_from(list.len()).unwrap_or(u16::MAX),
+            list,
+        })
+    }
+
+    /// 从最新一页开始向前翻页，返回时间在 [start_time, end_time) 内的K线（按时间升序）
+    ///
+    /// 某页最旧的K线早于 start_time 或不足一页时停止。重叠的K线按时间去重。
+    /// 分页偏移超出 u16 时返回 [`ClientError::KlineRangeTooLong`]。
+    pub(crate) async fn kline_pages_between(
+        &self,
+        kline_type: KlineType,
+        code: &str,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<Vec<Kline>, ClientError> {
+        let mut bars = BTreeMap::new();
+        let batch_size = 800u16;
+        let mut start = 0u16;
+        loop {
+            let resp = self.get_kline(kline_type, code, start, batch_size).await?;
+            // 每页内按时间从旧到新排列
+            let oldest = resp.list.first().map(|k| k.time);
+            for k in resp.list {
+                if k.time >= start_time && k.time < end_time {
+                    bars.entry(k.time).or_insert(k);
+                }
+            }
+
+            match oldest {
+                Some(time) if time >= start_time && resp.count == batch_size => {}
+                _ => break,
+            }
+            start = start
+                .checked_add(batch_size)
+                .ok_or(ClientError::KlineRangeTooLong)?;
+        }
+        Ok(bars.into_values().collect())
+    }
+
     /// 获取1分钟K线数据
     pub async fn get_kline_minute(
         &self,
@@ -693,7 +1575,29 @@ impl Client {
         let response = self.send_frame(frame).await?;
         let cache = KlineCache {
             kline_type: kline_type as u8,
-            is_index: true,
+            kind: InstrumentKind::Index,
+        };
+        let klines = KlineMsg::decode_response(response.data(), cache)?;
+        Ok(klines)
+    }
+
+    /// 获取基金K线数据（单次最多800条）
+    ///
+    /// 价格按基金多一位小数的精度解码（除以10），成交量和成交额不换算。该规则尚未用真实的基金K线响应验证，
+    /// 因此 [`Client::get_kline`] 不会自动对基金代码使用它，需要时显式调用这个方法。
+    pub async fn get_etf_kline(
+        &self,
+        kline_type: KlineType,
+        code: &str,
+        start: u16,
+        count: u16,
+    ) -> Result<KlineResponse, ClientError> {
+        let code = add_prefix(code);
+        let frame = KlineMsg::request(self.next_msg_id(), kline_type, &code, start, count)?;
+        let response = self.send_frame(frame).await?;
+        let cache = KlineCache {
+            kline_type: kline_type as u8,
+            kind: InstrumentKind::Fund,
         };
         let klines = KlineMsg::decode_response(response.data(), cache)?;
         Ok(klines)
@@ -715,19 +1619,15 @@ impl Client {
         code: &str,
         from_start: u16,
     ) -> Result<KlineResponse, ClientError> {
-        let mut all_klines = KlineResponse {
-            count: 0,
-            list: Vec::new(),
-        };
+        let mut pages = Vec::new();
+        let mut count = 0;
         let batch_size = 800u16;
         let mut start = from_start;
 
         loop {
             let resp = self.get_index(kline_type, code, start, batch_size).await?;
-            all_klines.count += resp.count;
-            let mut new_list = resp.list;
-            new_list.append(&mut all_klines.list);
-            all_klines.list = new_list;
+            count += resp.count;
+            pages.push(resp.list);
 
             if resp.count < batch_size {
                 break;
@@ -735,7 +1635,10 @@ impl Client {
             start += batch_size;
         }
 
-        Ok(all_klines)
+        Ok(KlineResponse {
+            count,
+            list: concat_pages(pages),
+        })
     }
 
     /// 获取指数日K线数据
@@ -767,19 +1670,10 @@ impl Client {
 
     /// 获取分时数据（使用历史分时接口，与 Go 版本一致）
     pub async fn get_minute(&self, code: &str) -> Result<MinuteResponse, ClientError> {
-        let today = Self::today_str();
+        let today = self.clock.today();
         self.get_history_minute(&today, code).await
     }
 
-    /// 获取当前日期字符串（YYYYMMDD格式，北京时间）
-    fn today_str() -> String {
-        let beijing_offset = FixedOffset::east_opt(8 * 3600).unwrap();
-        Utc::now()
-            .with_timezone(&beijing_offset)
-            .format("%Y%m%d")
-            .to_string()
-    }
-
     /// 获取历史分时数据
     /// date格式：YYYYMMDD
     pub async fn get_history_minute(
@@ -807,19 +1701,33 @@ impl Client {
         let frame = TradeMsg::request(self.next_msg_id(), &code, start, count)?;
         let response = self.send_frame(frame).await?;
 
-        // 获取当天日期
-        let beijing_offset = FixedOffset::east_opt(8 * 3600).unwrap();
-        let now = Utc::now().with_timezone(&beijing_offset);
-        let date = now.format("%Y%m%d").to_string();
-
         let cache = TradeCache {
-            date,
+            date: self.clock.today(),
             code: code.clone(),
         };
         let trades = TradeMsg::decode_response(response.data(), &cache)?;
         Ok(trades)
     }
 
+    /// 获取逐笔成交明细（单次最多1800条），包含单数、买卖方向的原始状态值和未知字段
+    pub async fn get_tick_detail(
+        &self,
+        code: &str,
+        start: u16,
+        count: u16,
+    ) -> Result<TickDetailResponse, ClientError> {
+        let code = add_prefix(code);
+        let frame = TickDetailMsg::request(self.next_msg_id(), &code, start, count)?;
+        let response = self.send_frame(frame).await?;
+
+        let cache = TradeCache {
+            date: self.clock.today(),
+            code: code.clone(),
+        };
+        let ticks = TickDetailMsg::decode_response(response.data(), &cache)?;
+        Ok(ticks)
+    }
+
     /// 获取所有分时交易详情（从0开始）
     pub async fn get_trade_all(&self, code: &str) -> Result<TradeResponse, ClientError> {
         self.get_trade_all_from(code, 0).await
@@ -831,20 +1739,15 @@ impl Client {
         code: &str,
         from_start: u16,
     ) -> Result<TradeResponse, ClientError> {
-        let mut all_trades = TradeResponse {
-            count: 0,
-            list: Vec::new(),
-        };
+        let mut pages = Vec::new();
+        let mut count = 0;
         let batch_size = 1800u16;
         let mut start = from_start;
 
         loop {
             let resp = self.get_trade(code, start, batch_size).await?;
-            all_trades.count += resp.count;
-            // 新数据在前
-            let mut new_list = resp.list;
-            new_list.append(&mut all_trades.list);
-            all_trades.list = new_list;
+            count += resp.count;
+            pages.push(resp.list);
 
             if resp.count < batch_size {
                 break;
@@ -852,7 +1755,10 @@ impl Client {
             start += batch_size;
         }
 
-        Ok(all_trades)
+        Ok(TradeResponse {
+            count,
+            list: concat_pages(pages),
+        })
     }
 
     /// 获取历史分时交易（单次最多2000条）
@@ -891,10 +1797,8 @@ impl Client {
         code: &str,
         from_start: u16,
     ) -> Result<TradeResponse, ClientError> {
-        let mut all_trades = TradeResponse {
-            count: 0,
-            list: Vec::new(),
-        };
+        let mut pages = Vec::new();
+        let mut count = 0;
         let batch_size = 2000u16;
         let mut start = from_start;
 
@@ -902,10 +1806,8 @@ impl Client {
             let resp = self
                 .get_history_trade(date, code, start, batch_size)
                 .await?;
-            all_trades.count += resp.count;
-            let mut new_list = resp.list;
-            new_list.append(&mut all_trades.list);
-            all_trades.list = new_list;
+            count += resp.count;
+            pages.push(resp.list);
 
             if resp.count < batch_size {
                 break;
@@ -913,7 +1815,10 @@ impl Client {
             start += batch_size;
         }
 
-        Ok(all_trades)
+        Ok(TradeResponse {
+            count,
+            list: concat_pages(pages),
+        })
     }
 
     // ==================== 集合竞价 ====================
@@ -923,7 +1828,7 @@ impl Client {
         let code = add_prefix(code);
         let frame = CallAuctionMsg::request(self.next_msg_id(), &code)?;
         let response = self.send_frame(frame).await?;
-        let auction = CallAuctionMsg::decode_response(response.data())?;
+        let auction = CallAuctionMsg::decode_response_on(response.data(), &self.clock.today())?;
         Ok(auction)
     }
 
@@ -938,6 +1843,34 @@ impl Client {
         Ok(gbbq)
     }
 
+    /// 获取股本变迁数据（按代码缓存，同一客户端只请求一次）
+    pub async fn get_gbbq_cached(&self, code: &str) -> Result<Arc<Vec<Gbbq>>, ClientError> {
+        let code = add_prefix(code);
+        if let Some(list) = self.gbbq_cache.lock().unwrap().get(&code) {
+            return Ok(list.clone());
+        }
+        let list = Arc::new(self.get_gbbq(&code).await?.list);
+        self.gbbq_cache.lock().unwrap().insert(code, list.clone());
+        Ok(list)
+    }
+
+    /// 获取复权K线（全部历史数据，按时间升序）
+    ///
+    /// 自动获取（并缓存）除权除息数据，按复权方式调整价格。
+    pub async fn get_kline_adjusted(
+        &self,
+        kline_type: KlineType,
+        code: &str,
+        adjustment: Adjustment,
+    ) -> Result<KlineResponse, ClientError> {
+        let mut resp = self.get_kline_all(kline_type, code).await?;
+        if adjustment != Adjustment::None {
+            let gbbq = self.get_gbbq_cached(code).await?;
+            crate::adjust::adjust(&mut resp.list, &gbbq, adjustment);
+        }
+        Ok(resp)
+    }
+
     /// 获取下一个消息ID
     fn next_msg_id(&self) -> u32 {
         self.msg_id.fetch_add(1, Ordering::SeqCst) + 1
@@ -945,10 +1878,101 @@ impl Client {
 
     /// 设置超时时间
     pub fn set_timeout(&mut self, timeout: Duration) {
-        self.timeout = timeout;
+        self.options.timeout = timeout;
+    }
+
+    /// 限制每秒发送的请求数（包括重试），0 表示不限制（默认）
+    pub fn set_rate_limit(&mut self, reqs_per_sec: f64) {
+        self.rate_limit = (reqs_per_sec > 0.0).then(|| RateLimiter::new(reqs_per_sec));
+    }
+
+    /// 设置帧观察回调，每收到一个响应时以请求帧和（解压后的）响应帧调用
+    ///
+    /// 用于记录或检查收发的原始数据；回调在请求所在的任务中同步执行，不应阻塞。
+    pub fn set_frame_observer(
+        &mut self,
+        observer: impl Fn(&RequestFrame, &ResponseFrame) + Send + Sync + 'static,
+    ) {
+        self.observer = Some(Arc::new(observer));
+    }
+
+    /// 移除帧观察回调
+    pub fn clear_frame_observer(&mut self) {
+        self.observer = None;
+    }
+
+    /// 设置默认请求选项（超时和重试策略）
+    pub fn set_request_options(&mut self, options: RequestOptions) {
+        self.options = options;
+    }
+
+    /// 以指定的默认请求选项返回客户端
+    pub fn with_request_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 默认请求选项
+    pub fn request_options(&self) -> &RequestOptions {
+        &self.options
+    }
+
+    /// 设置连接断开时是否自动重连（默认开启）
+    pub fn set_reconnect(&mut self, reconnect: bool) {
+        self.reconnect = reconnect;
+    }
+
+    /// 当前连接的服务器地址（自动重连到其他服务器后会改变）
+    pub fn addr(&self) -> String {
+        self.addr.lock().unwrap().clone()
+    }
+
+    /// 当前连接的服务器信息：名称、公告、交易时段和服务器时间（未握手时为 None，重连后更新）
+    pub fn connect_info(&self) -> Option<ServerInfo> {
+        self.server_info.lock().unwrap().clone()
+    }
+
+    /// 重连后更新服务器信息，公告变化时记录日志
+    fn update_server_info(&self, addr: &str, info: Option<ServerInfo>) {
+        let mut current = self.server_info.lock().unwrap();
+        let old = current.as_ref().and_then(|i| i.announcement.as_deref());
+        let new = info.as_ref().and_then(|i| i.announcement.as_deref());
+        if old != new {
+            match new {
+                Some(notice) => warn!("服务器公告已变更({}): {}", addr, notice),
+                None => info!("服务器公告已撤销({})", addr),
+            }
+        }
+        *current = info;
+    }
+
+    /// 设置时钟（默认为系统时钟）
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// 当前使用的时钟
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// 订阅连接生命周期事件，见 [`crate::events`]
+    pub fn subscribe_events(&self) -> broadcast::Receiver<LifecycleEvent> {
+        self.events.subscribe()
     }
 }
 
 impl Drop for Client {
-    fn drop(&mut self) {}
+    fn drop(&mut self) {
+        // 读取任务已因连接出错退出时已经发出过关闭事件
+        if !self.conn.get_mut().pending.lock().unwrap().closed {
+            emit(
+                &self.events,
+                LifecycleEvent::Closed {
+                    addr: self.addr(),
+                    reason: "客户端已释放".to_string(),
+                },
+            );
+        }
+    }
 }
@@ -0,0 +1,65 @@
+//! 时钟抽象
+//!
+//! 客户端中依赖当前时间的逻辑（当天日期等）都通过 [`Clock`] 获取时间，
+//! 测试或回放时可以替换为 [`FixedClock`]。
+
+use chrono::{DateTime, FixedOffset, Utc};
+use std::fmt;
+use std::sync::Mutex;
+
+/// 时钟
+pub trait Clock: Send + Sync + fmt::Debug {
+    /// 当前时间
+    fn now(&self) -> DateTime<Utc>;
+
+    /// 当前北京时间
+    fn now_beijing(&self) -> DateTime<FixedOffset> {
+        let beijing_offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        self.now().with_timezone(&beijing_offset)
+    }
+
+    /// 当前日期字符串（YYYYMMDD格式，北京时间）
+    fn today(&self) -> String {
+        self.now_beijing().format("%Y%m%d").to_string()
+    }
+}
+
+/// 系统时钟
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 固定时钟（可手动设置/推进，用于测试）
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl FixedClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// 设置当前时间
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    /// 推进时间
+    pub fn advance(&self, duration: chrono::Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
@@ -0,0 +1,147 @@
+//! 代码表增量更新
+//!
+//! 定期获取代码数量，数量变化时才重新下载代码表，
+//! 与上一次的代码表比较后发出新增/删除/改名事件。
+
+use crate::client::Client;
+use crate::protocol::{Exchange, StockCode};
+use log::warn;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time;
+
+/// 代码表变化事件
+#[derive(Debug, Clone)]
+pub enum CodeTableEvent {
+    /// 新增代码
+    Added(Exchange, StockCode),
+    /// 删除代码
+    Removed(Exchange, StockCode),
+    /// 名称变化
+    Renamed {
+        exchange: Exchange,
+        code: String,
+        old_name: String,
+        new_name: String,
+    },
+}
+
+/// 单个市场的代码表
+#[derive(Debug, Clone)]
+pub struct CodeTable {
+    pub exchange: Exchange,
+    codes: HashMap<String, StockCode>,
+}
+
+impl CodeTable {
+    pub fn new(exchange: Exchange, codes: Vec<StockCode>) -> Self {
+        Self {
+            exchange,
+            codes: codes.into_iter().map(|c| (c.code.clone(), c)).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    pub fn get(&self, code: &str) -> Option<&StockCode> {
+        self.codes.get(code)
+    }
+
+    /// 用新的代码列表替换当前代码表，返回变化事件（按代码排序）
+    pub fn update(&mut self, codes: Vec<StockCode>) -> Vec<CodeTableEvent> {
+        let exchange = self.exchange;
+        let mut new = Self::new(exchange, codes).codes;
+        let mut events = Vec::new();
+
+        for (code, old) in &self.codes {
+            match new.get(code) {
+                None => events.push((code.clone(), CodeTableEvent::Removed(exchange, old.clone()))),
+                Some(c) if c.name != old.name => events.push((
+                    code.clone(),
+                    CodeTableEvent::Renamed {
+                        exchange,
+                        code: code.clone(),
+                        old_name: old.name.clone(),
+                        new_name: c.name.clone(),
+                    },
+                )),
+                Some(_) => {}
+            }
+        }
+        for (code, c) in &new {
+            if !self.codes.contains_key(code) {
+                events.push((code.clone(), CodeTableEvent::Added(exchange, c.clone())));
+            }
+        }
+
+        std::mem::swap(&mut self.codes, &mut new);
+        events.sort_by(|a, b| a.0.cmp(&b.0));
+        events.into_iter().map(|(_, e)| e).collect()
+    }
+}
+
+/// 启动代码表监视任务
+///
+/// 首次下载完整代码表作为基准（不发出事件），之后每隔 `interval` 获取一次代码数量，
+/// 数量变化时重新下载并发出事件。数量不变时的改名不会被发现。
+/// 接收端关闭后任务自动退出；请求失败时跳过本轮。
+pub fn watch_code_table(
+    client: Arc<Client>,
+    exchange: Exchange,
+    interval: Duration,
+) -> mpsc::Receiver<CodeTableEvent> {
+    let (tx, rx) = mpsc::channel(1024);
+
+    tokio::spawn(async move {
+        let mut table: Option<CodeTable> = None;
+        let mut ticker = time::interval(interval);
+        ticker.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            if tx.is_closed() {
+                break;
+            }
+
+            if let Some(t) = &table {
+                match client.get_count(exchange).await {
+                    Ok(count) if count as usize == t.len() => continue,
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!("获取{}代码数量失败: {}", exchange.name(), e);
+                        continue;
+                    }
+                }
+            }
+
+            let codes = match client.get_code_all(exchange).await {
+                Ok(resp) => resp.codes,
+                Err(e) => {
+                    warn!("获取{}代码表失败: {}", exchange.name(), e);
+                    continue;
+                }
+            };
+
+            match &mut table {
+                None => table = Some(CodeTable::new(exchange, codes)),
+                Some(t) => {
+                    for event in t.update(codes) {
+                        if tx.send(event).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    rx
+}
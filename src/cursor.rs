@@ -0,0 +1,167 @@
+//! 同步位置持久化
+//!
+//! 增量同步任务（[`stream_trades_resumable`](crate::subscribe::stream_trades_resumable)、
+//! [`sync_klines`]）把每个代码的同步位置保存在 [`CursorStore`] 中，重启后从上次的位置继续，
+//! 不必重新下载整个交易日的数据。内置内存（[`MemoryCursorStore`]）和 JSON 文件
+//! （[`FileCursorStore`]）实现，其他存储（SQLite 等）可以在外部实现该 trait 接入。
+
+use crate::client::{Client, ClientError};
+use crate::protocol::{add_prefix, Kline, KlineType};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// 同步位置错误
+#[derive(Debug, thiserror::Error)]
+pub enum CursorError {
+    #[error("IO错误: {0}")]
+    Io(#[from] io::Error),
+    #[error("序列化错误: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("请求失败: {0}")]
+    Client(#[from] ClientError),
+    /// 翻页停止时仍未遇到上次同步的K线（服务器已不再提供该K线）
+    #[error("未找到上次同步的K线 {0}，增量同步会遗漏K线")]
+    KlineGap(i64),
+}
+
+/// 同步位置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncCursor {
+    /// 已同步的最后一根K线时间（Unix时间戳，秒）
+    pub last_bar_time: Option<i64>,
+    /// 已推送成交所属的交易日 YYYYMMDD
+    pub trade_date: String,
+    /// 该交易日已推送的成交笔数（即下一笔成交的序号）
+    pub trade_count: usize,
+}
+
+/// 同步位置存储
+///
+/// 键由 [`trade_key`] / [`kline_key`] 生成，每个同步任务只读写自己的键。
+pub trait CursorStore: Send + Sync {
+    /// 读取同步位置，没有保存过时为 None
+    fn load(&self, key: &str) -> Result<Option<SyncCursor>, CursorError>;
+
+    /// 保存同步位置
+    fn save(&self, key: &str, cursor: &SyncCursor) -> Result<(), CursorError>;
+}
+
+/// 逐笔成交同步位置的键，如 `sz000001/trade`
+pub fn trade_key(code: &str) -> String {
+    format!("{}/trade", add_prefix(code))
+}
+
+/// K线同步位置的键，如日线为 `sz000001/kline9`
+pub fn kline_key(kline_type: KlineType, code: &str) -> String {
+    format!("{}/kline{}", add_prefix(code), kline_type as u8)
+}
+
+/// 内存存储（不持久化，用于测试或单次运行）
+#[derive(Debug, Default)]
+pub struct MemoryCursorStore {
+    cursors: Mutex<HashMap<String, SyncCursor>>,
+}
+
+impl MemoryCursorStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CursorStore for MemoryCursorStore {
+    fn load(&self, key: &str) -> Result<Option<SyncCursor>, CursorError> {
+        Ok(self.cursors.lock().unwrap().get(key).cloned())
+    }
+
+    fn save(&self, key: &str, cursor: &SyncCursor) -> Result<(), CursorError> {
+        self.cursors
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), cursor.clone());
+        Ok(())
+    }
+}
+
+/// JSON 文件存储
+///
+/// 全部同步位置保存在一个文件中（键 → 位置）。每次保存先写入同目录下的临时文件再替换，
+/// 进程中途退出也不会留下写了一半的文件。
+#[derive(Debug)]
+pub struct FileCursorStore {
+    path: PathBuf,
+    cursors: Mutex<BTreeMap<String, SyncCursor>>,
+}
+
+impl FileCursorStore {
+    /// 打开存储文件，文件不存在时从空白开始（首次保存时创建）
+    pub fn open<P: Into<PathBuf>>(path: P) -> Result<Self, CursorError> {
+        let path = path.into();
+        let cursors = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path,
+            cursors: Mutex::new(cursors),
+        })
+    }
+}
+
+impl CursorStore for FileCursorStore {
+    fn load(&self, key: &str) -> Result<Option<SyncCursor>, CursorError> {
+        Ok(self.cursors.lock().unwrap().get(key).cloned())
+    }
+
+    fn save(&self, key: &str, cursor: &SyncCursor) -> Result<(), CursorError> {
+        let mut cursors = self.cursors.lock().unwrap();
+        cursors.insert(key.to_string(), cursor.clone());
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(&*cursors)?)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// 增量同步K线，返回上次同步之后的新K线（按时间升序）
+///
+/// 没有同步位置时下载全部K线；否则从最新一页往前翻页，直到遇到已同步的K线。
+/// 返回前把最后一根K线的时间保存为新的同步位置。最后一根K线在收盘前可能还会变化，
+/// 同步位置越过它之后不会再次返回，盘中同步日线及以上周期时需要注意。
+///
+/// 翻页结束时仍未遇到上次同步的K线（例如服务器只保留了最近的分钟K线）返回
+/// [`CursorError::KlineGap`]，同步位置保持不变。
+pub async fn sync_klines(
+    client: &Client,
+    store: &dyn CursorStore,
+    kline_type: KlineType,
+    code: &str,
+) -> Result<Vec<Kline>, CursorError> {
+    let key = kline_key(kline_type, code);
+    let mut cursor = store.load(&key)?.unwrap_or_default();
+
+    let list = match cursor.last_bar_time {
+        None => client.get_kline_all(kline_type, code).await?.list,
+        Some(last) => {
+            // 从已同步的K线开始取，确认翻页确实到达了同步位置，中间没有缺口
+            let mut list = client
+                .kline_pages_between(kline_type, code, last, i64::MAX)
+                .await?;
+            if list.first().map(|k| k.time) != Some(last) {
+                return Err(CursorError::KlineGap(last));
+            }
+            list.remove(0);
+            list
+        }
+    };
+
+    if let Some(k) = list.last() {
+        cursor.last_bar_time = Some(k.time);
+        store.save(&key, &cursor)?;
+    }
+    Ok(list)
+}
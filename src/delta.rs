@@ -0,0 +1,293 @@
+//! 行情快照差分编码
+//!
+//! 连续录制同一只股票的 [`QuoteInfo`] 时，只保存与上一条相比发生变化的字段，
+//! 数值字段保存差值（zigzag + LEB128 变长编码）。
+//!
+//! 单条记录格式：
+//!
+//! ```text
+//! | Exchange(1字节) | CodeLen(1字节) | Code | Mask(变长) | 变化字段... |
+//! ```
+//!
+//! Mask 的第 i 位表示第 i 个字段有变化，字段顺序见 `FIELD_COUNT` 上方的说明。
+//! 保留字段（`raw_reserved`）使用服务器时间之后的位，不含这些位的旧数据仍可解码。
+//! 每只股票的第一条记录与全零快照比较，因此解码端不需要额外的初始状态。
+
+use crate::protocol::{Amount, Exchange, Price, PriceLevel, PriceLevels, QuoteInfo, K};
+use std::collections::HashMap;
+
+/// 差分编解码错误
+#[derive(Debug, thiserror::Error)]
+pub enum DeltaError {
+    #[error("数据不完整")]
+    Truncated,
+    #[error("无效的交易所: {0}")]
+    InvalidExchange(u8),
+    #[error("无效的字符串")]
+    InvalidString,
+    #[error("变长整数溢出")]
+    Overflow,
+}
+
+// 数值字段顺序：
+// active1, last, open, high, low, close, total_hand, intuition, amount, inside_dish, outer_disc,
+// 5档买盘(价格, 数量) × 5, 5档卖盘(价格, 数量) × 5, rate(f64 位模式), active2
+const FIELD_COUNT: usize = 33;
+// 服务器时间（字符串）对应的 Mask 位
+const SERVER_TIME_BIT: usize = FIELD_COUNT;
+// 保留字段 raw_reserved[i] 对应的 Mask 位为 RESERVED_BIT + i
+const RESERVED_BIT: usize = SERVER_TIME_BIT + 1;
+const RESERVED_COUNT: usize = 8;
+
+type Key = (Exchange, String);
+
+fn fields(q: &QuoteInfo) -> [i128; FIELD_COUNT] {
+    let mut f = [0i128; FIELD_COUNT];
+    f[0] = q.active1 as i128;
+    f[1] = q.k.last.0 as i128;
+    f[2] = q.k.open.0 as i128;
+    f[3] = q.k.high.0 as i128;
+    f[4] = q.k.low.0 as i128;
+    f[5] = q.k.close.0 as i128;
+    f[6] = q.total_hand as i128;
+    f[7] = q.intuition as i128;
+    f[8] = q.amount.0;
+    f[9] = q.inside_dish as i128;
+    f[10] = q.outer_disc as i128;
+    for i in 0..5 {
+        f[11 + i * 2] = q.buy_level[i].price.0 as i128;
+        f[12 + i * 2] = q.buy_level[i].number as i128;
+        f[21 + i * 2] = q.sell_level[i].price.0 as i128;
+        f[22 + i * 2] = q.sell_level[i].number as i128;
+    }
+    f[31] = q.rate.to_bits() as i64 as i128;
+    f[32] = q.active2 as i128;
+    f
+}
+
+fn apply_fields(q: &mut QuoteInfo, f: &[i128; FIELD_COUNT]) {
+    q.active1 = f[0] as u16;
+    q.k.last = Price(f[1] as i64);
+    q.k.open = Price(f[2] as i64);
+    q.k.high = Price(f[3] as i64);
+    q.k.low = Price(f[4] as i64);
+    q.k.close = Price(f[5] as i64);
+    q.total_hand = f[6] as i32;
+    q.intuition = f[7] as i32;
+    q.amount = Amount(f[8]);
+    q.inside_dish = f[9] as i32;
+    q.outer_disc = f[10] as i32;
+    for i in 0..5 {
+        q.buy_level[i].price = Price(f[11 + i * 2] as i64);
+        q.buy_level[i].number = f[12 + i * 2] as i32;
+        q.sell_level[i].price = Price(f[21 + i * 2] as i64);
+        q.sell_level[i].number = f[22 + i * 2] as i32;
+    }
+    q.rate = f64::from_bits(f[31] as i64 as u64);
+    q.active2 = f[32] as u16;
+}
+
+fn empty_levels(buy: bool) -> PriceLevels {
+    [PriceLevel {
+        buy,
+        price: Price(0),
+        number: 0,
+    }; 5]
+}
+
+/// 全零快照（每只股票第一条记录的比较基准）
+fn empty_quote(exchange: Exchange, code: &str) -> QuoteInfo {
+    QuoteInfo {
+        exchange,
+        code: code.to_string(),
+        active1: 0,
+        k: K {
+            last: Price(0),
+            open: Price(0),
+            high: Price(0),
+            low: Price(0),
+            close: Price(0),
+        },
+        server_time: String::new(),
+        total_hand: 0,
+        intuition: 0,
+        amount: Amount(0),
+        inside_dish: 0,
+        outer_disc: 0,
+        buy_level: empty_levels(true),
+        sell_level: empty_levels(false),
+        rate: 0.0,
+        active2: 0,
+        raw_reserved: [0; 8],
+    }
+}
+
+fn write_uvarint(buf: &mut Vec<u8>, mut value: u128) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn read_uvarint(bytes: &[u8], offset: &mut usize) -> Result<u128, DeltaError> {
+    let mut value = 0u128;
+    let mut shift = 0;
+    loop {
+        let b = *bytes.get(*offset).ok_or(DeltaError::Truncated)?;
+        *offset += 1;
+        if shift >= 128 {
+            return Err(DeltaError::Overflow);
+        }
+        value |= ((b & 0x7F) as u128) << shift;
+        if b & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+fn zigzag(v: i128) -> u128 {
+    ((v << 1) ^ (v >> 127)) as u128
+}
+
+fn unzigzag(v: u128) -> i128 {
+    ((v >> 1) as i128) ^ -((v & 1) as i128)
+}
+
+fn write_str(buf: &mut Vec<u8>, s: &str) -> Result<(), DeltaError> {
+    let len = u8::try_from(s.len()).map_err(|_| DeltaError::InvalidString)?;
+    buf.push(len);
+    buf.extend_from_slice(s.as_bytes());
+    Ok(())
+}
+
+fn read_str(bytes: &[u8], offset: &mut usize) -> Result<String, DeltaError> {
+    let len = *bytes.get(*offset).ok_or(DeltaError::Truncated)? as usize;
+    let start = *offset + 1;
+    let raw = bytes.get(start..start + len).ok_or(DeltaError::Truncated)?;
+    *offset = start + len;
+    String::from_utf8(raw.to_vec()).map_err(|_| DeltaError::InvalidString)
+}
+
+/// 行情差分编码器
+#[derive(Debug, Default)]
+pub struct QuoteDeltaEncoder {
+    last: HashMap<Key, QuoteInfo>,
+}
+
+impl QuoteDeltaEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 编码一条行情（追加到 buf）
+    pub fn encode(&mut self, quote: &QuoteInfo, buf: &mut Vec<u8>) -> Result<(), DeltaError> {
+        let key = (quote.exchange, quote.code.clone());
+        let prev = self.last.get(&key).map(fields).unwrap_or([0; FIELD_COUNT]);
+        let prev_time = self.last.get(&key).map(|q| q.server_time.as_str());
+        let cur = fields(quote);
+
+        let mut mask = 0u64;
+        for i in 0..FIELD_COUNT {
+            if cur[i] != prev[i] {
+                mask |= 1 << i;
+            }
+        }
+        if prev_time.unwrap_or("") != quote.server_time {
+            mask |= 1 << SERVER_TIME_BIT;
+        }
+        let prev_reserved = self
+            .last
+            .get(&key)
+            .map_or([0; RESERVED_COUNT], |q| q.raw_reserved);
+        for (i, (cur, prev)) in quote.raw_reserved.iter().zip(&prev_reserved).enumerate() {
+            if cur != prev {
+                mask |= 1 << (RESERVED_BIT + i);
+            }
+        }
+
+        buf.push(quote.exchange.as_u8());
+        write_str(buf, &quote.code)?;
+        write_uvarint(buf, mask as u128);
+        for i in 0..FIELD_COUNT {
+            if mask & (1 << i) != 0 {
+                write_uvarint(buf, zigzag(cur[i].wrapping_sub(prev[i])));
+            }
+        }
+        if mask & (1 << SERVER_TIME_BIT) != 0 {
+            write_str(buf, &quote.server_time)?;
+        }
+        for (i, (cur, prev)) in quote.raw_reserved.iter().zip(&prev_reserved).enumerate() {
+            if mask & (1 << (RESERVED_BIT + i)) != 0 {
+                write_uvarint(buf, zigzag(*cur as i128 - *prev as i128));
+            }
+        }
+
+        self.last.insert(key, quote.clone());
+        Ok(())
+    }
+}
+
+/// 行情差分解码器
+#[derive(Debug, Default)]
+pub struct QuoteDeltaDecoder {
+    last: HashMap<Key, QuoteInfo>,
+}
+
+impl QuoteDeltaDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 解码一条行情，返回行情和消耗的字节数
+    pub fn decode(&mut self, bytes: &[u8]) -> Result<(QuoteInfo, usize), DeltaError> {
+        let mut offset = 0;
+        let ex = *bytes.first().ok_or(DeltaError::Truncated)?;
+        let exchange = Exchange::from_u8(ex).ok_or(DeltaError::InvalidExchange(ex))?;
+        offset += 1;
+        let code = read_str(bytes, &mut offset)?;
+        let mask = read_uvarint(bytes, &mut offset)?;
+        if mask >> (RESERVED_BIT + RESERVED_COUNT) != 0 {
+            return Err(DeltaError::Overflow);
+        }
+
+        let key = (exchange, code);
+        let mut quote = self
+            .last
+            .get(&key)
+            .cloned()
+            .unwrap_or_else(|| empty_quote(exchange, &key.1));
+        let mut f = fields(&quote);
+        for (i, value) in f.iter_mut().enumerate() {
+            if mask & (1 << i) != 0 {
+                let delta = unzigzag(read_uvarint(bytes, &mut offset)?);
+                *value = value.wrapping_add(delta);
+            }
+        }
+        apply_fields(&mut quote, &f);
+        if mask & (1 << SERVER_TIME_BIT) != 0 {
+            quote.server_time = read_str(bytes, &mut offset)?;
+        }
+        for (i, value) in quote.raw_reserved.iter_mut().enumerate() {
+            if mask & (1 << (RESERVED_BIT + i)) != 0 {
+                let delta = unzigzag(read_uvarint(bytes, &mut offset)?);
+                *value = (*value as i128).wrapping_add(delta) as i32;
+            }
+        }
+
+        self.last.insert(key, quote.clone());
+        Ok((quote, offset))
+    }
+
+    /// 解码连续存放的多条行情
+    pub fn decode_all(&mut self, mut bytes: &[u8]) -> Result<Vec<QuoteInfo>, DeltaError> {
+        let mut quotes = Vec::new();
+        while !bytes.is_empty() {
+            let (quote, consumed) = self.decode(bytes)?;
+            quotes.push(quote);
+            bytes = &bytes[consumed..];
+        }
+        Ok(quotes)
+    }
+}
@@ -2,9 +2,10 @@
 
 use crate::client::Client;
 use crate::client::ClientError;
+use crate::protocol::Exchange;
 use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
 use std::time::{Duration, Instant};
 use tokio::net::TcpStream;
 use tokio::time;
@@ -25,12 +26,89 @@ pub const DEFAULT_HOSTS: &[&str] = &[
     "124.70.133.119",
 ];
 
+/// 默认端口
+pub const DEFAULT_PORT: u16 = 7709;
+
+/// 备用端口列表（部分镜像服务器不监听7709）
+///
+/// 只在显式调用 [`dial_ports`] / [`fast_hosts_ports`] 时使用，
+/// 其他连接函数只连接地址中的端口或 [`DEFAULT_PORT`]。
+pub const ALTERNATE_PORTS: &[u16] = &[7709, 7727, 80, 443];
+
+/// 单个端口的 TCP 连接超时时间（不含握手，握手受读取超时限制）
+const PORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 交易所行情的默认端口
+///
+/// 沪深京三个交易所的标准行情都在7709端口；7727端口是扩展行情（期货、港股等），本库暂不支持。
+pub fn default_port(exchange: Exchange) -> u16 {
+    match exchange {
+        Exchange::SZ | Exchange::SH | Exchange::BJ => DEFAULT_PORT,
+    }
+}
+
+/// 拆分地址中的主机和端口（未指定端口时返回 None）
+///
+/// 带端口的 IPv6 地址写作 `[::1]:7709`；不带方括号的 IPv6 地址视为未指定端口。
+/// IPv6 主机返回时带方括号，可以直接拼接端口。
+fn split_port(host: &str) -> (String, Option<u16>) {
+    if let Some((h, rest)) = host.strip_prefix('[').and_then(|s| s.split_once(']')) {
+        let port = rest.strip_prefix(':').and_then(|p| p.parse().ok());
+        return (format!("[{}]", h), port);
+    }
+    if host.matches(':').count() > 1 {
+        return (format!("[{}]", host), None);
+    }
+    match host.rsplit_once(':') {
+        Some((h, p)) => match p.parse() {
+            Ok(port) => (h.to_string(), Some(port)),
+            Err(_) => (host.to_string(), None),
+        },
+        None => (host.to_string(), None),
+    }
+}
+
+/// 待尝试的端口（地址中已指定端口时只尝试该端口）
+fn candidate_ports(host: &str, ports: &[u16]) -> (String, Vec<u16>) {
+    let (host, port) = split_port(host);
+    let ports = match port {
+        Some(port) => vec![port],
+        None => ports.to_vec(),
+    };
+    (host, ports)
+}
+
 /// 连接到指定地址
 pub async fn dial(addr: &str) -> Result<Client, ClientError> {
     Client::connect(addr).await
 }
 
+/// 依次尝试多个端口连接，返回客户端和连接成功的端口
+///
+/// 地址中已指定端口时只尝试该端口。每个端口的 TCP 连接最多等待5秒，握手受读取超时限制，
+/// 全部端口都不可用时最长耗时约为端口数 ×（5秒 + 读取超时）。
+pub async fn dial_ports(host: &str, ports: &[u16]) -> Result<(Client, u16), ClientError> {
+    let (host, ports) = candidate_ports(host, ports);
+
+    let mut last_error = None;
+    for port in ports {
+        let addr = format!("{}:{}", host, port);
+        match Client::builder()
+            .connect_timeout(PORT_TIMEOUT)
+            .connect(&addr)
+            .await
+        {
+            Ok(client) => return Ok((client, port)),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| ClientError::Other("没有可用的端口".to_string())))
+}
+
 /// 遍历多个地址进行连接，成功则返回
+///
+/// 每个地址只连接其中指定的端口，未指定时使用 [`DEFAULT_PORT`]；需要尝试备用端口时使用 [`dial_ports`]。
 pub async fn dial_hosts_range(hosts: &[&str]) -> Result<Client, ClientError> {
     let hosts = if hosts.is_empty() {
         DEFAULT_HOSTS
@@ -40,8 +118,8 @@ pub async fn dial_hosts_range(hosts: &[&str]) -> Result<Client, ClientError> {
 
     let mut last_error = None;
     for host in hosts {
-        match Client::connect(host).await {
-            Ok(client) => return Ok(client),
+        match dial_ports(host, &[DEFAULT_PORT]).await {
+            Ok((client, _)) => return Ok(client),
             Err(e) => {
                 last_error = Some(e);
                 // 等待2秒后尝试下一个
@@ -55,19 +133,38 @@ pub async fn dial_hosts_range(hosts: &[&str]) -> Result<Client, ClientError> {
 
 /// 随机选择一个地址连接
 pub async fn dial_hosts_random(hosts: &[&str]) -> Result<Client, ClientError> {
+    // Use a Send-friendly RNG so this async fn can be spawned onto the multithread runtime.
+    let mut rng = StdRng::from_entropy();
+    dial_hosts_random_with(hosts, &mut rng).await
+}
+
+/// 使用指定种子随机选择一个地址连接（相同种子选择相同地址，便于测试复现）
+pub async fn dial_hosts_seeded(hosts: &[&str], seed: u64) -> Result<Client, ClientError> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    dial_hosts_random_with(hosts, &mut rng).await
+}
+
+/// 使用指定随机数生成器随机选择一个地址连接
+pub async fn dial_hosts_random_with<R: Rng + ?Sized>(
+    hosts: &[&str],
+    rng: &mut R,
+) -> Result<Client, ClientError> {
+    let host = choose_host(hosts, rng)
+        .ok_or_else(|| ClientError::Other("没有可用的服务器地址".to_string()))?;
+
+    dial_ports(host, &[DEFAULT_PORT])
+        .await
+        .map(|(client, _)| client)
+}
+
+/// 随机选择一个地址（hosts 为空时从默认服务器列表中选择）
+pub fn choose_host<'a, R: Rng + ?Sized>(hosts: &[&'a str], rng: &mut R) -> Option<&'a str> {
     let hosts = if hosts.is_empty() {
         DEFAULT_HOSTS
     } else {
         hosts
     };
-
-    // Use a Send-friendly RNG so this async fn can be spawned onto the multithread runtime.
-    let mut rng = StdRng::from_entropy();
-    let host = hosts
-        .choose(&mut rng)
-        .ok_or_else(|| ClientError::Other("没有可用的服务器地址".to_string()))?;
-
-    Client::connect(host).await
+    hosts.choose(rng).copied()
 }
 
 /// 使用默认连接方式（遍历默认服务器列表）
@@ -76,14 +173,35 @@ pub async fn dial_default() -> Result<Client, ClientError> {
 }
 
 /// 连接结果（用于测试连接速度）
+///
+/// 以后可能增加字段，外部代码不能用结构体字面量构造。
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct DialResult {
+    /// 主机（IPv6 地址带方括号）
     pub host: String,
+    /// 连接成功的端口
+    pub port: u16,
+    /// TCP 连接耗时
     pub duration: Duration,
 }
 
+impl DialResult {
+    /// 完整地址（host:port）
+    pub fn addr(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
 /// 测试多个地址的连接速度并排序
+///
+/// 每个地址只测试其中指定的端口，未指定时使用 [`DEFAULT_PORT`]；需要探测备用端口时使用 [`fast_hosts_ports`]。
 pub async fn fast_hosts(hosts: &[&str]) -> Vec<DialResult> {
+    fast_hosts_ports(hosts, &[DEFAULT_PORT]).await
+}
+
+/// 测试多个地址的连接速度并排序（每个地址依次尝试多个端口，记录第一个可用端口）
+pub async fn fast_hosts_ports(hosts: &[&str], ports: &[u16]) -> Vec<DialResult> {
     let hosts = if hosts.is_empty() {
         DEFAULT_HOSTS
     } else {
@@ -93,22 +211,20 @@ pub async fn fast_hosts(hosts: &[&str]) -> Vec<DialResult> {
     let mut handles = Vec::new();
 
     for host in hosts {
-        let host = host.to_string();
+        let (host, ports) = candidate_ports(host, ports);
         handles.push(tokio::spawn(async move {
-            let addr = if host.contains(':') {
-                host.clone()
-            } else {
-                format!("{}:7709", host)
-            };
-
-            let start = Instant::now();
-            match TcpStream::connect(&addr).await {
-                Ok(_) => Some(DialResult {
-                    host,
-                    duration: start.elapsed(),
-                }),
-                Err(_) => None,
+            for port in ports {
+                let addr = format!("{}:{}", host, port);
+                let start = Instant::now();
+                if let Ok(Ok(_)) = time::timeout(PORT_TIMEOUT, TcpStream::connect(&addr)).await {
+                    return Some(DialResult {
+                        host,
+                        port,
+                        duration: start.elapsed(),
+                    });
+                }
             }
+            None
         }));
     }
 
@@ -0,0 +1,39 @@
+//! 连接生命周期事件
+//!
+//! [`Client`](crate::Client) 和 [`ResilientClient`](crate::ResilientClient) 在连接、重连、
+//! 切换服务器、心跳失败和连接关闭时通过广播通道发出 [`LifecycleEvent`]，
+//! 应用可以据此驱动连接状态指示和告警，不必解析日志。
+//!
+//! 广播通道只把事件发给订阅之后的接收端；需要收到首次连接事件时，先用 [`channel`] 创建通道并订阅，
+//! 再通过 [`ClientBuilder::events`](crate::ClientBuilder::events) 交给客户端。
+//! 接收端处理不及时会丢失最旧的事件（`RecvError::Lagged`）。
+
+use tokio::sync::broadcast;
+
+/// 事件通道的容量
+pub const EVENT_CAPACITY: usize = 64;
+
+/// 连接生命周期事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    /// 已连接并完成握手
+    Connected { addr: String },
+    /// 连接断开后重新连接到原服务器
+    Reconnected { addr: String },
+    /// 切换到其他服务器（自动重连到默认服务器列表，或故障转移）
+    HostSwitched { from: String, to: String },
+    /// 心跳请求失败
+    HeartbeatMissed { addr: String, error: String },
+    /// 连接已关闭：服务器断开、读取出错或客户端被释放
+    Closed { addr: String, reason: String },
+}
+
+/// 创建事件通道
+pub fn channel() -> broadcast::Sender<LifecycleEvent> {
+    broadcast::channel(EVENT_CAPACITY).0
+}
+
+/// 发送事件（没有接收端时忽略）
+pub(crate) fn emit(events: &broadcast::Sender<LifecycleEvent>, event: LifecycleEvent) {
+    let _ = events.send(event);
+}
@@ -0,0 +1,148 @@
+//! 批量导出K线
+//!
+//! 多个下载任务并发获取各代码的全部K线，经可选的转换后通过有界通道交给写入任务写入 [`Sink`]。
+//! 写入跟不上时通道被占满，下载任务随之等待，除正在写入的代码外内存中最多只有
+//! `buffer + concurrency` 个代码的数据，不会因为磁盘慢而无限堆积。
+
+use crate::client::Client;
+use crate::protocol::{add_prefix, Kline, KlineType};
+use crate::sink::{Sink, SinkError};
+use log::warn;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// K线转换函数：(带前缀的代码, K线) → 写入的K线
+pub type KlineTransform = Arc<dyn Fn(&str, Vec<Kline>) -> Vec<Kline> + Send + Sync>;
+
+/// 导出流水线配置（并发、缓冲和转换）
+#[derive(Clone)]
+pub struct ExportConfig {
+    /// 并发下载的代码数量
+    pub concurrency: usize,
+    /// 等待写入的代码数量上限
+    pub buffer: usize,
+    transform: Option<KlineTransform>,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            buffer: 8,
+            transform: None,
+        }
+    }
+}
+
+impl ExportConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn with_buffer(mut self, buffer: usize) -> Self {
+        self.buffer = buffer.max(1);
+        self
+    }
+
+    /// 写入前对每个代码的K线做转换（如复权、过滤），在下载任务中执行
+    pub fn with_transform(
+        mut self,
+        transform: impl Fn(&str, Vec<Kline>) -> Vec<Kline> + Send + Sync + 'static,
+    ) -> Self {
+        self.transform = Some(Arc::new(transform));
+        self
+    }
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Default)]
+pub struct ExportReport {
+    /// 写入的代码数量
+    pub codes: usize,
+    /// 写入的K线条数
+    pub bars: usize,
+    /// 下载失败的代码
+    pub failed: Vec<String>,
+}
+
+/// 导出一组代码的全部K线到 sink
+///
+/// 下载失败的代码记录日志并跳过；写入失败时停止导出并返回错误。
+/// 完成后刷新 sink 并将其返回。写入在阻塞线程中执行。
+pub async fn export_klines<S>(
+    client: Arc<Client>,
+    codes: Vec<String>,
+    kline_type: KlineType,
+    sink: S,
+    config: ExportConfig,
+) -> Result<(S, ExportReport), SinkError>
+where
+    S: Sink + Send + 'static,
+{
+    let (tx, mut rx) = mpsc::channel::<(String, Vec<Kline>)>(config.buffer.max(1));
+    let queue = Arc::new(Mutex::new(codes.into_iter().collect::<VecDeque<_>>()));
+    let failed = Arc::new(Mutex::new(Vec::new()));
+
+    let mut fetchers = Vec::new();
+    for _ in 0..config.concurrency.max(1) {
+        let client = client.clone();
+        let queue = queue.clone();
+        let failed = failed.clone();
+        let transform = config.transform.clone();
+        let tx = tx.clone();
+        fetchers.push(tokio::spawn(async move {
+            loop {
+                let Some(code) = queue.lock().unwrap().pop_front() else {
+                    break;
+                };
+                let klines = match client.get_kline_all(kline_type, &code).await {
+                    Ok(resp) => resp.list,
+                    Err(e) => {
+                        warn!("导出{}K线失败: {}", code, e);
+                        failed.lock().unwrap().push(code);
+                        continue;
+                    }
+                };
+                let code = add_prefix(&code);
+                let klines = match &transform {
+                    Some(f) => f(&code, klines),
+                    None => klines,
+                };
+                // 通道已满时在这里等待写入
+                if tx.send((code, klines)).await.is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+    drop(tx);
+
+    let writer = tokio::task::spawn_blocking(move || {
+        let mut sink = sink;
+        let mut report = ExportReport::default();
+        while let Some((code, klines)) = rx.blocking_recv() {
+            sink.write_klines(&code, &klines)?;
+            report.codes += 1;
+            report.bars += klines.len();
+        }
+        sink.flush()?;
+        Ok::<_, SinkError>((sink, report))
+    });
+
+    let res = writer
+        .await
+        .map_err(|e| SinkError::Other(format!("写入任务异常退出: {}", e)));
+    // 正常结束时下载任务均已退出；写入失败时不再等待进行中的下载
+    for fetcher in fetchers {
+        fetcher.abort();
+    }
+    let (sink, mut report) = res??;
+    report.failed = std::mem::take(&mut *failed.lock().unwrap());
+    Ok((sink, report))
+}
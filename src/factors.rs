@@ -0,0 +1,127 @@
+//! 横截面因子运算
+//!
+//! 对 [`Matrix`] 的每一行（同一时间点的所有股票）做横截面变换，
+//! NaN 视为缺失值，不参与计算且结果仍为 NaN。
+
+use crate::align::Matrix;
+use std::collections::HashMap;
+
+fn map_rows(m: &Matrix, f: impl Fn(&[f64]) -> Vec<f64>) -> Matrix {
+    Matrix {
+        times: m.times.clone(),
+        symbols: m.symbols.clone(),
+        values: m.values.iter().map(|row| f(row)).collect(),
+    }
+}
+
+fn valid(row: &[f64]) -> impl Iterator<Item = f64> + '_ {
+    row.iter().copied().filter(|v| !v.is_nan())
+}
+
+fn mean_std(row: &[f64]) -> Option<(f64, f64)> {
+    let n = valid(row).count();
+    if n == 0 {
+        return None;
+    }
+    let mean = valid(row).sum::<f64>() / n as f64;
+    let var = valid(row).map(|v| (v - mean).powi(2)).sum::<f64>() / n as f64;
+    Some((mean, var.sqrt()))
+}
+
+/// 单行排名，返回 0~1 的分位（并列取平均名次；只有一个有效值时为 0.5）
+pub fn rank_row(row: &[f64]) -> Vec<f64> {
+    let mut idx: Vec<usize> = (0..row.len()).filter(|&i| !row[i].is_nan()).collect();
+    idx.sort_by(|&a, &b| row[a].total_cmp(&row[b]));
+
+    let mut out = vec![f64::NAN; row.len()];
+    let n = idx.len();
+    let mut i = 0;
+    while i < n {
+        let mut j = i;
+        while j + 1 < n && row[idx[j + 1]] == row[idx[i]] {
+            j += 1;
+        }
+        let avg = (i + j) as f64 / 2.0;
+        let pct = if n > 1 { avg / (n - 1) as f64 } else { 0.5 };
+        for &k in &idx[i..=j] {
+            out[k] = pct;
+        }
+        i = j + 1;
+    }
+    out
+}
+
+/// 单行标准化 (x - 均值) / 标准差（标准差为0时结果为0）
+pub fn zscore_row(row: &[f64]) -> Vec<f64> {
+    let Some((mean, std)) = mean_std(row) else {
+        return row.to_vec();
+    };
+    row.iter()
+        .map(|&v| {
+            if v.is_nan() {
+                v
+            } else if std == 0.0 {
+                0.0
+            } else {
+                (v - mean) / std
+            }
+        })
+        .collect()
+}
+
+/// 单行去极值，将超出 均值 ± n倍标准差 的值截断到边界
+pub fn winsorize_row(row: &[f64], n: f64) -> Vec<f64> {
+    let Some((mean, std)) = mean_std(row) else {
+        return row.to_vec();
+    };
+    let (lo, hi) = (mean - n * std, mean + n * std);
+    row.iter()
+        .map(|&v| if v.is_nan() { v } else { v.clamp(lo, hi) })
+        .collect()
+}
+
+/// 单行按板块中性化：减去所在板块的均值（无板块的股票结果为 NaN）
+pub fn neutralize_row(row: &[f64], blocks: &[Option<&str>]) -> Vec<f64> {
+    let mut sums: HashMap<&str, (f64, usize)> = HashMap::new();
+    for (&v, block) in row.iter().zip(blocks) {
+        if let (false, Some(b)) = (v.is_nan(), block) {
+            let e = sums.entry(b).or_insert((0.0, 0));
+            e.0 += v;
+            e.1 += 1;
+        }
+    }
+    row.iter()
+        .zip(blocks)
+        .map(|(&v, block)| match block.and_then(|b| sums.get(b)) {
+            Some(&(sum, n)) if !v.is_nan() => v - sum / n as f64,
+            _ => f64::NAN,
+        })
+        .collect()
+}
+
+/// 横截面排名
+pub fn rank(m: &Matrix) -> Matrix {
+    map_rows(m, rank_row)
+}
+
+/// 横截面标准化
+pub fn zscore(m: &Matrix) -> Matrix {
+    map_rows(m, zscore_row)
+}
+
+/// 横截面去极值（均值 ± n倍标准差）
+pub fn winsorize(m: &Matrix, n: f64) -> Matrix {
+    map_rows(m, |row| winsorize_row(row, n))
+}
+
+/// 横截面板块中性化
+///
+/// blocks: 股票代码 → 板块名称，未包含的股票结果为 NaN。
+pub fn neutralize_by_block(m: &Matrix, blocks: &HashMap<String, String>) -> Matrix {
+    let cols: Vec<Option<&str>> = m
+        .symbols
+        .iter()
+        .map(|s| blocks.get(s).map(String::as_str))
+        .collect();
+    map_rows(m, |row| neutralize_row(row, &cols))
+}
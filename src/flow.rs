@@ -0,0 +1,161 @@
+//! 资金流向
+//!
+//! 按 [`classify`] 推断每笔成交的主动方向，汇总成每日的主动买入/卖出金额；
+//! 单笔金额不低于大单阈值的成交计入主力资金，阈值可以按代码单独设置（大盘股和小盘股的大单标准不同）。多日的历史汇总由 [`FlowHistory`] 逐日下载历史成交计算，
+//! 结果按 (代码, 日期) 缓存，重复查询不再下载。当天的数据尚未收盘，不会缓存。
+
+use crate::classify::{classify, InferredSide};
+use crate::client::{Client, ClientError};
+use crate::protocol::{add_prefix, Trade};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// 默认的大单金额阈值（元）
+pub const DEFAULT_LARGE_ORDER: f64 = 200_000.0;
+
+/// 单日资金流向汇总（金额单位：元）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyFlow {
+    /// 日期 YYYYMMDD
+    pub date: String,
+    /// 主动买入金额
+    pub buy_amount: f64,
+    /// 主动卖出金额
+    pub sell_amount: f64,
+    /// 大单主动买入金额
+    pub large_buy_amount: f64,
+    /// 大单主动卖出金额
+    pub large_sell_amount: f64,
+    /// 成交笔数（含集合竞价等未计入买卖的成交）
+    pub trades: usize,
+}
+
+impl DailyFlow {
+    /// 由一天的成交（按时间升序）汇总
+    pub fn from_trades(date: &str, trades: &[Trade], large_order: f64) -> Self {
+        let mut flow = DailyFlow {
+            date: date.to_string(),
+            buy_amount: 0.0,
+            sell_amount: 0.0,
+            large_buy_amount: 0.0,
+            large_sell_amount: 0.0,
+            trades: trades.len(),
+        };
+        for (t, side) in trades.iter().zip(classify(trades, &[])) {
+            // 成交量单位为手
+            let amount = t.price.to_yuan() * t.volume as f64 * 100.0;
+            let large = amount >= large_order;
+            match side {
+                InferredSide::Buy => {
+                    flow.buy_amount += amount;
+                    if large {
+                        flow.large_buy_amount += amount;
+                    }
+                }
+                InferredSide::Sell => {
+                    flow.sell_amount += amount;
+                    if large {
+                        flow.large_sell_amount += amount;
+                    }
+                }
+                _ => {}
+            }
+        }
+        flow
+    }
+
+    /// 净流入金额
+    pub fn net_amount(&self) -> f64 {
+        self.buy_amount - self.sell_amount
+    }
+
+    /// 主力（大单）净流入金额
+    pub fn main_net_amount(&self) -> f64 {
+        self.large_buy_amount - self.large_sell_amount
+    }
+}
+
+/// 多日资金流向（带缓存）
+#[derive(Debug, Clone)]
+pub struct FlowHistory {
+    large_order: f64,
+    /// 按代码（带市场前缀）设置的大单阈值
+    code_large_order: HashMap<String, f64>,
+    cache: HashMap<(String, String), DailyFlow>,
+}
+
+impl Default for FlowHistory {
+    fn default() -> Self {
+        Self {
+            large_order: DEFAULT_LARGE_ORDER,
+            code_large_order: HashMap::new(),
+            cache: HashMap::new(),
+        }
+    }
+}
+
+impl FlowHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 大单金额阈值（元），用于没有单独设置阈值的代码，修改后清空缓存
+    pub fn with_large_order(mut self, amount: f64) -> Self {
+        self.large_order = amount;
+        self.cache.clear();
+        self
+    }
+
+    /// 单独设置某个代码的大单金额阈值（元），清空该代码的缓存
+    pub fn with_code_large_order(mut self, code: &str, amount: f64) -> Self {
+        let code = add_prefix(code);
+        self.cache.retain(|(c, _), _| *c != code);
+        self.code_large_order.insert(code, amount);
+        self
+    }
+
+    /// 某个代码使用的大单金额阈值（元）
+    pub fn large_order(&self, code: &str) -> f64 {
+        self.code_large_order
+            .get(&add_prefix(code))
+            .copied()
+            .unwrap_or(self.large_order)
+    }
+
+    /// 已缓存的 (代码, 日期) 数量
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// 逐日计算资金流向，结果与 dates（YYYYMMDD）一一对应
+    pub async fn history(
+        &mut self,
+        client: &Client,
+        code: &str,
+        dates: &[String],
+    ) -> Result<Vec<DailyFlow>, ClientError> {
+        let code = add_prefix(code);
+        let today = client.clock().today();
+        let large_order = self.large_order(&code);
+        let mut series = Vec::with_capacity(dates.len());
+        for date in dates {
+            let key = (code.clone(), date.clone());
+            if let Some(flow) = self.cache.get(&key) {
+                series.push(flow.clone());
+                continue;
+            }
+
+            let trades = client.get_history_trade_day(date, &code).await?;
+            let flow = DailyFlow::from_trades(date, &trades.list, large_order);
+            if *date < today {
+                self.cache.insert(key, flow.clone());
+            }
+            series.push(flow);
+        }
+        Ok(series)
+    }
+}
@@ -0,0 +1,71 @@
+//! 数据新鲜度
+//!
+//! 负载过高的镜像服务器可能返回延迟的行情。[`DataFreshness`] 记录响应中的服务器时间和本地收到的时间，
+//! 两者之差即为延迟，下游可以据此提示或丢弃过时的数据。
+
+use crate::protocol::QuoteInfo;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, TimeZone, Utc};
+
+/// 响应的新鲜度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataFreshness {
+    /// 响应中的服务器时间（北京时间），响应不带服务器时间时为 None
+    pub server_time: Option<DateTime<FixedOffset>>,
+    /// 本地收到响应的时间
+    pub received_at: DateTime<Utc>,
+    /// 延迟（收到时间 - 服务器时间），服务器时间未知时为 None；本地时钟偏差也会计入
+    pub latency: Option<Duration>,
+}
+
+impl DataFreshness {
+    pub fn new(server_time: Option<DateTime<FixedOffset>>, received_at: DateTime<Utc>) -> Self {
+        Self {
+            server_time,
+            received_at,
+            latency: server_time.map(|t| received_at.signed_duration_since(t)),
+        }
+    }
+
+    /// 由一批行情计算：服务器时间取其中最新的一个
+    pub fn of_quotes(quotes: &[QuoteInfo], received_at: DateTime<Utc>) -> Self {
+        let beijing_offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        let date = received_at.with_timezone(&beijing_offset).date_naive();
+        let server_time = quotes
+            .iter()
+            .filter_map(|q| parse_server_time(&q.server_time, date))
+            .max();
+        Self::new(server_time, received_at)
+    }
+
+    /// 延迟是否超过 max（服务器时间未知时视为不过时）
+    pub fn is_stale(&self, max: Duration) -> bool {
+        self.latency.is_some_and(|latency| latency > max)
+    }
+}
+
+/// 解析行情中的服务器时间
+///
+/// 格式为 `HHMM` 后接4位的分钟内万分比，如 `13252999` 为 13:25 过 0.2999 分钟（17.994 秒）。
+/// 值为0表示没有服务器时间。
+pub fn parse_server_time(server_time: &str, date: NaiveDate) -> Option<DateTime<FixedOffset>> {
+    let value: u32 = server_time.parse().ok().filter(|&v| v > 0)?;
+    let hour = value / 1_000_000;
+    let minute = value / 10_000 % 100;
+    let millis = (value % 10_000) * 6;
+    let time = date.and_hms_milli_opt(hour, minute, millis / 1000, millis % 1000)?;
+    let beijing_offset = FixedOffset::east_opt(8 * 3600).unwrap();
+    beijing_offset.from_local_datetime(&time).single()
+}
+
+/// 一批行情中服务器时间的最大差值，可解析服务器时间的行情少于两条时为 None
+pub fn server_time_skew(quotes: &[QuoteInfo]) -> Option<Duration> {
+    let date = NaiveDate::default();
+    let times: Vec<_> = quotes
+        .iter()
+        .filter_map(|q| parse_server_time(&q.server_time, date))
+        .collect();
+    if times.len() < 2 {
+        return None;
+    }
+    Some(*times.iter().max()? - *times.iter().min()?)
+}
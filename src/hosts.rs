@@ -0,0 +1,217 @@
+//! 服务器地址列表加载与选择
+//!
+//! 支持社区维护的 JSON 格式服务器列表：
+//!
+//! ```json
+//! [
+//!   {"host": "124.71.187.122", "port": 7709, "region": "华东", "isp": "电信",
+//!    "capabilities": ["bj", "history"]}
+//! ]
+//! ```
+//!
+//! 除 `host` 外其余字段均可省略。内置列表（[`HostList::builtin`]）带有地区和运营商信息，
+//! 其他地区的服务器需要加载外部列表。
+
+use crate::dial::{fast_hosts, DEFAULT_HOSTS};
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// 能力标识：支持北交所
+pub const CAP_BJ: &str = "bj";
+
+/// 内置服务器的 (地址, 地区, 运营商)
+///
+/// 取自通达信客户端服务器列表中的站点名称，如“上海双线主站”记为华东、电信联通双线；
+/// 无法确认的留空。
+const BUILTIN_META: &[(&str, Option<&str>, Option<&str>)] = &[
+    ("124.71.187.122", Some("华东"), Some("双线")),
+    ("122.51.120.217", Some("华东"), Some("双线")),
+    ("111.229.247.189", None, None),
+    ("124.70.176.52", Some("华东"), Some("双线")),
+    ("123.60.186.45", Some("华东"), Some("双线")),
+    ("122.51.232.182", Some("华东"), Some("双线")),
+    ("118.25.98.114", Some("华东"), Some("双线")),
+    ("124.70.199.56", Some("华东"), Some("双线")),
+    ("121.36.225.169", Some("华东"), Some("双线")),
+    ("123.60.70.228", Some("华东"), Some("双线")),
+    ("123.60.73.44", Some("华东"), Some("双线")),
+    ("124.70.133.119", Some("华东"), Some("双线")),
+];
+
+/// 服务器列表错误
+#[derive(Debug, thiserror::Error)]
+pub enum HostListError {
+    #[error("IO错误: {0}")]
+    Io(#[from] io::Error),
+    #[error("解析错误: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// 服务器信息
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct HostEntry {
+    pub host: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub isp: Option<String>,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+impl HostEntry {
+    /// 完整地址（未指定端口时只返回 host，由连接时补全默认端口）
+    pub fn addr(&self) -> String {
+        match self.port {
+            Some(port) => format!("{}:{}", self.host, port),
+            None => self.host.clone(),
+        }
+    }
+
+    /// 是否具备指定能力
+    pub fn has_capability(&self, cap: &str) -> bool {
+        self.capabilities
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(cap))
+    }
+}
+
+/// 选择策略
+#[derive(Debug, Clone, Default)]
+pub struct SelectPolicy {
+    /// 优先选择的地区
+    pub region: Option<String>,
+    /// 优先选择的运营商
+    pub isp: Option<String>,
+    /// 必须具备的能力（如 [`CAP_BJ`]）
+    pub require: Vec<String>,
+}
+
+impl SelectPolicy {
+    /// 优先同地区
+    pub fn prefer_region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    /// 优先同运营商
+    pub fn prefer_isp(mut self, isp: &str) -> Self {
+        self.isp = Some(isp.to_string());
+        self
+    }
+
+    /// 要求具备指定能力
+    pub fn require(mut self, cap: &str) -> Self {
+        self.require.push(cap.to_string());
+        self
+    }
+}
+
+/// 服务器地址列表
+#[derive(Debug, Clone, Default)]
+pub struct HostList {
+    pub hosts: Vec<HostEntry>,
+}
+
+impl HostList {
+    /// 从 JSON 字符串解析
+    pub fn from_json(json: &str) -> Result<Self, HostListError> {
+        let hosts = serde_json::from_str(json)?;
+        Ok(Self { hosts })
+    }
+
+    /// 从 JSON 文件加载
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, HostListError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// 内置默认服务器列表（[`DEFAULT_HOSTS`]，带地区和运营商信息）
+    pub fn builtin() -> Self {
+        let meta = |host: &str| {
+            BUILTIN_META
+                .iter()
+                .find(|(h, _, _)| *h == host)
+                .map_or((None, None), |&(_, region, isp)| (region, isp))
+        };
+        Self {
+            hosts: DEFAULT_HOSTS
+                .iter()
+                .map(|h| {
+                    let (region, isp) = meta(h);
+                    HostEntry {
+                        host: h.to_string(),
+                        region: region.map(str::to_string),
+                        isp: isp.map(str::to_string),
+                        ..Default::default()
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// 按策略选择服务器地址
+    ///
+    /// 过滤掉不具备所需能力的服务器，同地区、同运营商的排在前面，
+    /// 其余保持原有顺序。
+    pub fn select(&self, policy: &SelectPolicy) -> Vec<String> {
+        let matches = |value: &Option<String>, want: &Option<String>| match (value, want) {
+            (Some(v), Some(w)) => v == w,
+            _ => false,
+        };
+
+        let mut hosts: Vec<&HostEntry> = self
+            .hosts
+            .iter()
+            .filter(|h| policy.require.iter().all(|cap| h.has_capability(cap)))
+            .collect();
+        // 稳定排序，保持原有顺序
+        hosts.sort_by_key(|h| {
+            (
+                !matches(&h.region, &policy.region),
+                !matches(&h.isp, &policy.isp),
+            )
+        });
+        hosts.into_iter().map(HostEntry::addr).collect()
+    }
+
+    /// 列表中出现的地区（按首次出现顺序）
+    pub fn regions(&self) -> Vec<&str> {
+        let mut regions: Vec<&str> = Vec::new();
+        for region in self.hosts.iter().filter_map(|h| h.region.as_deref()) {
+            if !regions.contains(&region) {
+                regions.push(region);
+            }
+        }
+        regions
+    }
+
+    /// 选择指定地区延迟最低的服务器
+    ///
+    /// 对该地区的服务器测试连接延迟，返回可连接的地址（host:port），按延迟升序；
+    /// 列表中没有该地区的服务器时测试全部服务器。
+    pub async fn select_by_region(&self, region: &str) -> Vec<String> {
+        let in_region: Vec<String> = self
+            .hosts
+            .iter()
+            .filter(|h| h.region.as_deref() == Some(region))
+            .map(HostEntry::addr)
+            .collect();
+        let addrs = if in_region.is_empty() {
+            self.hosts.iter().map(HostEntry::addr).collect()
+        } else {
+            in_region
+        };
+
+        let addrs: Vec<&str> = addrs.iter().map(String::as_str).collect();
+        if addrs.is_empty() {
+            return Vec::new();
+        }
+        fast_hosts(&addrs).await.iter().map(|r| r.addr()).collect()
+    }
+}
@@ -1,10 +1,75 @@
+pub mod adjust;
+pub mod align;
+pub mod archive;
+pub mod batch;
+pub mod block_index;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod classify;
 pub mod client;
+pub mod clock;
+pub mod code_table;
+pub mod cursor;
+pub mod delta;
 pub mod dial;
+pub mod events;
+pub mod export;
+pub mod factors;
+pub mod flow;
+pub mod freshness;
+pub mod hosts;
+#[cfg(feature = "test-data")]
+pub mod mock;
+pub mod prelude;
+pub mod profile;
 pub mod protocol;
+pub mod queue;
+pub mod rank;
+pub mod rate_limit;
+pub mod registry;
+pub mod resample;
+pub mod resilient;
+pub mod sink;
+pub mod stats;
+pub mod subscribe;
+pub mod ticksize;
 
-pub use client::{Client, ClientError};
-pub use dial::{dial, dial_default, dial_hosts_random, dial_hosts_range, fast_hosts, DialResult};
+pub use adjust::Adjustment;
+pub use archive::{Archive, ArchiveError, ArchiveReader, ArchiveRecord};
+pub use batch::{QuoteBatcher, DEFAULT_BATCH_WINDOW};
+pub use block_index::{BlockChange, BlockIndex, BlockIndexError};
+pub use classify::InferredSide;
+pub use client::{
+    current_request_id, traced, Client, ClientBuilder, ClientError, FrameObserver, RequestOptions,
+    SNAPSHOT_ATTEMPTS,
+};
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use code_table::{watch_code_table, CodeTable, CodeTableEvent};
+pub use cursor::{
+    sync_klines, CursorError, CursorStore, FileCursorStore, MemoryCursorStore, SyncCursor,
+};
+pub use delta::{DeltaError, QuoteDeltaDecoder, QuoteDeltaEncoder};
+pub use dial::{
+    choose_host, default_port, dial, dial_default, dial_hosts_random, dial_hosts_random_with,
+    dial_hosts_range, dial_hosts_seeded, dial_ports, fast_hosts, fast_hosts_ports, DialResult,
+    ALTERNATE_PORTS, DEFAULT_PORT,
+};
+pub use events::LifecycleEvent;
+pub use export::{export_klines, ExportConfig, ExportReport};
+pub use flow::{DailyFlow, FlowHistory};
+pub use freshness::DataFreshness;
+pub use hosts::{HostEntry, HostList, HostListError, SelectPolicy};
+pub use profile::{volume_by_price, VolumeProfile};
 pub use protocol::*;
+pub use rank::{RankEntry, RankKey, RankService, Ranking};
+pub use rate_limit::RateLimiter;
+pub use resilient::ResilientClient;
+pub use sink::{CsvSink, JsonLinesSink, Sink, SinkError};
+pub use stats::{StatsSnapshot, SymbolStats};
+pub use subscribe::{
+    stream_trades, stream_trades_resumable, subscribe_quotes, ShardedSubscriber, TradeCursor,
+};
+pub use ticksize::{snap_price, tick_for};
 
 // 重新导出 log 宏供用户使用
 pub use log;
@@ -0,0 +1,207 @@
+//! 模拟服务器（需要开启 `test-data` 特性）
+//!
+//! 在本地端口上按消息类型应答请求，用于不联网地测试客户端的分页、解码和重连逻辑。
+//! 应答可以是固定数据、根据请求数据生成，或直接使用 `tdx-test/test-data` 中抓取的响应。
+//! 未注册的消息类型返回空数据；[`MockServerBuilder::reject`] 可以模拟服务器返回错误。
+
+use crate::client::{Client, ClientError};
+use crate::protocol::test_data::TestData;
+use crate::protocol::{
+    bytes_to_u16_le, utf8_to_gbk, MessageType, ResponseFrame, CONTROL_RESP_ERROR,
+    CONTROL_RESP_SUCCESS, PREFIX_RESP,
+};
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// 应答函数：根据请求数据（不含消息类型）返回响应数据
+pub type MockHandler = Arc<dyn Fn(&[u8]) -> Vec<u8> + Send + Sync>;
+
+/// 收到的请求：(消息类型, 请求数据)
+type RequestLog = Arc<Mutex<Vec<(MessageType, Vec<u8>)>>>;
+
+/// 带真实响应数据的测试数据文件
+const FIXTURES: &[(&str, MessageType)] = &[
+    ("connect", MessageType::Connect),
+    ("count", MessageType::Count),
+    ("quote", MessageType::Quote),
+    ("kline", MessageType::Kline),
+];
+
+/// 模拟服务器构建器，见 [`MockServer::builder`]
+#[derive(Default)]
+pub struct MockServerBuilder {
+    handlers: HashMap<MessageType, MockHandler>,
+    errors: HashMap<MessageType, Vec<u8>>,
+}
+
+impl MockServerBuilder {
+    /// 对某类消息总是返回固定数据
+    pub fn respond(self, msg_type: MessageType, data: Vec<u8>) -> Self {
+        self.handle(msg_type, move |_| data.clone())
+    }
+
+    /// 对某类消息按请求数据生成响应
+    pub fn handle(
+        mut self,
+        msg_type: MessageType,
+        handler: impl Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) -> Self {
+        self.handlers.insert(msg_type, Arc::new(handler));
+        self
+    }
+
+    /// 对某类消息返回错误响应（错误控制码，数据域为 GBK 编码的错误信息），优先于应答函数
+    pub fn reject(mut self, msg_type: MessageType, message: &str) -> Self {
+        self.errors.insert(msg_type, utf8_to_gbk(message));
+        self
+    }
+
+    /// 使用 `tdx-test/test-data` 中的真实响应应答连接、数量、行情和K线请求
+    pub fn with_fixtures(mut self) -> io::Result<Self> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tdx-test/test-data");
+        for &(name, msg_type) in FIXTURES {
+            let content = std::fs::read_to_string(dir.join(format!("{}.json", name)))?;
+            let test_data: TestData = serde_json::from_str(&content).map_err(io::Error::other)?;
+            let data = fixture_data(&test_data).map_err(io::Error::other)?;
+            self = self.respond(msg_type, data);
+        }
+        Ok(self)
+    }
+
+    /// 在随机端口上启动
+    pub async fn start(self) -> io::Result<MockServer> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?.to_string();
+        let handlers = Arc::new(self.handlers);
+        let errors = Arc::new(self.errors);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let log = requests.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, handlers.clone(), errors.clone(), log.clone()));
+            }
+        });
+        Ok(MockServer {
+            addr,
+            requests,
+            task,
+        })
+    }
+}
+
+/// 测试数据中的响应数据域：完整响应帧可用时取帧的数据，否则使用解压后的响应数据
+fn fixture_data(test_data: &TestData) -> Result<Vec<u8>, String> {
+    let frame = test_data
+        .decode_response()
+        .ok()
+        .and_then(|bytes| ResponseFrame::decode(&bytes).ok());
+    if let Some(frame) = frame {
+        return Ok(frame.data);
+    }
+    test_data
+        .decode_response_data()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("{} 没有响应数据", test_data.name))
+}
+
+/// 模拟服务器，丢弃时停止监听
+pub struct MockServer {
+    addr: String,
+    requests: RequestLog,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    pub fn builder() -> MockServerBuilder {
+        MockServerBuilder::default()
+    }
+
+    /// 监听地址
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// 连接到模拟服务器
+    pub async fn client(&self) -> Result<Client, ClientError> {
+        Client::connect(&self.addr).await
+    }
+
+    /// 已收到的请求：(消息类型, 请求数据)，按收到的顺序
+    pub fn requests(&self) -> Vec<(MessageType, Vec<u8>)> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// 已收到的某类请求的数量
+    pub fn count(&self, msg_type: MessageType) -> usize {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(t, _)| *t == msg_type)
+            .count()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve(
+    mut stream: TcpStream,
+    handlers: Arc<HashMap<MessageType, MockHandler>>,
+    errors: Arc<HashMap<MessageType, Vec<u8>>>,
+    requests: RequestLog,
+) {
+    loop {
+        let mut header = [0u8; 10];
+        if stream.read_exact(&mut header).await.is_err() {
+            return;
+        }
+        let length = bytes_to_u16_le(&header[6..8]) as usize;
+        let mut body = vec![0u8; length];
+        if length < 2 || stream.read_exact(&mut body).await.is_err() {
+            return;
+        }
+
+        let type_value = bytes_to_u16_le(&body[0..2]);
+        let (control, data) = match MessageType::from_u16(type_value) {
+            Some(msg_type) => {
+                requests
+                    .lock()
+                    .unwrap()
+                    .push((msg_type, body[2..].to_vec()));
+                match errors.get(&msg_type) {
+                    Some(message) => (CONTROL_RESP_ERROR, message.clone()),
+                    None => (
+                        CONTROL_RESP_SUCCESS,
+                        handlers
+                            .get(&msg_type)
+                            .map(|h| h(&body[2..]))
+                            .unwrap_or_default(),
+                    ),
+                }
+            }
+            None => (CONTROL_RESP_SUCCESS, Vec::new()),
+        };
+
+        let mut frame = PREFIX_RESP.to_be_bytes().to_vec();
+        frame.push(control);
+        frame.extend_from_slice(&header[1..5]);
+        frame.push(0);
+        frame.extend_from_slice(&type_value.to_le_bytes());
+        frame.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        frame.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        frame.extend_from_slice(&data);
+        if stream.write_all(&frame).await.is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,15 @@
+//! 常用类型的统一导入：`use tdx_rust::prelude::*;`
+//!
+//! 这里列出的是稳定的高层接口：客户端、连接方式、请求参数和响应类型。
+//! 0.x 版本内这些名称只会新增，不会删除或改名；需要调整时先标记 `#[deprecated]`，
+//! 保留至少一个次版本后再移除。协议帧、编解码函数等底层细节不在此列，可能随协议研究调整。
+
+pub use crate::adjust::Adjustment;
+pub use crate::client::{Client, ClientBuilder, ClientError, RequestOptions};
+pub use crate::dial::{dial, dial_default, dial_hosts_random, dial_hosts_range};
+pub use crate::protocol::{
+    add_prefix, Amount, CallAuction, CallAuctionResponse, CodeResponse, Exchange, Gbbq,
+    GbbqResponse, InstrumentKind, Kline, KlineResponse, KlineType, MinuteResponse, Price,
+    QuoteInfo, ServerInfo, StockCode, TickDetail, TickDetailResponse, Trade, TradeResponse,
+    TradeStatus,
+};
@@ -0,0 +1,91 @@
+//! 成交量分布（分价成交量）
+//!
+//! 把一组逐笔成交按价格区间汇总成交量，并计算成交量最大的价位（POC）
+//! 和包含指定比例成交量的价值区域（Value Area）。
+
+use crate::protocol::{Price, Trade};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// 默认价值区域比例（70%）
+pub const DEFAULT_VALUE_AREA: f64 = 0.7;
+
+/// 成交量分布
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VolumeProfile {
+    /// 价格区间宽度
+    pub bucket: Price,
+    /// (价格区间下界, 成交量手)，按价格升序，不含无成交的区间
+    pub levels: Vec<(Price, i64)>,
+    /// 总成交量（手）
+    pub total: i64,
+}
+
+impl VolumeProfile {
+    /// 成交量最大的价格区间下界（Point of Control），成交量相同时取价格较低者
+    pub fn poc(&self) -> Option<Price> {
+        self.poc_index().map(|i| self.levels[i].0)
+    }
+
+    fn poc_index(&self) -> Option<usize> {
+        let mut best: Option<usize> = None;
+        for (i, &(_, volume)) in self.levels.iter().enumerate() {
+            if best.is_none_or(|b| volume > self.levels[b].1) {
+                best = Some(i);
+            }
+        }
+        best
+    }
+
+    /// 价值区域：从 POC 开始，每次向成交量较大的一侧扩展一个区间，
+    /// 直到包含 ratio 比例的成交量。返回价格区间 [下界, 上界)。
+    pub fn value_area(&self, ratio: f64) -> Option<(Price, Price)> {
+        let poc = self.poc_index()?;
+        let target = (self.total as f64 * ratio.clamp(0.0, 1.0)).ceil() as i64;
+
+        let (mut low, mut high) = (poc, poc);
+        let mut covered = self.levels[poc].1;
+        while covered < target {
+            let below = low.checked_sub(1).map(|i| self.levels[i].1);
+            let above = self.levels.get(high + 1).map(|l| l.1);
+            match (below, above) {
+                (Some(b), Some(a)) if a >= b => {
+                    high += 1;
+                    covered += a;
+                }
+                (Some(b), _) => {
+                    low -= 1;
+                    covered += b;
+                }
+                (None, Some(a)) => {
+                    high += 1;
+                    covered += a;
+                }
+                (None, None) => break,
+            }
+        }
+        let top = self.levels[high].0;
+        Some((self.levels[low].0, Price(top.0 + self.bucket.0)))
+    }
+}
+
+/// 按价格区间统计成交量
+///
+/// bucket: 价格区间宽度（小于1厘时按1厘），区间下界为 bucket 的整数倍。
+pub fn volume_by_price(trades: &[Trade], bucket: Price) -> VolumeProfile {
+    let width = bucket.0.max(1);
+    let mut levels: BTreeMap<i64, i64> = BTreeMap::new();
+    let mut total = 0i64;
+    for trade in trades {
+        let volume = trade.volume as i64;
+        *levels
+            .entry(trade.price.0.div_euclid(width) * width)
+            .or_insert(0) += volume;
+        total += volume;
+    }
+    VolumeProfile {
+        bucket: Price(width),
+        levels: levels.into_iter().map(|(p, v)| (Price(p), v)).collect(),
+        total,
+    }
+}
@@ -1,5 +1,7 @@
 //! 协议常量定义
 
+use serde::Serialize;
+
 /// 请求帧固定前缀
 pub const PREFIX: u8 = 0x0C;
 
@@ -8,7 +10,7 @@ pub const PREFIX_RESP: u32 = 0xB1CB7400;
 
 /// 消息类型常量
 #[repr(u16)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MessageType {
     Connect = 0x000D,            // 建立连接
     Heart = 0x0004,              // 心跳
@@ -56,7 +58,7 @@ pub enum KlineType {
     Minute15 = 1,     // 15分钟K线
     Minute30 = 2,     // 30分钟K线
     Minute60 = 3,     // 60分钟K线（1小时）
-    Day2 = 4,         // 日K线（需除以100）
+    Day2 = 4,         // 日K线（变体，格式与日K线相同）
     Week = 5,         // 周K线
     Month = 6,        // 月K线
     Minute = 7,       // 1分钟K线
@@ -68,7 +70,7 @@ pub enum KlineType {
 
 /// 交易所类型
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum Exchange {
     SZ = 0,  // 深圳交易所
     SH = 1,  // 上海交易所
@@ -118,3 +120,38 @@ impl Control {
         self as u8
     }
 }
+
+/// 单次行情请求的最大代码数量
+pub const QUOTE_MAX_CODES: usize = 80;
+
+/// 响应控制码：成功
+pub const CONTROL_RESP_SUCCESS: u8 = 0x1C;
+
+/// 响应控制码：错误
+pub const CONTROL_RESP_ERROR: u8 = 0x0C;
+
+/// 响应控制码中的成功标志位
+pub const CONTROL_RESP_SUCCESS_FLAG: u8 = 0x10;
+
+/// 响应类型（由响应控制码判断）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseKind {
+    /// 成功
+    Success,
+    /// 服务器返回错误
+    Error,
+    /// 未知控制码
+    Unknown(u8),
+}
+
+impl ResponseKind {
+    /// 根据响应控制码判断响应类型
+    pub fn from_control(control: u8) -> Self {
+        match control {
+            CONTROL_RESP_SUCCESS => ResponseKind::Success,
+            CONTROL_RESP_ERROR => ResponseKind::Error,
+            c if c & CONTROL_RESP_SUCCESS_FLAG != 0 => ResponseKind::Success,
+            c => ResponseKind::Unknown(c),
+        }
+    }
+}
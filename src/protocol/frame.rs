@@ -1,7 +1,7 @@
 //! 协议帧格式定义和编解码
 
 use crate::protocol::{
-    constants::{Control, MessageType, PREFIX},
+    constants::{Control, MessageType, ResponseKind, PREFIX},
     codec::{bytes_to_u16_le, bytes_to_u32_le, u16_to_bytes_le, u32_to_bytes_le},
 };
 use flate2::read::ZlibDecoder;
@@ -30,29 +30,17 @@ impl RequestFrame {
 
     /// 编码为字节数组
     pub fn encode(&self) -> Vec<u8> {
-        let length = (self.data.len() + 2) as u16;
-        let mut result = Vec::with_capacity(12 + self.data.len());
-
-        // Prefix
-        result.push(PREFIX);
-
-        // MsgID (小端序)
-        result.extend_from_slice(&u32_to_bytes_le(self.msg_id));
-
-        // Control
-        result.push(self.control.as_u8());
-
-        // Length (重复两次，小端序)
-        result.extend_from_slice(&u16_to_bytes_le(length));
-        result.extend_from_slice(&u16_to_bytes_le(length));
-
-        // Type (小端序)
-        result.extend_from_slice(&u16_to_bytes_le(self.msg_type.as_u16()));
-
-        // Data
-        result.extend_from_slice(&self.data);
+        encode_parts(
+            self.msg_id,
+            self.control,
+            self.msg_type.as_u16(),
+            &self.data,
+        )
+    }
 
-        result
+    /// 以任意类型值编码请求帧，用于本库未定义的消息类型
+    pub fn encode_raw(msg_id: u32, msg_type: u16, data: &[u8]) -> Vec<u8> {
+        encode_parts(msg_id, Control::Control01, msg_type, data)
     }
 
     /// 从字节数组解码
@@ -96,6 +84,32 @@ impl RequestFrame {
     }
 }
 
+fn encode_parts(msg_id: u32, control: Control, msg_type: u16, data: &[u8]) -> Vec<u8> {
+    let length = (data.len() + 2) as u16;
+    let mut result = Vec::with_capacity(12 + data.len());
+
+    // Prefix
+    result.push(PREFIX);
+
+    // MsgID (小端序)
+    result.extend_from_slice(&u32_to_bytes_le(msg_id));
+
+    // Control
+    result.push(control.as_u8());
+
+    // Length (重复两次，小端序)
+    result.extend_from_slice(&u16_to_bytes_le(length));
+    result.extend_from_slice(&u16_to_bytes_le(length));
+
+    // Type (小端序)
+    result.extend_from_slice(&u16_to_bytes_le(msg_type));
+
+    // Data
+    result.extend_from_slice(data);
+
+    result
+}
+
 /// 响应帧
 #[derive(Debug, Clone)]
 pub struct ResponseFrame {
@@ -141,21 +155,7 @@ impl ResponseFrame {
             return Ok(());
         }
 
-        // 如果压缩长度 != 未压缩长度，需要解压
-        if self.zip_length != self.length {
-            let mut decoder = ZlibDecoder::new(self.data.as_slice());
-            let mut decompressed = Vec::with_capacity(self.length as usize);
-            decoder
-                .read_to_end(&mut decompressed)
-                .map_err(|e| FrameError::DecompressionError(e.to_string()))?;
-            self.data = decompressed;
-        }
-
-        // 验证解压后的数据长度
-        if self.data.len() != self.length as usize {
-            return Err(FrameError::LengthMismatch);
-        }
-
+        self.data = inflate(std::mem::take(&mut self.data), self.zip_length, self.length)?;
         self.decompressed = true;
         Ok(())
     }
@@ -215,10 +215,39 @@ impl ResponseFrame {
         Ok(frame)
     }
 
+    /// 响应类型
+    pub fn kind(&self) -> ResponseKind {
+        ResponseKind::from_control(self.control)
+    }
+
     /// 检查响应是否成功
     pub fn is_success(&self) -> bool {
-        self.control & 0x10 == 0x10
+        self.kind() == ResponseKind::Success
+    }
+
+    /// 数据是否经过 zlib 压缩
+    pub fn is_compressed(&self) -> bool {
+        self.zip_length != self.length
+    }
+}
+
+/// 解压响应数据域：压缩长度 != 未压缩长度时按 zlib 解压，并验证解压后的长度
+pub fn inflate(data: Vec<u8>, zip_length: u16, length: u16) -> Result<Vec<u8>, FrameError> {
+    let data = if zip_length != length {
+        let mut decoder = ZlibDecoder::new(data.as_slice());
+        let mut decompressed = Vec::with_capacity(length as usize);
+        decoder
+            .read_to_end(&mut decompressed)
+            .map_err(|e| FrameError::DecompressionError(e.to_string()))?;
+        decompressed
+    } else {
+        data
+    };
+
+    if data.len() != length as usize {
+        return Err(FrameError::LengthMismatch);
     }
+    Ok(data)
 }
 
 /// 帧错误类型
@@ -0,0 +1,443 @@
+//! 消息线上格式描述
+//!
+//! 以表格形式记录每种消息的请求/响应字段布局（字段名、偏移、编码、含义），
+//! 与 `messages.rs` 中的编解码实现一一对应。修改解码逻辑时请同步更新这里，
+//! 可以通过 [`describe`] 打印成文档。
+
+use crate::protocol::constants::MessageType;
+use std::fmt::Write;
+
+/// 字段编码方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// 单字节无符号整数
+    U8,
+    /// 2字节小端序无符号整数
+    U16,
+    /// 2字节小端序有符号整数
+    I16,
+    /// 4字节小端序无符号整数
+    U32,
+    /// 4字节小端序浮点数
+    F32,
+    /// 变长整数（见 `decode_varint`）
+    Varint,
+    /// 变长价格差值（见 `decode_price`）
+    Price,
+    /// 4字节特殊浮点编码（见 `decode_volume2`）
+    Volume,
+    /// 固定长度 ASCII 字符串
+    Ascii(usize),
+    /// 固定长度 GBK 字符串
+    Gbk(usize),
+    /// 固定长度字节（常量或未知）
+    Bytes(usize),
+    /// 直到数据末尾的 GBK 字符串
+    GbkRest,
+}
+
+impl Encoding {
+    /// 固定长度（变长编码返回 None）
+    pub fn size(self) -> Option<usize> {
+        match self {
+            Encoding::U8 => Some(1),
+            Encoding::U16 | Encoding::I16 => Some(2),
+            Encoding::U32 | Encoding::F32 | Encoding::Volume => Some(4),
+            Encoding::Ascii(n) | Encoding::Gbk(n) | Encoding::Bytes(n) => Some(n),
+            Encoding::Varint | Encoding::Price | Encoding::GbkRest => None,
+        }
+    }
+
+    /// 编码名称
+    pub fn name(self) -> String {
+        match self {
+            Encoding::U8 => "u8".to_string(),
+            Encoding::U16 => "u16le".to_string(),
+            Encoding::I16 => "i16le".to_string(),
+            Encoding::U32 => "u32le".to_string(),
+            Encoding::F32 => "f32le".to_string(),
+            Encoding::Varint => "varint".to_string(),
+            Encoding::Price => "price".to_string(),
+            Encoding::Volume => "volume".to_string(),
+            Encoding::Ascii(n) => format!("ascii[{}]", n),
+            Encoding::Gbk(n) => format!("gbk[{}]", n),
+            Encoding::Bytes(n) => format!("bytes[{}]", n),
+            Encoding::GbkRest => "gbk[..]".to_string(),
+        }
+    }
+}
+
+/// 字段描述
+#[derive(Debug, Clone, Copy)]
+pub struct Field {
+    pub name: &'static str,
+    pub encoding: Encoding,
+    pub meaning: &'static str,
+}
+
+const fn field(name: &'static str, encoding: Encoding, meaning: &'static str) -> Field {
+    Field {
+        name,
+        encoding,
+        meaning,
+    }
+}
+
+/// 消息格式描述
+///
+/// 请求/响应的数据域由 `*_header` 和重复的 `*_record` 组成，
+/// 记录数量由头部的 count 字段决定。
+#[derive(Debug, Clone, Copy)]
+pub struct MessageLayout {
+    pub msg_type: MessageType,
+    pub name: &'static str,
+    pub request_header: &'static [Field],
+    pub request_record: &'static [Field],
+    pub response_header: &'static [Field],
+    pub response_record: &'static [Field],
+    pub notes: &'static str,
+}
+
+use Encoding::*;
+
+const CODE_REQUEST: &[Field] = &[
+    field("exchange", U8, "交易所（0=深圳，1=上海，2=北京）"),
+    field("reserved", U8, "固定 0x00"),
+    field("code", Ascii(6), "股票代码"),
+];
+
+const MINUTE_RECORD: &[Field] = &[
+    field("price", Price, "价格差值，累加后乘以10为厘"),
+    field("unknown", Price, "未知"),
+    field("number", Varint, "成交量（手）"),
+];
+
+const LAYOUTS: &[MessageLayout] = &[
+    MessageLayout {
+        msg_type: MessageType::Connect,
+        name: "建立连接",
+        request_header: &[field("data", Bytes(1), "固定 0x01")],
+        request_record: &[],
+        response_header: &[
+            field("unknown", Bytes(9), "未知"),
+            field("open1", U16, "第1个交易时段开始（当天的分钟数）"),
+            field("close1", U16, "第1个交易时段结束"),
+            field("open2", U16, "第2个交易时段开始"),
+            field("close2", U16, "第2个交易时段结束"),
+            field("open3", U16, "第3个交易时段开始"),
+            field("close3", U16, "第3个交易时段结束"),
+            field("open4", U16, "第4个交易时段开始"),
+            field("close4", U16, "第4个交易时段结束"),
+            field("hours2", Bytes(16), "第二组交易时段，样本中与第一组相同"),
+            field("unknown", Bytes(1), "未知"),
+            field("date", U32, "服务器日期 YYYYMMDD"),
+            field("time", U32, "服务器时间 HHMMSS"),
+            field("unknown", Bytes(18), "未知"),
+            field("info", GbkRest, "服务器名称和公告，以控制字符分隔"),
+        ],
+        response_record: &[],
+        notes: "交易时段开始等于结束表示未使用",
+    },
+    MessageLayout {
+        msg_type: MessageType::Heart,
+        name: "心跳",
+        request_header: &[],
+        request_record: &[],
+        response_header: &[],
+        response_record: &[],
+        notes: "请求和响应数据域均为空",
+    },
+    MessageLayout {
+        msg_type: MessageType::Count,
+        name: "获取股票数量",
+        request_header: &[
+            field("exchange", U8, "交易所"),
+            field("reserved", U8, "固定 0x00"),
+            field("unknown", Bytes(4), "固定 75 C7 33 01"),
+        ],
+        request_record: &[],
+        response_header: &[field("count", U16, "股票数量")],
+        response_record: &[],
+        notes: "",
+    },
+    MessageLayout {
+        msg_type: MessageType::Code,
+        name: "获取股票代码",
+        request_header: &[
+            field("exchange", U8, "交易所"),
+            field("reserved", U8, "固定 0x00"),
+            field("start", U16, "起始位置（每次最多返回1000条）"),
+        ],
+        request_record: &[],
+        response_header: &[field("count", U16, "记录数量")],
+        response_record: &[
+            field("code", Ascii(6), "股票代码"),
+            field("multiple", U16, "倍数，通常为100"),
+            field("name", Gbk(8), "股票名称"),
+            field("unknown", Bytes(4), "未知"),
+            field("decimal", U8, "小数位数"),
+            field("last_price", Volume, "昨收价（元，指数有效）"),
+            field("unknown", Bytes(4), "未知"),
+        ],
+        notes: "",
+    },
+    MessageLayout {
+        msg_type: MessageType::Quote,
+        name: "行情信息",
+        request_header: &[
+            field("header", Bytes(8), "固定 05 00 00 00 00 00 00 00"),
+            field("count", U16, "股票数量"),
+        ],
+        request_record: &[
+            field("exchange", U8, "交易所"),
+            field("code", Ascii(6), "股票代码"),
+        ],
+        response_header: &[
+            field("unknown", Bytes(2), "未知"),
+            field("count", U16, "记录数量"),
+        ],
+        response_record: &[
+            field("exchange", U8, "交易所"),
+            field("code", Ascii(6), "股票代码"),
+            field("active1", U16, "活跃度"),
+            field("close", Price, "现价（×10为厘）"),
+            field("last", Price, "昨收，相对现价的差值"),
+            field("open", Price, "开盘，相对现价的差值"),
+            field("high", Price, "最高，相对现价的差值"),
+            field("low", Price, "最低，相对现价的差值"),
+            field("reversed0", Varint, "服务器时间"),
+            field("reversed1", Varint, "未知"),
+            field("total_hand", Varint, "总手"),
+            field("intuition", Varint, "现量"),
+            field("amount", Volume, "金额（元）"),
+            field("inside_dish", Varint, "内盘"),
+            field("outer_disc", Varint, "外盘"),
+            field("reversed2", Varint, "未知"),
+            field("reversed3", Varint, "未知"),
+            field(
+                "levels",
+                Price,
+                "5组：买价差、卖价差(price)、买量、卖量(varint)",
+            ),
+            field("reversed4", Bytes(2), "未知"),
+            field("reversed5_8", Varint, "4个变长整数，未知"),
+            field("rate", U16, "涨速（÷100）"),
+            field("active2", U16, "活跃度"),
+        ],
+        notes: "5档价格 = 差值×10 + 现价",
+    },
+    MessageLayout {
+        msg_type: MessageType::Minute,
+        name: "分时数据",
+        request_header: &[
+            CODE_REQUEST[0],
+            CODE_REQUEST[1],
+            CODE_REQUEST[2],
+            field("reserved", Bytes(4), "固定 0"),
+        ],
+        request_record: &[],
+        response_header: &[
+            field("count", U16, "记录数量"),
+            field("unknown", Bytes(4), "未知"),
+        ],
+        response_record: MINUTE_RECORD,
+        notes: "时间从09:31开始逐分钟递增，第120条之后从13:01继续",
+    },
+    MessageLayout {
+        msg_type: MessageType::HistoryMinute,
+        name: "历史分时数据",
+        request_header: &[
+            field("date", U32, "日期 YYYYMMDD"),
+            field("exchange", U8, "交易所"),
+            field("code", Ascii(6), "股票代码"),
+        ],
+        request_record: &[],
+        response_header: &[
+            field("count", U16, "记录数量"),
+            field("unknown", Bytes(4), "未知"),
+        ],
+        response_record: MINUTE_RECORD,
+        notes: "与分时数据格式相同",
+    },
+    MessageLayout {
+        msg_type: MessageType::CallAuction,
+        name: "集合竞价",
+        request_header: &[
+            CODE_REQUEST[0],
+            CODE_REQUEST[1],
+            CODE_REQUEST[2],
+            field("unknown", Bytes(20), "固定参数"),
+        ],
+        request_record: &[],
+        response_header: &[field("count", U16, "记录数量")],
+        response_record: &[
+            field("time", U16, "小时×60+分钟"),
+            field("price", F32, "价格（元）"),
+            field("matched", U32, "匹配量"),
+            field("unmatched", I16, "未匹配量，负数表示卖单"),
+            field("unknown", Bytes(3), "未知"),
+            field("second", U8, "秒"),
+        ],
+        notes: "",
+    },
+    MessageLayout {
+        msg_type: MessageType::MinuteTrade,
+        name: "分时成交",
+        request_header: &[
+            CODE_REQUEST[0],
+            CODE_REQUEST[1],
+            CODE_REQUEST[2],
+            field("start", U16, "起始位置"),
+            field("count", U16, "数量（最多1800）"),
+        ],
+        request_record: &[],
+        response_header: &[field("count", U16, "记录数量")],
+        response_record: &[
+            field("time", U16, "小时×60+分钟"),
+            field("price", Price, "价格差值（分），累加"),
+            field("volume", Varint, "成交量（手）"),
+            field("number", Varint, "单数"),
+            field("status", Varint, "0=买，1=卖，2=中性"),
+            field("unknown", Varint, "未知"),
+        ],
+        notes: "",
+    },
+    MessageLayout {
+        msg_type: MessageType::HistoryMinuteTrade,
+        name: "历史分时成交",
+        request_header: &[
+            field("date", U32, "日期 YYYYMMDD"),
+            CODE_REQUEST[0],
+            CODE_REQUEST[1],
+            CODE_REQUEST[2],
+            field("start", U16, "起始位置"),
+            field("count", U16, "数量（最多2000）"),
+        ],
+        request_record: &[],
+        response_header: &[
+            field("count", U16, "记录数量"),
+            field("unknown", Bytes(4), "未知"),
+        ],
+        response_record: &[
+            field("time", U16, "小时×60+分钟"),
+            field("price", Price, "价格差值（分），累加"),
+            field("volume", Varint, "成交量（手）"),
+            field("status", Varint, "0=买，1=卖，2=中性"),
+            field("unknown", Varint, "未知"),
+        ],
+        notes: "",
+    },
+    MessageLayout {
+        msg_type: MessageType::Kline,
+        name: "K线",
+        request_header: &[
+            CODE_REQUEST[0],
+            CODE_REQUEST[1],
+            CODE_REQUEST[2],
+            field("kline_type", U8, "K线类型"),
+            field("reserved", U8, "固定 0x00"),
+            field("unknown", Bytes(2), "固定 01 00"),
+            field("start", U16, "起始位置"),
+            field("count", U16, "数量（最多800）"),
+            field("unknown", Bytes(10), "固定 0"),
+        ],
+        request_record: &[],
+        response_header: &[field("count", U16, "记录数量")],
+        response_record: &[
+            field("time", U32, "分钟级为压缩日期+分钟，日线及以上为 YYYYMMDD"),
+            field("open", Price, "相对上一根收盘价的差值（厘）"),
+            field("close", Price, "相对开盘价的差值"),
+            field("high", Price, "相对开盘价的差值"),
+            field("low", Price, "相对开盘价的差值"),
+            field("volume", Volume, "成交量"),
+            field("amount", Volume, "成交额（元）"),
+            field("up_count", U16, "上涨家数（仅指数）"),
+            field("down_count", U16, "下跌家数（仅指数）"),
+        ],
+        notes: "分钟级K线成交量需除以100，指数成交量需乘以100",
+    },
+    MessageLayout {
+        msg_type: MessageType::Gbbq,
+        name: "股本变迁",
+        request_header: &[
+            field("unknown", Bytes(2), "固定 01 00"),
+            field("exchange", U8, "交易所"),
+            field("code", Ascii(6), "股票代码"),
+        ],
+        request_record: &[],
+        response_header: &[
+            field("unknown", Bytes(9), "未知"),
+            field("count", U16, "记录数量"),
+        ],
+        response_record: &[
+            field("exchange", U8, "交易所"),
+            field("code", Ascii(6), "股票代码"),
+            field("unknown", U8, "未知"),
+            field("date", U32, "日期 YYYYMMDD"),
+            field("category", U8, "类别"),
+            field("c1", F32, "分红/前流通（类别不同编码不同）"),
+            field("c2", F32, "配股价/前总股本"),
+            field("c3", F32, "送转股/后流通"),
+            field("c4", F32, "配股/后总股本"),
+        ],
+        notes: "股本变化类记录的 c1~c4 使用 volume 编码并乘以10000",
+    },
+];
+
+/// 获取消息格式描述
+pub fn layout(msg_type: MessageType) -> Option<&'static MessageLayout> {
+    LAYOUTS.iter().find(|l| l.msg_type == msg_type)
+}
+
+/// 全部已知消息格式
+pub fn layouts() -> &'static [MessageLayout] {
+    LAYOUTS
+}
+
+fn write_fields(out: &mut String, title: &str, fields: &[Field]) {
+    if fields.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "  {}:", title);
+    let mut offset = Some(0usize);
+    for f in fields {
+        let pos = match offset {
+            Some(o) => format!("{:>4}", o),
+            None => "   ?".to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "    {} {:<14} {:<10} {}",
+            pos,
+            f.name,
+            f.encoding.name(),
+            f.meaning
+        );
+        offset = match (offset, f.encoding.size()) {
+            (Some(o), Some(n)) => Some(o + n),
+            _ => None,
+        };
+    }
+}
+
+/// 生成消息格式的文本描述
+///
+/// 偏移为相对所在区块（头部或单条记录）起始的字节数，
+/// 变长字段之后的偏移无法确定，显示为 `?`。
+pub fn describe(msg_type: MessageType) -> String {
+    let mut out = String::new();
+    let Some(l) = layout(msg_type) else {
+        let _ = writeln!(out, "0x{:04X} 未知消息", msg_type.as_u16());
+        return out;
+    };
+
+    let _ = writeln!(out, "0x{:04X} {:?} {}", msg_type.as_u16(), msg_type, l.name);
+    write_fields(&mut out, "请求", l.request_header);
+    write_fields(&mut out, "请求记录 × count", l.request_record);
+    write_fields(&mut out, "响应", l.response_header);
+    write_fields(&mut out, "响应记录 × count", l.response_record);
+    if !l.notes.is_empty() {
+        let _ = writeln!(out, "  说明: {}", l.notes);
+    }
+    out
+}
@@ -5,15 +5,17 @@ use crate::protocol::{
         bytes_to_u16_le, bytes_to_u32_le, decode_price, decode_varint, decode_volume2, gbk_to_utf8,
         u16_to_bytes_le, u32_to_bytes_le,
     },
-    constants::{Exchange, KlineType, MessageType},
+    constants::{Exchange, KlineType, MessageType, QUOTE_MAX_CODES},
     frame::RequestFrame,
     types::{
-        CallAuction, CallAuctionResponse, Gbbq, GbbqResponse, Kline, KlineCache, KlineResponse,
-        MinuteResponse, Price, PriceLevel, PriceNumber, QuoteInfo, StockCode, Trade, TradeResponse,
-        TradeStatus, K,
+        Amount, CallAuction, CallAuctionResponse, Gbbq, GbbqResponse, InstrumentKind, Kline,
+        KlineCache, KlineResponse, MinuteResponse, OrderedQuotes, Price, PriceLevel, PriceNumber,
+        QuoteInfo, ServerInfo, Session, StockCode, TickDetail, TickDetailResponse, Trade,
+        TradeResponse, TradeStatus, K,
     },
 };
-use chrono::{Datelike, FixedOffset, TimeZone, Utc};
+use chrono::{FixedOffset, TimeZone, Utc};
+use std::collections::HashMap;
 use thiserror::Error;
 
 /// 消息编解码错误
@@ -25,6 +27,8 @@ pub enum MessageError {
     InvalidCode(String),
     #[error("解析错误: {0}")]
     ParseError(String),
+    #[error("代码数量 {0} 超过单次请求上限 {QUOTE_MAX_CODES}")]
+    TooManyCodes(usize),
 }
 
 /// 连接消息
@@ -41,10 +45,51 @@ impl Connect {
         if data.len() < 68 {
             return Err(MessageError::InsufficientData);
         }
-        // 前68字节未知，后续为GBK编码的字符串信息
+        // 前68字节为二进制字段（见 decode_info），后续为GBK编码的字符串信息
         let info = gbk_to_utf8(&data[68..]);
         Ok(info)
     }
+
+    /// 解码连接响应中的服务器信息
+    ///
+    /// 前68字节中已知的字段：
+    ///
+    /// - 9..25：8个 u16，依次为4个交易时段的开始和结束（当天的分钟数），未使用的时段开始等于结束；
+    /// - 25..41：第二组交易时段，样本中与第一组相同，不解析；
+    /// - 42..46：服务器日期（u32，YYYYMMDD）；
+    /// - 46..50：服务器时间（u32，HHMMSS）。
+    ///
+    /// 字符串信息由多段以 NUL 等控制字符填充的文本组成：第一段为服务器名称，
+    /// 之后以 `#` 开头的各段去掉 `#` 后按行拼接为服务器公告（维护通知等）。
+    /// 每台服务器都会返回的客户端标识 `#通达信` 不算公告。
+    pub fn decode_info(data: &[u8]) -> Result<ServerInfo, MessageError> {
+        let info = Self::decode_response(data)?;
+        let trading_hours = data[9..25]
+            .chunks(4)
+            .map(|c| (bytes_to_u16_le(&c[0..2]), bytes_to_u16_le(&c[2..4])))
+            .filter(|(start, end)| start < end)
+            .collect();
+        let date = bytes_to_u32_le(&data[42..46]);
+        let time = bytes_to_u32_le(&data[46..50]);
+
+        let mut segments = info
+            .split(|c: char| c.is_control())
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        let name = segments.next().unwrap_or_default().to_string();
+        let announcement = segments
+            .map(|s| s.trim_start_matches('#').trim())
+            .filter(|s| !s.is_empty() && *s != "通达信")
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(ServerInfo {
+            name,
+            announcement: (!announcement.is_empty()).then_some(announcement),
+            trading_hours,
+            date,
+            time,
+        })
+    }
 }
 
 /// 心跳消息
@@ -135,8 +180,12 @@ pub struct CodeResponse {
 pub struct Quote;
 
 impl Quote {
-    /// 创建行情信息请求帧
+    /// 创建行情信息请求帧（最多 [`QUOTE_MAX_CODES`] 个代码，更多的代码需要分成多个请求）
     pub fn request(msg_id: u32, codes: &[String]) -> Result<RequestFrame, MessageError> {
+        if codes.len() > QUOTE_MAX_CODES {
+            return Err(MessageError::TooManyCodes(codes.len()));
+        }
+
         let mut data = vec![0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
         data.extend_from_slice(&u16_to_bytes_le(codes.len() as u16));
 
@@ -149,6 +198,30 @@ impl Quote {
         Ok(RequestFrame::new(msg_id, MessageType::Quote, data))
     }
 
+    /// 按请求代码顺序对齐行情信息，并列出服务器未返回的代码
+    ///
+    /// 同一代码在请求中出现多次时，每个位置都填入该代码的行情。
+    pub fn align(codes: &[String], quotes: Vec<QuoteInfo>) -> OrderedQuotes {
+        let by_code: HashMap<String, QuoteInfo> = quotes
+            .into_iter()
+            .map(|q| (format!("{}{}", q.exchange.as_str(), q.code), q))
+            .collect();
+
+        let mut missing = Vec::new();
+        let list = codes
+            .iter()
+            .map(|code| {
+                let quote = by_code.get(&add_prefix(code)).cloned();
+                if quote.is_none() {
+                    missing.push(code.clone());
+                }
+                quote
+            })
+            .collect();
+
+        OrderedQuotes { list, missing }
+    }
+
     /// 解码行情信息响应
     pub fn decode_response(data: &[u8]) -> Result<Vec<QuoteInfo>, MessageError> {
         if data.len() < 4 {
@@ -192,7 +265,7 @@ impl Quote {
             let server_time = format!("{}", reversed0);
 
             // ReversedBytes1 (变长整数)
-            let (_reversed1, consumed) = decode_varint(&data[offset..]);
+            let (reversed1, consumed) = decode_varint(&data[offset..]);
             offset += consumed;
 
             // TotalHand (变长整数)
@@ -204,7 +277,7 @@ impl Quote {
             offset += consumed;
 
             // Amount (4字节，特殊浮点编码)
-            let amount = decode_volume2(&data[offset..offset + 4]);
+            let amount = Amount::from_yuan(decode_volume2(&data[offset..offset + 4]));
             offset += 4;
 
             // InsideDish (变长整数)
@@ -216,11 +289,11 @@ impl Quote {
             offset += consumed;
 
             // ReversedBytes2 (变长整数)
-            let (_reversed2, consumed) = decode_varint(&data[offset..]);
+            let (reversed2, consumed) = decode_varint(&data[offset..]);
             offset += consumed;
 
             // ReversedBytes3 (变长整数)
-            let (_reversed3, consumed) = decode_varint(&data[offset..]);
+            let (reversed3, consumed) = decode_varint(&data[offset..]);
             offset += consumed;
 
             // 5档买卖盘
@@ -258,12 +331,24 @@ impl Quote {
             }
 
             // ReversedBytes4 (2字节)
+            let reversed4 = bytes_to_u16_le(&data[offset..offset + 2]);
             offset += 2;
 
             // ReversedBytes5 ~ 8 (变长整数)
-            for _ in 0..4 {
-                let (_val, consumed) = decode_varint(&data[offset..]);
+            let mut raw_reserved = [
+                reversed1,
+                reversed2,
+                reversed3,
+                reversed4 as i32,
+                0,
+                0,
+                0,
+                0,
+            ];
+            for value in raw_reserved.iter_mut().skip(4) {
+                let (val, consumed) = decode_varint(&data[offset..]);
                 offset += consumed;
+                *value = val;
             }
 
             // ReversedBytes9 (2字节) - Rate
@@ -290,6 +375,7 @@ impl Quote {
                 sell_level,
                 rate,
                 active2,
+                raw_reserved,
             });
         }
 
@@ -428,6 +514,23 @@ pub fn is_etf(code: &str) -> bool {
     }
 }
 
+/// 判断是否为场内基金（ETF、LOF、封闭式基金等）
+pub fn is_fund(code: &str) -> bool {
+    if is_etf(code) {
+        return true;
+    }
+    let code = add_prefix(code);
+    if code.len() != 8 {
+        return false;
+    }
+    let (exchange_prefix, number) = code.split_at(2);
+    match exchange_prefix {
+        "sh" => number.starts_with("50") || number.starts_with("52"),
+        "sz" => number.starts_with("16") || number.starts_with("18"),
+        _ => false,
+    }
+}
+
 /// 判断是否为指数
 pub fn is_index(code: &str) -> bool {
     let code = add_prefix(code);
@@ -513,13 +616,24 @@ impl KlineMsg {
 
     /// 解码K线数据响应
     pub fn decode_response(data: &[u8], cache: KlineCache) -> Result<KlineResponse, MessageError> {
+        Self::decode_response_filtered(data, cache, |_| true)
+    }
+
+    /// 解码K线数据响应，只保留 keep 返回 true 的记录
+    ///
+    /// 价格为差分编码，仍需逐条解析；`count` 为服务器返回的条数（用于分页），不受过滤影响。
+    pub fn decode_response_filtered(
+        data: &[u8],
+        cache: KlineCache,
+        mut keep: impl FnMut(&Kline) -> bool,
+    ) -> Result<KlineResponse, MessageError> {
         if data.len() < 2 {
             return Err(MessageError::InsufficientData);
         }
 
         let count = bytes_to_u16_le(&data[0..2]);
         let mut offset = 2;
-        let mut list = Vec::with_capacity(count as usize);
+        let mut list = Vec::new();
         let mut last_price = Price(0);
 
         for _ in 0..count {
@@ -546,6 +660,8 @@ impl KlineMsg {
             let close = Price(last_price.0 + open_diff.0 + close_diff.0);
             let high = Price(last_price.0 + open_diff.0 + high_diff.0);
             let low = Price(last_price.0 + open_diff.0 + low_diff.0);
+            // 差值按原始精度累加，记录时再换算
+            last_price = close;
 
             // 成交量（4字节）
             if offset + 4 > data.len() {
@@ -555,20 +671,19 @@ impl KlineMsg {
             offset += 4;
 
             // 分钟级K线成交量需要除以100
-            match cache.kline_type {
-                0 | 1 | 2 | 3 | 4 | 7 | 8 => volume /= 100,
-                _ => {}
+            if is_minute_kline(cache.kline_type) {
+                volume /= 100;
             }
 
             // 成交额（4字节）
             if offset + 4 > data.len() {
                 return Err(MessageError::InsufficientData);
             }
-            let amount = Price((decode_volume2(&data[offset..offset + 4]) * 1000.0) as i64);
+            let amount = Amount::from_yuan(decode_volume2(&data[offset..offset + 4]));
             offset += 4;
 
             // 如果是指数，还有额外4字节（上涨/下跌数量）
-            let (up_count, down_count) = if cache.is_index {
+            let (up_count, down_count) = if cache.kind == InstrumentKind::Index {
                 if offset + 4 > data.len() {
                     return Err(MessageError::InsufficientData);
                 }
@@ -581,35 +696,48 @@ impl KlineMsg {
                 (0, 0)
             };
 
-            last_price = close;
+            // 基金价格比股票多一位小数
+            let scale = |p: Price| match cache.kind {
+                InstrumentKind::Fund => Price(p.0 / 10),
+                _ => p,
+            };
 
-            list.push(Kline {
-                last: last_price,
-                open,
-                high,
-                low,
-                close,
+            let record = Kline {
+                last: scale(last_price),
+                open: scale(open),
+                high: scale(high),
+                low: scale(low),
+                close: scale(close),
                 order: 0,
                 volume,
                 amount,
                 time,
                 up_count,
                 down_count,
-            });
+            };
+            if keep(&record) {
+                list.push(record);
+            }
         }
 
         Ok(KlineResponse { count, list })
     }
 }
 
+/// 是否为分钟级K线（时间为压缩的日期+分钟，成交量需除以100）
+///
+/// TypeKline5Minute=0, TypeKline15Minute=1, TypeKline30Minute=2, TypeKline60Minute=3,
+/// TypeKlineMinute=7, TypeKlineMinute2=8。TypeKlineDay2=4 与日线一样按 YYYYMMDD 记录日期。
+fn is_minute_kline(kline_type: u8) -> bool {
+    matches!(kline_type, 0..=3 | 7 | 8)
+}
+
 /// 解码K线时间
 fn decode_kline_time(data: &[u8], kline_type: u8) -> i64 {
     // 根据K线类型解析时间
     let (year, month, day, hour, minute) = match kline_type {
         // 分钟级K线：前2字节是年月日压缩格式，后2字节是小时分钟
-        // TypeKlineMinute=7, TypeKlineMinute2=8, TypeKline5Minute=0,
-        // TypeKline15Minute=1, TypeKline30Minute=2, TypeKline60Minute=3, TypeKlineDay2=4
-        0 | 1 | 2 | 3 | 4 | 7 | 8 => {
+        t if is_minute_kline(t) => {
             let year_month_day = bytes_to_u16_le(&data[0..2]);
             let hour_minute = bytes_to_u16_le(&data[2..4]);
 
@@ -668,6 +796,8 @@ impl MinuteMsg {
     /// - 价格是累加的，且要乘以 10
     /// - 时间从 09:30 开始，使用 i+1 分钟
     /// - 当 i==120 时额外加 90 分钟
+    /// - 科创板部分服务器在第240条之后附带盘后固定价格交易记录（15:06~15:30），
+    ///   标记为 `Session::AfterHours`
     pub fn decode_response(data: &[u8], date: &str) -> Result<MinuteResponse, MessageError> {
         if data.len() < 6 {
             return Err(MessageError::InsufficientData);
@@ -708,17 +838,15 @@ impl MinuteMsg {
             offset += consumed;
 
             // 计算时间：从 09:30 开始，使用 i+1 分钟
-            let hour = if i < 120 {
-                (9 * 60 + 30 + (i + 1) as u32) / 60
-            } else {
-                (11 * 60 + (i + 1) as u32) / 60
-            };
-            let minute = if i < 120 {
-                (9 * 60 + 30 + (i + 1) as u32) % 60
+            // 第240条之后为科创板盘后固定价格交易（15:05 开始，同样使用 i+1 分钟）
+            let (minutes, session) = if i < 120 {
+                (9 * 60 + 30 + (i + 1) as u32, Session::Regular)
+            } else if i < 240 {
+                (11 * 60 + (i + 1) as u32, Session::Regular)
             } else {
-                (11 * 60 + (i + 1) as u32) % 60
+                (15 * 60 + 5 + (i - 239) as u32, Session::AfterHours)
             };
-            let time = parse_datetime(date, hour, minute, 0);
+            let time = parse_datetime(date, minutes / 60, minutes % 60, 0);
 
             // 价格乘以 10（multiple）
             let price = Price(last_price.0 * 10);
@@ -727,6 +855,7 @@ impl MinuteMsg {
                 time,
                 price,
                 number,
+                session,
             });
         }
 
@@ -794,66 +923,113 @@ impl TradeMsg {
 
     /// 解码分时交易响应
     pub fn decode_response(data: &[u8], cache: &TradeCache) -> Result<TradeResponse, MessageError> {
-        if data.len() < 2 {
-            return Err(MessageError::InsufficientData);
-        }
-
-        let count = bytes_to_u16_le(&data[0..2]);
-        let mut offset = 2;
-        let mut list = Vec::with_capacity(count as usize);
-        let mut last_price = Price(0);
+        Self::decode_response_filtered(data, cache, |_| true)
+    }
 
-        for _ in 0..count {
-            if offset + 2 > data.len() {
-                return Err(MessageError::InsufficientData);
+    /// 解码分时交易响应，只保留 keep 返回 true 的记录（`count` 不受过滤影响）
+    pub fn decode_response_filtered(
+        data: &[u8],
+        cache: &TradeCache,
+        mut keep: impl FnMut(&Trade) -> bool,
+    ) -> Result<TradeResponse, MessageError> {
+        let mut list = Vec::new();
+        let count = decode_ticks(data, cache, |tick| {
+            let record = Trade::from(&tick);
+            if keep(&record) {
+                list.push(record);
             }
+        })?;
+        Ok(TradeResponse { count, list })
+    }
+}
 
-            // 时间（2字节）
-            let time_val = bytes_to_u16_le(&data[offset..offset + 2]);
-            let hour = time_val / 60;
-            let minute = time_val % 60;
-            offset += 2;
-
-            // 价格差值
-            let (price_diff, consumed) = decode_price(&data[offset..]);
-            offset += consumed;
-            last_price = Price(last_price.0 + price_diff.0 * 10);
-
-            // 成交量
-            let (volume, consumed) = decode_varint(&data[offset..]);
-            offset += consumed;
+/// 逐笔成交明细消息
+///
+/// 与分时交易使用同一请求（0x0FC5），解码时保留单数、原始状态值和未知字段。
+pub struct TickDetailMsg;
 
-            // 单数
-            let (number, consumed) = decode_varint(&data[offset..]);
-            offset += consumed;
+impl TickDetailMsg {
+    /// 创建逐笔成交请求帧
+    pub fn request(
+        msg_id: u32,
+        code: &str,
+        start: u16,
+        count: u16,
+    ) -> Result<RequestFrame, MessageError> {
+        TradeMsg::request(msg_id, code, start, count)
+    }
 
-            // 状态
-            let (status_val, consumed) = decode_varint(&data[offset..]);
-            offset += consumed;
-            let status = match status_val {
-                0 => TradeStatus::Buy,
-                1 => TradeStatus::Sell,
-                _ => TradeStatus::Neutral,
-            };
+    /// 解码逐笔成交响应
+    pub fn decode_response(
+        data: &[u8],
+        cache: &TradeCache,
+    ) -> Result<TickDetailResponse, MessageError> {
+        let mut list = Vec::new();
+        let count = decode_ticks(data, cache, |tick| list.push(tick))?;
+        Ok(TickDetailResponse { count, list })
+    }
+}
 
-            // 未知字段
-            let (_unknown, consumed) = decode_varint(&data[offset..]);
-            offset += consumed;
+/// 逐条解码当日分时成交记录，返回服务器给出的记录数量
+fn decode_ticks(
+    data: &[u8],
+    cache: &TradeCache,
+    mut f: impl FnMut(TickDetail),
+) -> Result<u16, MessageError> {
+    if data.len() < 2 {
+        return Err(MessageError::InsufficientData);
+    }
 
-            // 构造时间
-            let time = parse_datetime(&cache.date, hour as u32, minute as u32, 0);
+    let count = bytes_to_u16_le(&data[0..2]);
+    let mut offset = 2;
+    let mut last_price = Price(0);
 
-            list.push(Trade {
-                time,
-                price: last_price,
-                volume,
-                status,
-                number,
-            });
+    for _ in 0..count {
+        if offset + 2 > data.len() {
+            return Err(MessageError::InsufficientData);
         }
 
-        Ok(TradeResponse { count, list })
+        // 时间（2字节）
+        let time_val = bytes_to_u16_le(&data[offset..offset + 2]);
+        let hour = time_val / 60;
+        let minute = time_val % 60;
+        offset += 2;
+
+        // 价格差值
+        let (price_diff, consumed) = decode_price(&data[offset..]);
+        offset += consumed;
+        last_price = Price(last_price.0 + price_diff.0 * 10);
+
+        // 成交量
+        let (volume, consumed) = decode_varint(&data[offset..]);
+        offset += consumed;
+
+        // 单数
+        let (order_count, consumed) = decode_varint(&data[offset..]);
+        offset += consumed;
+
+        // 状态
+        let (direction, consumed) = decode_varint(&data[offset..]);
+        offset += consumed;
+
+        // 未知字段
+        let (unknown, consumed) = decode_varint(&data[offset..]);
+        offset += consumed;
+
+        // 构造时间
+        let time = parse_datetime(&cache.date, hour as u32, minute as u32, 0);
+
+        f(TickDetail {
+            time,
+            price: last_price,
+            volume,
+            order_count,
+            direction,
+            unknown,
+        });
     }
+
+    Ok(count)
 }
 
 // ==================== 历史分时交易消息 ====================
@@ -891,13 +1067,22 @@ impl HistoryTradeMsg {
 
     /// 解码历史分时交易响应
     pub fn decode_response(data: &[u8], cache: &TradeCache) -> Result<TradeResponse, MessageError> {
+        Self::decode_response_filtered(data, cache, |_| true)
+    }
+
+    /// 解码历史分时交易响应，只保留 keep 返回 true 的记录（`count` 不受过滤影响）
+    pub fn decode_response_filtered(
+        data: &[u8],
+        cache: &TradeCache,
+        mut keep: impl FnMut(&Trade) -> bool,
+    ) -> Result<TradeResponse, MessageError> {
         if data.len() < 6 {
             return Err(MessageError::InsufficientData);
         }
 
         let count = bytes_to_u16_le(&data[0..2]);
         let mut offset = 6; // 前2字节数量，2-6字节未知
-        let mut list = Vec::with_capacity(count as usize);
+        let mut list = Vec::new();
         let mut last_price = Price(0);
 
         for _ in 0..count {
@@ -923,11 +1108,7 @@ impl HistoryTradeMsg {
             // 状态
             let (status_val, consumed) = decode_varint(&data[offset..]);
             offset += consumed;
-            let status = match status_val {
-                0 => TradeStatus::Buy,
-                1 => TradeStatus::Sell,
-                _ => TradeStatus::Neutral,
-            };
+            let status = TradeStatus::from_value(status_val);
 
             // 未知字段
             let (_unknown, consumed) = decode_varint(&data[offset..]);
@@ -936,13 +1117,16 @@ impl HistoryTradeMsg {
             // 构造时间
             let time = parse_datetime(&cache.date, hour as u32, minute as u32, 0);
 
-            list.push(Trade {
+            let record = Trade {
                 time,
                 price: last_price,
                 volume,
                 status,
                 number: 0, // 历史数据无单数
-            });
+            };
+            if keep(&record) {
+                list.push(record);
+            }
         }
 
         Ok(TradeResponse { count, list })
@@ -969,8 +1153,21 @@ impl CallAuctionMsg {
         Ok(RequestFrame::new(msg_id, MessageType::CallAuction, data))
     }
 
-    /// 解码集合竞价响应
+    /// 解码集合竞价响应（时间使用当天日期）
     pub fn decode_response(data: &[u8]) -> Result<CallAuctionResponse, MessageError> {
+        let beijing_offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        let today = Utc::now()
+            .with_timezone(&beijing_offset)
+            .format("%Y%m%d")
+            .to_string();
+        Self::decode_response_on(data, &today)
+    }
+
+    /// 解码集合竞价响应，date格式：YYYYMMDD
+    pub fn decode_response_on(
+        data: &[u8],
+        date: &str,
+    ) -> Result<CallAuctionResponse, MessageError> {
         if data.len() < 2 {
             return Err(MessageError::InsufficientData);
         }
@@ -1010,21 +1207,7 @@ impl CallAuctionMsg {
 
             let second = data[offset + 15] as u32;
 
-            // 构造时间（使用当天日期）
-            let beijing_offset = FixedOffset::east_opt(8 * 3600).unwrap();
-            let now = Utc::now().with_timezone(&beijing_offset);
-            let time = beijing_offset
-                .with_ymd_and_hms(
-                    now.year(),
-                    now.month(),
-                    now.day(),
-                    hour as u32,
-                    minute as u32,
-                    second,
-                )
-                .single()
-                .map(|dt| dt.timestamp())
-                .unwrap_or(0);
+            let time = parse_datetime(date, hour as u32, minute as u32, second);
 
             list.push(CallAuction {
                 time,
@@ -1,19 +1,26 @@
 pub mod constants;
 pub mod frame;
 pub mod types;
+#[doc(hidden)]
 pub mod codec;
 pub mod messages;
+pub mod layout;
 
 #[cfg(any(test, feature = "test-data"))]
 pub mod test_data;
 
-pub use constants::{Control, Exchange, KlineType, MessageType, PREFIX, PREFIX_RESP};
-pub use frame::{FrameError, RequestFrame, ResponseFrame};
+pub use constants::{
+    Control, Exchange, KlineType, MessageType, ResponseKind, CONTROL_RESP_ERROR,
+    CONTROL_RESP_SUCCESS, CONTROL_RESP_SUCCESS_FLAG, PREFIX, PREFIX_RESP, QUOTE_MAX_CODES,
+};
+pub use frame::{inflate, FrameError, RequestFrame, ResponseFrame};
 pub use types::{
-    CallAuction, CallAuctionResponse, Gbbq, GbbqResponse, K, Kline, KlineCache, KlineResponse,
-    MinuteResponse, Price, PriceLevel, PriceLevels, PriceNumber, QuoteInfo, StockCode, Trade,
+    Amount, CallAuction, CallAuctionResponse, Gbbq, GbbqResponse, InstrumentKind, K, Kline,
+    KlineCache, KlineResponse, MinuteResponse, OrderedQuotes, Price, PriceLevel, PriceLevels,
+    PriceNumber, QuoteInfo, ServerInfo, Session, StockCode, TickDetail, TickDetailResponse, Trade,
     TradeResponse, TradeStatus,
 };
+#[doc(hidden)]
 pub use codec::*;
 pub use messages::*;
 
@@ -1,7 +1,9 @@
 //! 协议数据类型定义
 
 use crate::protocol::constants::Exchange;
+use crate::protocol::messages::{is_fund, is_index};
 use chrono::{FixedOffset, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// 格式化 Unix 毫秒时间戳为可读字符串
@@ -16,7 +18,7 @@ fn format_time(timestamp_secs: i64) -> String {
 // 移除不再需要的 is_leap_year
 
 /// 价格类型，单位为厘（1元 = 1000厘）
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub struct Price(pub i64);
 
 impl Price {
@@ -45,8 +47,77 @@ impl fmt::Display for Price {
     }
 }
 
+/// 金额类型，单位为厘（1元 = 1000厘）
+///
+/// 使用 i128 存储，指数等大额成交额不会溢出或因 i64 截断丢失精度。
+/// 注意协议中的成交额是 4 字节浮点编码，有效位数有限，解码（`decode_volume2`）得到 f64
+/// 后再经 [`Amount::from_yuan`] 换算，结果并不精确到厘；i128 只保证之后的累加不再损失精度。
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Amount(pub i128);
+
+impl Amount {
+    /// 由元换算（经过 f64，超过 2^53 厘约 9e12 元时有舍入误差）
+    pub fn from_yuan(yuan: f64) -> Self {
+        Amount((yuan * 1000.0).round() as i128)
+    }
+
+    pub fn to_yuan(self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+
+    pub fn as_i128(self) -> i128 {
+        self.0
+    }
+}
+
+impl From<Price> for Amount {
+    fn from(price: Price) -> Self {
+        Amount(price.0 as i128)
+    }
+}
+
+impl TryFrom<Amount> for Price {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(amount: Amount) -> Result<Self, Self::Error> {
+        i64::try_from(amount.0).map(Price)
+    }
+}
+
+impl std::ops::Add for Amount {
+    type Output = Amount;
+
+    fn add(self, rhs: Amount) -> Amount {
+        Amount(self.0 + rhs.0)
+    }
+}
+
+impl std::ops::AddAssign for Amount {
+    fn add_assign(&mut self, rhs: Amount) {
+        self.0 += rhs.0;
+    }
+}
+
+impl std::iter::Sum for Amount {
+    fn sum<I: Iterator<Item = Amount>>(iter: I) -> Amount {
+        iter.fold(Amount(0), |a, b| a + b)
+    }
+}
+
+impl fmt::Debug for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.3}", self.to_yuan())
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.3}元", self.to_yuan())
+    }
+}
+
 /// 价格档位（5档买卖盘）
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize)]
 pub struct PriceLevel {
     pub buy: bool,    // 是否为买盘
     pub price: Price, // 价格
@@ -64,7 +135,7 @@ impl fmt::Debug for PriceLevel {
 pub type PriceLevels = [PriceLevel; 5];
 
 /// K线数据
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct K {
     pub last: Price,  // 昨天收盘价
     pub open: Price,  // 今日开盘价
@@ -88,7 +159,7 @@ impl fmt::Debug for K {
 }
 
 /// K线数据项
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct Kline {
     pub last: Price,     // 昨日收盘价
     pub open: Price,     // 开盘价
@@ -97,7 +168,7 @@ pub struct Kline {
     pub close: Price,    // 收盘价
     pub order: i32,      // 成交单数
     pub volume: i64,     // 成交量
-    pub amount: Price,   // 成交额
+    pub amount: Amount,  // 成交额
     pub time: i64,       // 时间（Unix时间戳，秒）
     pub up_count: i32,   // 上涨数量（指数有效）
     pub down_count: i32, // 下跌数量（指数有效）
@@ -135,28 +206,43 @@ impl fmt::Debug for Kline {
     }
 }
 
+/// 交易时段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum Session {
+    /// 常规交易时段（9:30~11:30，13:00~15:00）
+    #[default]
+    Regular,
+    /// 盘后固定价格交易（科创板 15:05~15:30）
+    AfterHours,
+}
+
 /// 分时数据项
 #[derive(Clone)]
 pub struct PriceNumber {
-    pub time: i64,    // 时间（Unix时间戳，秒）
-    pub price: Price, // 价格
-    pub number: i32,  // 成交量（手）
+    pub time: i64,        // 时间（Unix时间戳，秒）
+    pub price: Price,     // 价格
+    pub number: i32,      // 成交量（手）
+    pub session: Session, // 交易时段
 }
 
 impl fmt::Debug for PriceNumber {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{} {:.2} {}手",
+            "{} {:.2} {}手{}",
             format_time(self.time),
             self.price.to_yuan(),
-            self.number
+            self.number,
+            match self.session {
+                Session::Regular => "",
+                Session::AfterHours => " 盘后",
+            }
         )
     }
 }
 
 /// 分时成交数据项
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Serialize)]
 pub struct Trade {
     pub time: i64,           // 时间（Unix时间戳，秒）
     pub price: Price,        // 价格
@@ -180,11 +266,13 @@ impl fmt::Debug for Trade {
 }
 
 /// 成交状态
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum TradeStatus {
-    Buy = 0,     // 买入
-    Sell = 1,    // 卖出
-    Neutral = 2, // 中性/汇总
+    Buy,        // 0 买入
+    Sell,       // 1 卖出
+    Neutral,    // 2 中性/汇总
+    Auction,    // 4 盘前集合竞价成交
+    Other(i32), // 其他未识别的状态值，原样保留
 }
 
 impl fmt::Debug for TradeStatus {
@@ -193,6 +281,87 @@ impl fmt::Debug for TradeStatus {
             TradeStatus::Buy => write!(f, "买"),
             TradeStatus::Sell => write!(f, "卖"),
             TradeStatus::Neutral => write!(f, "中"),
+            TradeStatus::Auction => write!(f, "竞"),
+            TradeStatus::Other(v) => write!(f, "状态{}", v),
+        }
+    }
+}
+
+impl TradeStatus {
+    /// 由协议中的状态值转换（0=买入，1=卖出，2=中性/汇总，4=盘前集合竞价，其他保留原值）
+    pub fn from_value(value: i32) -> Self {
+        match value {
+            0 => TradeStatus::Buy,
+            1 => TradeStatus::Sell,
+            2 => TradeStatus::Neutral,
+            4 => TradeStatus::Auction,
+            v => TradeStatus::Other(v),
+        }
+    }
+
+    /// 协议中的状态值
+    pub fn value(self) -> i32 {
+        match self {
+            TradeStatus::Buy => 0,
+            TradeStatus::Sell => 1,
+            TradeStatus::Neutral => 2,
+            TradeStatus::Auction => 4,
+            TradeStatus::Other(v) => v,
+        }
+    }
+}
+
+/// 逐笔成交明细（当日分时成交的全部字段）
+#[derive(Clone, PartialEq, Serialize)]
+pub struct TickDetail {
+    pub time: i64,        // 时间（Unix时间戳，秒）
+    pub price: Price,     // 价格
+    pub volume: i32,      // 成交量（手）
+    pub order_count: i32, // 成交单数
+    pub direction: i32,   // 原始状态值，见 TradeStatus::from_value
+    pub unknown: i32,     // 状态之后的未知字段，原样保留
+}
+
+impl TickDetail {
+    /// 主动买入
+    pub fn is_buy(&self) -> bool {
+        self.direction == 0
+    }
+
+    /// 主动卖出
+    pub fn is_sell(&self) -> bool {
+        self.direction == 1
+    }
+
+    pub fn status(&self) -> TradeStatus {
+        TradeStatus::from_value(self.direction)
+    }
+}
+
+impl fmt::Debug for TickDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:.2} {}手 {}笔 {:?}({}) 未知:{}",
+            format_time(self.time),
+            self.price.to_yuan(),
+            self.volume,
+            self.order_count,
+            self.status(),
+            self.direction,
+            self.unknown
+        )
+    }
+}
+
+impl From<&TickDetail> for Trade {
+    fn from(t: &TickDetail) -> Self {
+        Trade {
+            time: t.time,
+            price: t.price,
+            volume: t.volume,
+            status: t.status(),
+            number: t.order_count,
         }
     }
 }
@@ -222,7 +391,7 @@ impl fmt::Debug for StockCode {
 }
 
 /// 行情信息
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct QuoteInfo {
     pub exchange: Exchange,      // 市场
     pub code: String,            // 股票代码
@@ -231,13 +400,53 @@ pub struct QuoteInfo {
     pub server_time: String,     // 服务器时间
     pub total_hand: i32,         // 总手
     pub intuition: i32,          // 现量
-    pub amount: f64,             // 金额
+    pub amount: Amount,          // 金额
     pub inside_dish: i32,        // 内盘
     pub outer_disc: i32,         // 外盘
     pub buy_level: PriceLevels,  // 5档买盘
     pub sell_level: PriceLevels, // 5档卖盘
     pub rate: f64,               // 涨速
     pub active2: u16,            // 活跃度
+    /// 含义尚未确认的保留字段，依次为 ReversedBytes1~8（ReversedBytes4 为2字节无符号数）
+    pub raw_reserved: [i32; 8],
+}
+
+impl QuoteInfo {
+    /// 按基金的价格精度换算：价格除以10（基金价格比股票多一位小数）
+    ///
+    /// 该规则尚未用真实的基金行情响应验证，解码时不会自动使用，见 `Client::get_fund_quote`。
+    pub fn with_fund_precision(mut self) -> Self {
+        let scale = |p: Price| Price(p.0 / 10);
+        self.k = K {
+            last: scale(self.k.last),
+            open: scale(self.k.open),
+            high: scale(self.k.high),
+            low: scale(self.k.low),
+            close: scale(self.k.close),
+        };
+        for level in self.buy_level.iter_mut().chain(self.sell_level.iter_mut()) {
+            level.price = scale(level.price);
+        }
+        self
+    }
+
+    /// 振幅（%）：(最高价 - 最低价) / 昨收价
+    pub fn amplitude(&self) -> Option<f64> {
+        if self.k.last.0 <= 0 {
+            return None;
+        }
+        Some((self.k.high.0 - self.k.low.0) as f64 / self.k.last.0 as f64 * 100.0)
+    }
+
+    /// 委比（%）：(5档委买量 - 5档委卖量) / (5档委买量 + 5档委卖量)
+    pub fn order_ratio(&self) -> Option<f64> {
+        let buy: i64 = self.buy_level.iter().map(|l| l.number as i64).sum();
+        let sell: i64 = self.sell_level.iter().map(|l| l.number as i64).sum();
+        if buy + sell == 0 {
+            return None;
+        }
+        Some((buy - sell) as f64 / (buy + sell) as f64 * 100.0)
+    }
 }
 
 impl fmt::Debug for QuoteInfo {
@@ -259,7 +468,7 @@ impl fmt::Debug for QuoteInfo {
             change,
             change_pct,
             self.total_hand,
-            self.amount / 10000.0
+            self.amount.to_yuan() / 10000.0
         )?;
 
         // K线数据
@@ -479,6 +688,24 @@ impl fmt::Debug for KlineResponse {
     }
 }
 
+/// 按请求顺序排列的行情信息
+///
+/// 服务器会丢弃无法识别的代码，返回条数可能少于请求条数。
+#[derive(Debug, Clone)]
+pub struct OrderedQuotes {
+    /// 与请求代码一一对应，服务器未返回的为 None
+    pub list: Vec<Option<QuoteInfo>>,
+    /// 服务器未返回的代码（请求时的原始写法）
+    pub missing: Vec<String>,
+}
+
+impl OrderedQuotes {
+    /// 是否所有代码都有返回
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
 /// 分时数据响应
 #[derive(Clone)]
 pub struct MinuteResponse {
@@ -519,6 +746,26 @@ impl fmt::Debug for TradeResponse {
     }
 }
 
+/// 逐笔成交明细响应
+#[derive(Clone)]
+pub struct TickDetailResponse {
+    pub count: u16,
+    pub list: Vec<TickDetail>,
+}
+
+impl fmt::Debug for TickDetailResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "逐笔成交({}):", self.count)?;
+        for (i, t) in self.list.iter().take(10).enumerate() {
+            writeln!(f, "  {:>3}. {:?}", i + 1, t)?;
+        }
+        if self.list.len() > 10 {
+            writeln!(f, "  ... 还有 {} 条", self.list.len() - 10)?;
+        }
+        Ok(())
+    }
+}
+
 /// 集合竞价响应
 #[derive(Clone)]
 pub struct CallAuctionResponse {
@@ -559,11 +806,44 @@ impl fmt::Debug for GbbqResponse {
     }
 }
 
+/// 证券类别（决定K线的解码方式）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InstrumentKind {
+    /// 股票
+    #[default]
+    Stock,
+    /// 基金（ETF、LOF等），价格精度为0.001元，K线价格比股票多一位小数
+    Fund,
+    /// 指数，每条K线额外包含上涨/下跌家数，成交量单位为股
+    Index,
+}
+
+impl InstrumentKind {
+    /// 根据代码判断类别（可带或不带交易所前缀）
+    pub fn from_code(code: &str) -> Self {
+        if is_index(code) {
+            InstrumentKind::Index
+        } else if is_fund(code) {
+            InstrumentKind::Fund
+        } else {
+            InstrumentKind::Stock
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            InstrumentKind::Stock => "股票",
+            InstrumentKind::Fund => "基金",
+            InstrumentKind::Index => "指数",
+        }
+    }
+}
+
 /// K线缓存信息（用于解码时的上下文）
 #[derive(Clone, Copy)]
 pub struct KlineCache {
-    pub kline_type: u8, // K线类型
-    pub is_index: bool, // 是否为指数
+    pub kline_type: u8,       // K线类型
+    pub kind: InstrumentKind, // 证券类别
 }
 
 impl fmt::Debug for KlineCache {
@@ -583,7 +863,21 @@ impl fmt::Debug for KlineCache {
             11 => "年线",
             _ => "未知",
         };
-        let kind = if self.is_index { "指数" } else { "股票" };
-        write!(f, "{}K线({})", type_name, kind)
+        write!(f, "{}K线({})", type_name, self.kind.name())
     }
 }
+
+/// 服务器信息（连接响应）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerInfo {
+    /// 服务器名称
+    pub name: String,
+    /// 服务器公告（维护通知等），没有时为 None
+    pub announcement: Option<String>,
+    /// 交易时段：(开始, 结束)，为当天的分钟数，如 (570, 690) 表示 09:30-11:30
+    pub trading_hours: Vec<(u16, u16)>,
+    /// 服务器日期（YYYYMMDD）
+    pub date: u32,
+    /// 服务器时间（HHMMSS）
+    pub time: u32,
+}
@@ -0,0 +1,137 @@
+//! 买一/卖一排队消耗估计
+//!
+//! 只有5档快照时，根据相邻两次快照之间最优价位的挂单变化和该价位上的成交，
+//! 估计排队的成交消耗、撤单量以及按当前速度排完队所需时间，用于成交模拟。
+
+use crate::protocol::{Price, PriceLevels, QuoteInfo, Trade};
+
+/// 盘口方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// 买一
+    Bid,
+    /// 卖一
+    Ask,
+}
+
+/// 单个区间的排队估计
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueEstimate {
+    pub code: String,
+    /// 区间结束时间（后一次快照时间）
+    pub time: i64,
+    pub side: Side,
+    /// 区间开始时的最优价
+    pub price: Price,
+    /// 区间开始时该价位挂单量（手）
+    pub start: i32,
+    /// 区间结束时该价位挂单量（价位已超出5档时为 None）
+    pub end: Option<i32>,
+    /// 区间内该价位成交量（手）
+    pub traded: i64,
+    /// 估计撤单量（负数表示净新增挂单）
+    pub cancelled: Option<i64>,
+    /// 成交消耗速度（手/秒）
+    pub rate: f64,
+    /// 按当前成交速度排完剩余挂单所需秒数
+    pub eta_secs: Option<f64>,
+}
+
+/// 区间结束时原最优价位的挂单量
+fn volume_at(levels: &PriceLevels, price: Price, side: Side) -> Option<i32> {
+    if let Some(l) = levels.iter().find(|l| l.price == price && l.number > 0) {
+        return Some(l.number);
+    }
+    let best = levels[0].price;
+    let consumed = match side {
+        Side::Bid => best < price,
+        Side::Ask => best > price,
+    };
+    // 最优价已越过原价位说明原价位被吃完；否则在5档之外，无法判断
+    if consumed || best.0 == 0 {
+        Some(0)
+    } else {
+        None
+    }
+}
+
+/// 时间所在的分钟序号
+fn minute(time: i64) -> i64 {
+    time.div_euclid(60)
+}
+
+fn estimate_side(
+    code: &str,
+    (t0, prev): (i64, &QuoteInfo),
+    (t1, cur): (i64, &QuoteInfo),
+    trades: &[Trade],
+    side: Side,
+) -> Option<QueueEstimate> {
+    let (prev_levels, cur_levels) = match side {
+        Side::Bid => (&prev.buy_level, &cur.buy_level),
+        Side::Ask => (&prev.sell_level, &cur.sell_level),
+    };
+    let best = prev_levels[0];
+    if best.price.0 == 0 || best.number <= 0 {
+        return None;
+    }
+
+    // 分时成交只精确到分钟，按分钟归属到区间
+    let traded: i64 = trades
+        .iter()
+        .filter(|t| minute(t.time) > minute(t0) && minute(t.time) <= minute(t1))
+        .filter(|t| t.price == best.price)
+        .map(|t| t.volume as i64)
+        .sum();
+    let end = volume_at(cur_levels, best.price, side);
+    let cancelled = end.map(|e| best.number as i64 - e as i64 - traded);
+
+    let secs = (t1 - t0).max(1) as f64;
+    let rate = traded as f64 / secs;
+    let eta_secs = match end {
+        Some(e) if rate > 0.0 => Some(e as f64 / rate),
+        _ => None,
+    };
+
+    Some(QueueEstimate {
+        code: code.to_string(),
+        time: t1,
+        side,
+        price: best.price,
+        start: best.number,
+        end,
+        traded,
+        cancelled,
+        rate,
+        eta_secs,
+    })
+}
+
+/// 估计每个快照区间内买一/卖一的排队消耗
+///
+/// quotes 为按时间升序的 (本地接收时间, 快照)，trades 为同一股票的分时成交。
+/// 成交按价格归属：区间内成交价等于区间开始时买一（卖一）价的计入买一（卖一）。
+///
+/// 分时成交的时间只精确到分钟（HH:MM:00），因此每分钟只使用最后一次快照，
+/// 区间为相邻两个分钟的最后一次快照之间，时间戳在 (前一分钟, 后一分钟] 内的成交计入该区间。
+pub fn estimate(code: &str, quotes: &[(i64, QuoteInfo)], trades: &[Trade]) -> Vec<QueueEstimate> {
+    let mut last_per_minute: Vec<&(i64, QuoteInfo)> = Vec::new();
+    for quote in quotes {
+        match last_per_minute.last_mut() {
+            Some(last) if minute(last.0) == minute(quote.0) => *last = quote,
+            _ => last_per_minute.push(quote),
+        }
+    }
+
+    let mut out = Vec::new();
+    for pair in last_per_minute.windows(2) {
+        let (t0, prev) = (pair[0].0, &pair[0].1);
+        let (t1, cur) = (pair[1].0, &pair[1].1);
+        for side in [Side::Bid, Side::Ask] {
+            if let Some(e) = estimate_side(code, (t0, prev), (t1, cur), trades, side) {
+                out.push(e);
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,176 @@
+//! 盘中强弱排行
+//!
+//! 订阅一组代码的行情（[`ShardedSubscriber`]），用 [`SymbolStats`] 维护每个代码的盘中统计，
+//! 按涨幅或量比实时排序，随时通过 [`RankService::top`] 取前几名。
+
+use crate::protocol::{add_prefix, Price, QuoteInfo};
+use crate::stats::SymbolStats;
+use crate::subscribe::ShardedSubscriber;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+
+/// 排序指标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RankKey {
+    /// 涨幅
+    #[default]
+    Return,
+    /// 量比（当日成交量 / 基准成交量）
+    VolumeRatio,
+}
+
+/// 排行条目
+#[derive(Debug, Clone, Serialize)]
+pub struct RankEntry {
+    pub code: String,
+    pub last: Price,
+    pub high: Option<Price>,
+    pub low: Option<Price>,
+    /// 涨幅（%）
+    pub change_pct: f64,
+    /// 成交量（手）
+    pub volume: i64,
+    /// 量比，未设置基准成交量时为 None
+    pub volume_ratio: Option<f64>,
+}
+
+struct Symbol {
+    stats: SymbolStats,
+    prev_close: Price,
+    volume: i64,
+}
+
+/// 排行榜（不含订阅，可直接用行情快照驱动）
+#[derive(Default)]
+pub struct Ranking {
+    key: RankKey,
+    baseline: HashMap<String, i64>,
+    symbols: HashMap<String, Symbol>,
+}
+
+impl Ranking {
+    pub fn new(key: RankKey) -> Self {
+        Self {
+            key,
+            ..Default::default()
+        }
+    }
+
+    /// 设置量比的基准成交量（手），通常为过去5日的平均成交量
+    pub fn with_baseline(mut self, baseline: HashMap<String, i64>) -> Self {
+        self.baseline = baseline
+            .into_iter()
+            .map(|(code, volume)| (add_prefix(&code), volume))
+            .collect();
+        self
+    }
+
+    /// 用一批行情快照更新
+    pub fn update(&mut self, quotes: &[QuoteInfo]) {
+        for quote in quotes {
+            let code = format!("{}{}", quote.exchange.as_str(), quote.code);
+            let symbol = self.symbols.entry(code.clone()).or_insert_with(|| Symbol {
+                stats: SymbolStats::new(&code, Price(1), 0),
+                prev_close: quote.k.last,
+                volume: 0,
+            });
+            symbol.stats.update_quote(quote);
+            symbol.prev_close = quote.k.last;
+            symbol.volume = quote.total_hand as i64;
+        }
+    }
+
+    fn entry(&self, code: &str, symbol: &Symbol) -> Option<RankEntry> {
+        let snapshot = symbol.stats.snapshot();
+        let last = snapshot.last?;
+        let change_pct = if symbol.prev_close.0 > 0 {
+            (last.0 - symbol.prev_close.0) as f64 / symbol.prev_close.0 as f64 * 100.0
+        } else {
+            0.0
+        };
+        let volume_ratio = self
+            .baseline
+            .get(code)
+            .filter(|&&base| base > 0)
+            .map(|&base| symbol.volume as f64 / base as f64);
+        Some(RankEntry {
+            code: code.to_string(),
+            last,
+            high: snapshot.high,
+            low: snapshot.low,
+            change_pct,
+            volume: symbol.volume,
+            volume_ratio,
+        })
+    }
+
+    /// 按排序指标从高到低取前 n 名（指标缺失的排在最后，相同时按代码排序）
+    pub fn top(&self, n: usize) -> Vec<RankEntry> {
+        let mut entries: Vec<RankEntry> = self
+            .symbols
+            .iter()
+            .filter_map(|(code, symbol)| self.entry(code, symbol))
+            .collect();
+        let key = |e: &RankEntry| match self.key {
+            RankKey::Return => Some(e.change_pct),
+            RankKey::VolumeRatio => e.volume_ratio,
+        };
+        entries.sort_by(|a, b| {
+            match (key(a), key(b)) {
+                (Some(x), Some(y)) => y.total_cmp(&x),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+            .then_with(|| a.code.cmp(&b.code))
+        });
+        entries.truncate(n);
+        entries
+    }
+}
+
+/// 实时排行服务
+///
+/// 后台任务持续接收订阅的行情并更新排行榜；服务被丢弃时停止接收（订阅任务随之退出）。
+pub struct RankService {
+    ranking: Arc<Mutex<Ranking>>,
+    task: JoinHandle<()>,
+}
+
+impl RankService {
+    /// 启动订阅并按 ranking 的设置排序
+    pub fn spawn(subscriber: ShardedSubscriber, ranking: Ranking) -> Self {
+        let ranking = Arc::new(Mutex::new(ranking));
+        let mut rx = subscriber.spawn();
+        let shared = ranking.clone();
+        let task = tokio::spawn(async move {
+            while let Some(quotes) = rx.recv().await {
+                shared.lock().unwrap().update(&quotes);
+            }
+        });
+        Self { ranking, task }
+    }
+
+    /// 当前排行前 n 名
+    pub fn top(&self, n: usize) -> Vec<RankEntry> {
+        self.ranking.lock().unwrap().top(n)
+    }
+
+    /// 已收到行情的代码数量
+    pub fn len(&self) -> usize {
+        self.ranking.lock().unwrap().symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Drop for RankService {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,68 @@
+//! 请求限速（令牌桶）
+//!
+//! 公共服务器会对短时间内大量请求的客户端限流甚至封禁，批量下载时通过
+//! [`crate::Client::set_rate_limit`] 限制每秒请求数。
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::{self, Instant};
+
+/// 最低速率（每秒请求数），更低的速率按此处理，避免等待时间溢出
+const MIN_RATE: f64 = 1e-6;
+
+/// 令牌桶限速器
+///
+/// 每秒补充 `rate` 个令牌，最多积攒 `burst` 个；令牌不足时预支并等待到可用时刻，
+/// 并发请求按到达顺序排队。
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    /// reqs_per_sec: 每秒请求数（突发上限与之相同，至少为1）
+    pub fn new(reqs_per_sec: f64) -> Self {
+        Self::with_burst(reqs_per_sec, reqs_per_sec)
+    }
+
+    /// 指定突发上限
+    ///
+    /// 每秒请求数低于 1e-6（约11.6天一个请求）时按 1e-6 处理。
+    pub fn with_burst(reqs_per_sec: f64, burst: f64) -> Self {
+        let burst = burst.max(1.0);
+        Self {
+            rate: reqs_per_sec.max(MIN_RATE),
+            burst,
+            state: Mutex::new((burst, Instant::now())),
+        }
+    }
+
+    /// 每秒请求数
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// 取得一个令牌需要等待的时间（同时扣除令牌）
+    fn reserve(&self) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        let (tokens, last) = &mut *state;
+        let now = Instant::now();
+        *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * self.rate).min(self.burst);
+        *last = now;
+        *tokens -= 1.0;
+        if *tokens >= 0.0 {
+            None
+        } else {
+            Some(Duration::from_secs_f64(-*tokens / self.rate))
+        }
+    }
+
+    /// 等待直到可以发送下一个请求
+    pub async fn acquire(&self) {
+        if let Some(wait) = self.reserve() {
+            time::sleep(wait).await;
+        }
+    }
+}
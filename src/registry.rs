@@ -0,0 +1,114 @@
+//! 进程级客户端注册表
+//!
+//! 按服务器地址共享同一个 [`Client`]，避免多个组件重复连接同一台服务器
+//! （部分通达信服务器会对同一来源的大量连接限流）。
+//!
+//! 客户端断线后可能自动重连到其他服务器，注册表按客户端当前的 [`Client::addr`] 查找，
+//! 而不是登记时的地址。
+
+use crate::client::{with_default_port, Client, ClientError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+#[derive(Default)]
+struct Registry {
+    /// 已登记的客户端（弱引用）
+    clients: Vec<Weak<Client>>,
+    /// 每个地址的连接锁，同一地址的并发调用只建立一次连接
+    connecting: HashMap<String, Arc<tokio::sync::Mutex<()>>>,
+}
+
+impl Registry {
+    /// 清理已释放的客户端和无人等待的连接锁
+    fn prune(&mut self) {
+        self.clients.retain(|client| client.strong_count() > 0);
+        self.connecting
+            .retain(|_, lock| Arc::strong_count(lock) > 1);
+    }
+
+    /// 当前连接到指定地址的存活客户端
+    fn live(&self, addr: &str) -> Vec<Arc<Client>> {
+        self.clients
+            .iter()
+            .filter_map(Weak::upgrade)
+            .filter(|client| client.addr() == addr)
+            .collect()
+    }
+}
+
+fn registry() -> &'static Mutex<Registry> {
+    static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(Registry::default()))
+}
+
+/// 查找连接到指定地址的客户端（顺带清理已释放的登记）
+fn find(addr: &str) -> Option<Arc<Client>> {
+    let mut registry = registry().lock().unwrap();
+    registry.prune();
+    let live = registry.live(addr);
+    if live.len() > 1 {
+        log::warn!("注册表中有 {} 个客户端连接到 {}", live.len(), addr);
+    }
+    live.into_iter().next()
+}
+
+/// 获取指定地址的共享客户端
+///
+/// 如果已有连接到该地址的存活客户端则直接复用，否则建立新连接并登记。
+/// 同一地址的并发调用只会建立一次连接。
+/// 注册表只持有弱引用，所有使用者释放后连接随之关闭。
+pub async fn client_for(host: &str) -> Result<Arc<Client>, ClientError> {
+    let addr = with_default_port(host);
+    if let Some(client) = find(&addr) {
+        return Ok(client);
+    }
+
+    let lock = registry()
+        .lock()
+        .unwrap()
+        .connecting
+        .entry(addr.clone())
+        .or_default()
+        .clone();
+    let _guard = lock.lock().await;
+
+    // 等待期间其他调用可能已经建立了连接
+    if let Some(client) = find(&addr) {
+        return Ok(client);
+    }
+
+    let client = Arc::new(Client::connect(&addr).await?);
+    registry()
+        .lock()
+        .unwrap()
+        .clients
+        .push(Arc::downgrade(&client));
+    Ok(client)
+}
+
+/// 从注册表移除连接到指定地址的客户端（例如连接已失效）
+///
+/// 已经持有该客户端的使用者不受影响，之后的 [`client_for`] 会建立新连接。
+pub fn evict(host: &str) {
+    let addr = with_default_port(host);
+    let mut registry = registry().lock().unwrap();
+    registry.clients.retain(|client| match client.upgrade() {
+        Some(client) => client.addr() != addr,
+        None => false,
+    });
+}
+
+/// 检测重复连接：返回有多个已登记客户端连接到的地址及其客户端数量
+///
+/// 客户端自动重连到其他服务器后，可能和另一个客户端连接到同一台服务器。
+pub fn duplicates() -> Vec<(String, usize)> {
+    let mut registry = registry().lock().unwrap();
+    registry.prune();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for client in registry.clients.iter().filter_map(Weak::upgrade) {
+        *counts.entry(client.addr()).or_default() += 1;
+    }
+    let mut duplicates: Vec<_> = counts.into_iter().filter(|(_, n)| *n > 1).collect();
+    duplicates.sort();
+    duplicates
+}
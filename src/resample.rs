@@ -0,0 +1,85 @@
+//! K线周期合成
+//!
+//! 由日线合成周线/月线，可以绕过服务器周线、月线历史深度不足的限制。
+//! 周期边界以实际出现的交易日为准（停牌、节假日自动跳过）。
+
+use crate::adjust::{self, Adjustment};
+use crate::protocol::{Gbbq, Kline};
+use chrono::{Datelike, FixedOffset, TimeZone};
+
+/// 合成周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// 周（ISO 周，周一至周日）
+    Week,
+    /// 自然月
+    Month,
+}
+
+/// K线所属周期的标识（北京时间）
+fn period_key(time: i64, period: Period) -> (i32, u32) {
+    let beijing_offset = FixedOffset::east_opt(8 * 3600).unwrap();
+    let dt = beijing_offset.timestamp_opt(time, 0).unwrap();
+    match period {
+        Period::Week => {
+            let week = dt.iso_week();
+            (week.year(), week.week())
+        }
+        Period::Month => (dt.year(), dt.month()),
+    }
+}
+
+fn merge(bar: &mut Kline, k: &Kline) {
+    bar.high = bar.high.max(k.high);
+    bar.low = bar.low.min(k.low);
+    bar.close = k.close;
+    bar.time = k.time;
+    bar.order += k.order;
+    bar.volume += k.volume;
+    bar.amount += k.amount;
+    bar.up_count = k.up_count;
+    bar.down_count = k.down_count;
+}
+
+/// 由日线合成指定周期K线（daily 需按时间升序排列）
+///
+/// 合成K线的时间为该周期最后一个交易日，昨收为第一个交易日的昨收，
+/// 成交量/成交额/单数累加，涨跌家数取最后一个交易日。
+pub fn resample(daily: &[Kline], period: Period) -> Vec<Kline> {
+    let mut bars: Vec<Kline> = Vec::new();
+    let mut current_key = None;
+
+    for k in daily {
+        let key = period_key(k.time, period);
+        match bars.last_mut() {
+            Some(bar) if current_key == Some(key) => merge(bar, k),
+            _ => {
+                bars.push(k.clone());
+                current_key = Some(key);
+            }
+        }
+    }
+    bars
+}
+
+/// 由日线合成周线
+pub fn daily_to_weekly(daily: &[Kline]) -> Vec<Kline> {
+    resample(daily, Period::Week)
+}
+
+/// 由日线合成月线
+pub fn daily_to_monthly(daily: &[Kline]) -> Vec<Kline> {
+    resample(daily, Period::Month)
+}
+
+/// 先对日线复权，再合成指定周期K线
+pub fn resample_adjusted(
+    daily: &[Kline],
+    period: Period,
+    gbbq: &[Gbbq],
+    adjustment: Adjustment,
+) -> Vec<Kline> {
+    let mut daily = daily.to_vec();
+    adjust::adjust(&mut daily, gbbq, adjustment);
+    resample(&daily, period)
+}
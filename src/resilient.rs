@@ -0,0 +1,186 @@
+//! 多服务器故障转移客户端
+//!
+//! 连接后服务器失效时，[`Client`] 自身只会重连原地址或默认服务器列表。
+//! [`ResilientClient`] 在请求超时或连接断开时重新测速，切换到配置列表中最快的其他服务器并重试。
+
+use crate::client::{current_request_id, is_retryable, traced, Client, ClientError};
+use crate::dial::fast_hosts;
+use crate::events::{emit, LifecycleEvent};
+use crate::protocol::*;
+use log::warn;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::{broadcast, Mutex};
+
+/// 默认最多切换服务器的次数（每个请求）
+pub const DEFAULT_MAX_FAILOVERS: u32 = 2;
+
+/// 故障转移客户端
+pub struct ResilientClient {
+    hosts: Vec<String>,
+    max_failovers: u32,
+    // (连接序号, 当前客户端)，序号用于判断是否已被其他请求切换
+    current: Mutex<(u64, Arc<Client>)>,
+    events: broadcast::Sender<LifecycleEvent>,
+}
+
+/// 按测速结果依次尝试连接，跳过 exclude
+async fn connect_fastest(
+    hosts: &[String],
+    exclude: Option<&str>,
+    events: &broadcast::Sender<LifecycleEvent>,
+) -> Result<Client, ClientError> {
+    let hosts: Vec<&str> = hosts.iter().map(String::as_str).collect();
+    let mut last_err = ClientError::Other("没有可用的服务器".to_string());
+    for result in fast_hosts(&hosts).await {
+        let addr = result.addr();
+        if Some(addr.as_str()) == exclude {
+            continue;
+        }
+        // 由本层负责切换服务器
+        let builder = Client::builder().reconnect(false).events(events.clone());
+        match builder.connect(&addr).await {
+            Ok(client) => return Ok(client),
+            Err(e) => last_err = e,
+        }
+    }
+    Err(last_err)
+}
+
+impl ResilientClient {
+    /// 连接到列表中最快的服务器（列表为空时使用默认服务器列表）
+    pub async fn connect(hosts: &[&str]) -> Result<Self, ClientError> {
+        Self::connect_with_events(hosts, crate::events::channel()).await
+    }
+
+    /// 连接到列表中最快的服务器，所有连接的生命周期事件和服务器切换事件发送到 events
+    pub async fn connect_with_events(
+        hosts: &[&str],
+        events: broadcast::Sender<LifecycleEvent>,
+    ) -> Result<Self, ClientError> {
+        let hosts: Vec<String> = hosts.iter().map(|h| h.to_string()).collect();
+        let client = connect_fastest(&hosts, None, &events).await?;
+        Ok(Self {
+            hosts,
+            max_failovers: DEFAULT_MAX_FAILOVERS,
+            current: Mutex::new((0, Arc::new(client))),
+            events,
+        })
+    }
+
+    /// 订阅生命周期事件，见 [`crate::events`]
+    pub fn subscribe_events(&self) -> broadcast::Receiver<LifecycleEvent> {
+        self.events.subscribe()
+    }
+
+    /// 设置每个请求最多切换服务器的次数
+    pub fn with_max_failovers(mut self, max_failovers: u32) -> Self {
+        self.max_failovers = max_failovers;
+        self
+    }
+
+    /// 当前使用的客户端
+    pub async fn current(&self) -> Arc<Client> {
+        self.current.lock().await.1.clone()
+    }
+
+    /// 切换到其他服务器；failed 为出错的连接序号，已被其他请求切换时直接返回
+    async fn failover(&self, failed: u64) -> Result<(), ClientError> {
+        let mut current = self.current.lock().await;
+        if current.0 != failed {
+            return Ok(());
+        }
+        let old = current.1.addr();
+        let client = connect_fastest(&self.hosts, Some(&old), &self.events).await?;
+        warn!("服务器 {} 失效，切换到 {}", old, client.addr());
+        emit(
+            &self.events,
+            LifecycleEvent::HostSwitched {
+                from: old,
+                to: client.addr(),
+            },
+        );
+        *current = (failed + 1, Arc::new(client));
+        Ok(())
+    }
+
+    /// 在当前客户端上执行请求，超时或连接断开时切换服务器并重试
+    pub async fn run<T, F, Fut>(&self, f: F) -> Result<T, ClientError>
+    where
+        F: Fn(Arc<Client>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        traced(async {
+            let req = current_request_id().unwrap_or_default();
+            let mut failovers = 0;
+            loop {
+                let (id, client) = self.current.lock().await.clone();
+                match f(client).await {
+                    Err(e) if failovers < self.max_failovers && is_retryable(&e) => {
+                        warn!("#{} 请求失败: {}", req, e);
+                        failovers += 1;
+                        if let Err(fe) = self.failover(id).await {
+                            warn!("#{} 切换服务器失败: {}", req, fe);
+                            return Err(e);
+                        }
+                    }
+                    res => return res,
+                }
+            }
+        })
+        .await
+    }
+
+    /// 发送帧并等待响应
+    pub async fn send_frame(&self, frame: RequestFrame) -> Result<ResponseFrame, ClientError> {
+        self.run(|c| {
+            let frame = frame.clone();
+            async move { c.send_frame(frame).await }
+        })
+        .await
+    }
+
+    /// 获取行情信息（代码数量不限）
+    pub async fn get_quote(&self, codes: &[String]) -> Result<Vec<QuoteInfo>, ClientError> {
+        self.run(|c| async move { c.get_quote_batch(codes).await })
+            .await
+    }
+
+    /// 获取K线数据
+    pub async fn get_kline(
+        &self,
+        kline_type: KlineType,
+        code: &str,
+        start: u16,
+        count: u16,
+    ) -> Result<KlineResponse, ClientError> {
+        self.run(|c| async move { c.get_kline(kline_type, code, start, count).await })
+            .await
+    }
+
+    /// 获取全部K线数据
+    pub async fn get_kline_all(
+        &self,
+        kline_type: KlineType,
+        code: &str,
+    ) -> Result<KlineResponse, ClientError> {
+        self.run(|c| async move { c.get_kline_all(kline_type, code).await })
+            .await
+    }
+
+    /// 获取分时数据
+    pub async fn get_minute(&self, code: &str) -> Result<MinuteResponse, ClientError> {
+        self.run(|c| async move { c.get_minute(code).await }).await
+    }
+
+    /// 获取分时成交
+    pub async fn get_trade(
+        &self,
+        code: &str,
+        start: u16,
+        count: u16,
+    ) -> Result<TradeResponse, ClientError> {
+        self.run(|c| async move { c.get_trade(code, start, count).await })
+            .await
+    }
+}
@@ -0,0 +1,323 @@
+//! 数据落地接口
+//!
+//! [`Sink`] 定义了行情、K线、分时成交的统一写入接口，
+//! 内置 CSV（[`CsvSink`]）和 JSON Lines（[`JsonLinesSink`]）实现，
+//! 其他存储（数据库等）可以在外部实现该 trait 接入。
+
+use crate::adjust::Adjustment;
+use crate::protocol::{Kline, QuoteInfo, Trade};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// 落地错误
+#[derive(Debug, thiserror::Error)]
+pub enum SinkError {
+    #[error("IO错误: {0}")]
+    Io(#[from] io::Error),
+    #[error("序列化错误: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("其他错误: {0}")]
+    Other(String),
+}
+
+/// 数据落地接口
+pub trait Sink {
+    /// 写入行情快照
+    fn write_quotes(&mut self, quotes: &[QuoteInfo]) -> Result<(), SinkError>;
+
+    /// 写入K线（code 带交易所前缀，如 sz000001）
+    fn write_klines(&mut self, code: &str, klines: &[Kline]) -> Result<(), SinkError>;
+
+    /// 写入分时成交（code 带交易所前缀，如 sz000001）
+    fn write_trades(&mut self, code: &str, trades: &[Trade]) -> Result<(), SinkError>;
+
+    /// 刷新缓冲区
+    fn flush(&mut self) -> Result<(), SinkError>;
+}
+
+impl<S: Sink + ?Sized> Sink for Box<S> {
+    fn write_quotes(&mut self, quotes: &[QuoteInfo]) -> Result<(), SinkError> {
+        (**self).write_quotes(quotes)
+    }
+
+    fn write_klines(&mut self, code: &str, klines: &[Kline]) -> Result<(), SinkError> {
+        (**self).write_klines(code, klines)
+    }
+
+    fn write_trades(&mut self, code: &str, trades: &[Trade]) -> Result<(), SinkError> {
+        (**self).write_trades(code, trades)
+    }
+
+    fn flush(&mut self) -> Result<(), SinkError> {
+        (**self).flush()
+    }
+}
+
+// ==================== CSV ====================
+
+const QUOTE_HEADER: &str = "exchange,code,server_time,last,open,high,low,close,total_hand,intuition,amount,inside_dish,outer_disc,\
+buy1_price,buy1_number,buy2_price,buy2_number,buy3_price,buy3_number,buy4_price,buy4_number,buy5_price,buy5_number,\
+sell1_price,sell1_number,sell2_price,sell2_number,sell3_price,sell3_number,sell4_price,sell4_number,sell5_price,sell5_number,rate";
+
+const KLINE_HEADER: &str =
+    "code,time,last,open,high,low,close,volume,amount,order,up_count,down_count";
+
+const TRADE_HEADER: &str = "code,time,price,volume,status,number";
+
+/// 各文件的列单位说明，写在表头前的注释行中
+const QUOTE_UNITS: &str = "last,open,high,low,close,buy*_price,sell*_price,amount=厘; \
+total_hand,intuition,inside_dish,outer_disc,buy*_number,sell*_number=手";
+
+const KLINE_UNITS: &str = "last,open,high,low,close,amount=厘; volume=手（指数为股）";
+
+const TRADE_UNITS: &str = "price=厘; volume=手; status: 0=买 1=卖 2=中性 4=盘前集合竞价";
+
+const TIMEZONE_NOTE: &str = "time 为 Unix 时间戳（秒），按北京时间（UTC+8）解释";
+
+/// CSV 落地
+///
+/// 在指定目录下写入 `quotes.csv`、`klines.csv`、`trades.csv`，
+/// 文件在第一次写入对应数据时创建。价格和金额均为厘（整数）。
+///
+/// 表头前有以 `#` 开头的注释行，说明各列单位、时区和K线的复权方式；
+/// 读取时需要跳过注释（如 pandas 的 `comment='#'`）。
+pub struct CsvSink {
+    dir: PathBuf,
+    adjustment: Adjustment,
+    quotes: Option<BufWriter<File>>,
+    klines: Option<BufWriter<File>>,
+    trades: Option<BufWriter<File>>,
+}
+
+impl CsvSink {
+    /// 创建 CSV 落地（目录不存在时自动创建）
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Result<Self, SinkError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            adjustment: Adjustment::None,
+            quotes: None,
+            klines: None,
+            trades: None,
+        })
+    }
+
+    /// 记录写入的K线所用的复权方式（只用于 `klines.csv` 的注释，默认不复权）
+    pub fn with_adjustment(mut self, adjustment: Adjustment) -> Self {
+        self.adjustment = adjustment;
+        self
+    }
+
+    fn open<'a>(
+        dir: &Path,
+        slot: &'a mut Option<BufWriter<File>>,
+        name: &str,
+        notes: &[String],
+        header: &str,
+    ) -> Result<&'a mut BufWriter<File>, SinkError> {
+        if slot.is_none() {
+            let mut writer = BufWriter::new(File::create(dir.join(name))?);
+            for note in notes {
+                writeln!(writer, "# {}", note)?;
+            }
+            writeln!(writer, "{}", header)?;
+            *slot = Some(writer);
+        }
+        Ok(slot.as_mut().unwrap())
+    }
+}
+
+impl Sink for CsvSink {
+    fn write_quotes(&mut self, quotes: &[QuoteInfo]) -> Result<(), SinkError> {
+        let notes = [format!("单位: {}", QUOTE_UNITS)];
+        let w = Self::open(
+            &self.dir,
+            &mut self.quotes,
+            "quotes.csv",
+            &notes,
+            QUOTE_HEADER,
+        )?;
+        for q in quotes {
+            write!(
+                w,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                q.exchange.as_str(),
+                q.code,
+                q.server_time,
+                q.k.last.0,
+                q.k.open.0,
+                q.k.high.0,
+                q.k.low.0,
+                q.k.close.0,
+                q.total_hand,
+                q.intuition,
+                q.amount.0,
+                q.inside_dish,
+                q.outer_disc
+            )?;
+            for level in q.buy_level.iter().chain(q.sell_level.iter()) {
+                write!(w, ",{},{}", level.price.0, level.number)?;
+            }
+            writeln!(w, ",{}", q.rate)?;
+        }
+        Ok(())
+    }
+
+    fn write_klines(&mut self, code: &str, klines: &[Kline]) -> Result<(), SinkError> {
+        let adjustment = match self.adjustment {
+            Adjustment::None => "不复权",
+            Adjustment::Forward => "前复权",
+            Adjustment::Backward => "后复权",
+        };
+        let notes = [
+            format!("单位: {}", KLINE_UNITS),
+            format!("时区: {}", TIMEZONE_NOTE),
+            format!("复权: {}", adjustment),
+        ];
+        let w = Self::open(
+            &self.dir,
+            &mut self.klines,
+            "klines.csv",
+            &notes,
+            KLINE_HEADER,
+        )?;
+        for k in klines {
+            writeln!(
+                w,
+                "{},{},{},{},{},{},{},{},{},{},{},{}",
+                code,
+                k.time,
+                k.last.0,
+                k.open.0,
+                k.high.0,
+                k.low.0,
+                k.close.0,
+                k.volume,
+                k.amount.0,
+                k.order,
+                k.up_count,
+                k.down_count
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_trades(&mut self, code: &str, trades: &[Trade]) -> Result<(), SinkError> {
+        let notes = [
+            format!("单位: {}", TRADE_UNITS),
+            format!("时区: {}", TIMEZONE_NOTE),
+        ];
+        let w = Self::open(
+            &self.dir,
+            &mut self.trades,
+            "trades.csv",
+            &notes,
+            TRADE_HEADER,
+        )?;
+        for t in trades {
+            writeln!(
+                w,
+                "{},{},{},{},{},{}",
+                code,
+                t.time,
+                t.price.0,
+                t.volume,
+                t.status.value(),
+                t.number
+            )?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), SinkError> {
+        for w in [&mut self.quotes, &mut self.klines, &mut self.trades]
+            .into_iter()
+            .flatten()
+        {
+            w.flush()?;
+        }
+        Ok(())
+    }
+}
+
+// ==================== JSON Lines ====================
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Line<'a> {
+    Quote {
+        #[serde(flatten)]
+        quote: &'a QuoteInfo,
+    },
+    Kline {
+        code: &'a str,
+        #[serde(flatten)]
+        kline: &'a Kline,
+    },
+    Trade {
+        code: &'a str,
+        #[serde(flatten)]
+        trade: &'a Trade,
+    },
+}
+
+/// JSON Lines 落地（每条记录一行 JSON，带 `type` 字段区分数据类型）
+pub struct JsonLinesSink<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonLinesSink<W> {
+    /// 使用任意 Writer 创建
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// 取回内部 Writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_line(&mut self, line: &Line<'_>) -> Result<(), SinkError> {
+        serde_json::to_writer(&mut self.writer, line)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+impl JsonLinesSink<io::Stdout> {
+    /// 输出到标准输出
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+}
+
+impl<W: Write> Sink for JsonLinesSink<W> {
+    fn write_quotes(&mut self, quotes: &[QuoteInfo]) -> Result<(), SinkError> {
+        for quote in quotes {
+            self.write_line(&Line::Quote { quote })?;
+        }
+        Ok(())
+    }
+
+    fn write_klines(&mut self, code: &str, klines: &[Kline]) -> Result<(), SinkError> {
+        for kline in klines {
+            self.write_line(&Line::Kline { code, kline })?;
+        }
+        Ok(())
+    }
+
+    fn write_trades(&mut self, code: &str, trades: &[Trade]) -> Result<(), SinkError> {
+        for trade in trades {
+            self.write_line(&Line::Trade { code, trade })?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), SinkError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
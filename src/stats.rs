@@ -0,0 +1,138 @@
+//! 单只股票盘中统计
+//!
+//! 随行情快照和逐笔成交增量更新开高低、均价、分价成交量和最近成交，
+//! 刷新界面时直接导出快照，不需要每次从完整数据重新计算。
+
+use crate::protocol::{Amount, Price, QuoteInfo, Trade};
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+
+/// 统计快照
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsSnapshot {
+    pub code: String,
+    pub open: Option<Price>,
+    pub high: Option<Price>,
+    pub low: Option<Price>,
+    pub last: Option<Price>,
+    /// 均价（成交额/成交股数）
+    pub vwap: Option<Price>,
+    /// 成交量（手）
+    pub volume: i64,
+    /// 成交额（由逐笔成交累计）
+    pub amount: Amount,
+    /// 成交笔数
+    pub trades: usize,
+    /// 分价成交量：(价格区间下界, 成交量手)，按价格升序
+    pub profile: Vec<(Price, i64)>,
+    /// 最近成交（旧的在前）
+    pub recent: Vec<Trade>,
+}
+
+/// 单只股票盘中统计累加器
+#[derive(Debug, Clone)]
+pub struct SymbolStats {
+    code: String,
+    bucket: i64,
+    max_recent: usize,
+    open: Option<Price>,
+    high: Option<Price>,
+    low: Option<Price>,
+    last: Option<Price>,
+    volume: i64,
+    amount: Amount,
+    trades: usize,
+    profile: BTreeMap<i64, i64>,
+    recent: VecDeque<Trade>,
+}
+
+impl SymbolStats {
+    /// bucket: 分价统计的价格区间宽度；max_recent: 保留的最近成交笔数
+    pub fn new(code: &str, bucket: Price, max_recent: usize) -> Self {
+        Self {
+            code: code.to_string(),
+            bucket: bucket.0.max(1),
+            max_recent,
+            open: None,
+            high: None,
+            low: None,
+            last: None,
+            volume: 0,
+            amount: Amount(0),
+            trades: 0,
+            profile: BTreeMap::new(),
+            recent: VecDeque::with_capacity(max_recent),
+        }
+    }
+
+    fn update_price(&mut self, price: Price) {
+        if price.0 <= 0 {
+            return;
+        }
+        self.open.get_or_insert(price);
+        self.high = Some(self.high.map_or(price, |h| h.max(price)));
+        self.low = Some(self.low.map_or(price, |l| l.min(price)));
+        self.last = Some(price);
+    }
+
+    /// 用逐笔成交更新
+    pub fn update_trade(&mut self, trade: &Trade) {
+        self.update_price(trade.price);
+        let volume = trade.volume as i64;
+        self.volume += volume;
+        // 成交量单位为手（100股）
+        self.amount += Amount(trade.price.0 as i128 * volume as i128 * 100);
+        self.trades += 1;
+
+        let bucket = trade.price.0.div_euclid(self.bucket) * self.bucket;
+        *self.profile.entry(bucket).or_insert(0) += volume;
+
+        if self.max_recent > 0 {
+            if self.recent.len() == self.max_recent {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(trade.clone());
+        }
+    }
+
+    /// 用行情快照更新（快照中的开高低为全天数据，优先采用）
+    pub fn update_quote(&mut self, quote: &QuoteInfo) {
+        if quote.k.open.0 > 0 {
+            self.open = Some(quote.k.open);
+        }
+        if quote.k.high.0 > 0 {
+            self.high = Some(self.high.map_or(quote.k.high, |h| h.max(quote.k.high)));
+        }
+        if quote.k.low.0 > 0 {
+            self.low = Some(self.low.map_or(quote.k.low, |l| l.min(quote.k.low)));
+        }
+        if quote.k.close.0 > 0 {
+            self.last = Some(quote.k.close);
+        }
+    }
+
+    /// 均价
+    pub fn vwap(&self) -> Option<Price> {
+        if self.volume == 0 {
+            return None;
+        }
+        Some(Price((self.amount.0 / (self.volume as i128 * 100)) as i64))
+    }
+
+    /// 导出快照
+    pub fn snapshot(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            code: self.code.clone(),
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            last: self.last,
+            vwap: self.vwap(),
+            volume: self.volume,
+            amount: self.amount,
+            trades: self.trades,
+            profile: self.profile.iter().map(|(&p, &v)| (Price(p), v)).collect(),
+            recent: self.recent.iter().cloned().collect(),
+        }
+    }
+}
@@ -0,0 +1,430 @@
+//! 多连接分片行情订阅
+//!
+//! 单个连接轮询全市场5000多只股票的行情需要很长时间，这里把代码平均分配到多个连接，
+//! 每个连接在一个周期内均匀地分批请求自己负责的代码，结果合并到同一个通道中。
+//!
+//! 配置活跃度分层（[`PollTier`]）后，价格和成交量长时间不变的代码会降低轮询频率。
+
+use crate::client::Client;
+use crate::cursor::{trade_key, CursorStore, SyncCursor};
+use crate::protocol::{add_prefix, QuoteInfo, Trade};
+use chrono::{DateTime, FixedOffset};
+use log::warn;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::{self, Instant};
+
+/// 默认轮询周期
+pub const DEFAULT_CYCLE: Duration = Duration::from_secs(3);
+
+/// 分片行情订阅
+pub struct ShardedSubscriber {
+    clients: Vec<Arc<Client>>,
+    codes: Vec<String>,
+    shard_size: usize,
+    cycle: Duration,
+    tiers: Vec<PollTier>,
+}
+
+/// 轮询分层：连续 `idle_cycles` 次轮询无变化的代码，每 `every` 个周期轮询一次
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollTier {
+    pub idle_cycles: u32,
+    pub every: u32,
+}
+
+impl PollTier {
+    pub const fn new(idle_cycles: u32, every: u32) -> Self {
+        Self { idle_cycles, every }
+    }
+}
+
+/// 默认分层：无变化5次后每2个周期轮询一次，20次后每5个周期一次
+pub const DEFAULT_TIERS: &[PollTier] = &[PollTier::new(5, 2), PollTier::new(20, 5)];
+
+/// 代码活跃度跟踪
+#[derive(Debug, Clone, Default)]
+pub struct ActivityTracker {
+    tiers: Vec<PollTier>,
+    // 代码 → (连续无变化次数, 上次的现价和总手)
+    state: HashMap<String, (u32, Option<(i64, i32)>)>,
+}
+
+impl ActivityTracker {
+    pub fn new(mut tiers: Vec<PollTier>) -> Self {
+        tiers.sort_by_key(|t| t.idle_cycles);
+        Self {
+            tiers,
+            state: HashMap::new(),
+        }
+    }
+
+    /// 代码的轮询间隔（周期数）
+    pub fn every(&self, code: &str) -> u32 {
+        let idle = self.state.get(&add_prefix(code)).map_or(0, |s| s.0);
+        self.tiers
+            .iter()
+            .rev()
+            .find(|t| idle >= t.idle_cycles)
+            .map_or(1, |t| t.every.max(1))
+    }
+
+    /// 第 cycle 个周期需要轮询的代码
+    pub fn due(&self, codes: &[String], cycle: u64) -> Vec<String> {
+        codes
+            .iter()
+            .enumerate()
+            // 加上序号错开同一层的代码，避免集中在同一个周期
+            .filter(|(i, code)| (cycle + *i as u64).is_multiple_of(self.every(code) as u64))
+            .map(|(_, code)| code.clone())
+            .collect()
+    }
+
+    /// 根据一次请求的结果更新活跃度（未返回的代码视为无变化）
+    pub fn observe(&mut self, requested: &[String], quotes: &[QuoteInfo]) {
+        let latest: HashMap<String, (i64, i32)> = quotes
+            .iter()
+            .map(|q| {
+                (
+                    format!("{}{}", q.exchange.as_str(), q.code),
+                    (q.k.close.0, q.total_hand),
+                )
+            })
+            .collect();
+        for code in requested {
+            let key = add_prefix(code);
+            let current = latest.get(&key).copied();
+            let entry = self.state.entry(key).or_insert((0, None));
+            if current.is_some() && current != entry.1 {
+                entry.0 = 0;
+                entry.1 = current;
+            } else {
+                entry.0 = entry.0.saturating_add(1);
+            }
+        }
+    }
+}
+
+impl ShardedSubscriber {
+    /// pool: 连接池；all_codes: 订阅的全部代码；shard_size: 单次请求的代码数量
+    pub fn new(pool: Vec<Arc<Client>>, all_codes: Vec<String>, shard_size: usize) -> Self {
+        Self {
+            clients: pool,
+            codes: all_codes,
+            shard_size: shard_size.max(1),
+            cycle: DEFAULT_CYCLE,
+            tiers: Vec::new(),
+        }
+    }
+
+    /// 设置活跃度分层（默认不分层，每个周期轮询全部代码），见 [`DEFAULT_TIERS`]
+    pub fn with_tiers(mut self, tiers: &[PollTier]) -> Self {
+        self.tiers = tiers.to_vec();
+        self
+    }
+
+    /// 设置目标轮询周期（每个代码每个周期请求一次）
+    pub fn with_cycle(mut self, cycle: Duration) -> Self {
+        self.cycle = cycle;
+        self
+    }
+
+    /// 每个连接负责的代码
+    pub fn partitions(&self) -> Vec<Vec<String>> {
+        partition(&self.codes, self.clients.len())
+    }
+
+    /// 启动订阅，返回合并后的行情通道
+    ///
+    /// 每个连接一个任务；请求失败时记录日志并继续下一批。接收端关闭后所有任务退出。
+    pub fn spawn(self) -> mpsc::Receiver<Vec<QuoteInfo>> {
+        let (tx, rx) = mpsc::channel(self.clients.len().max(1) * 4);
+        let parts = self.partitions();
+
+        for (client, codes) in self.clients.into_iter().zip(parts) {
+            if codes.is_empty() {
+                continue;
+            }
+            let tx = tx.clone();
+            let shard_size = self.shard_size;
+            let cycle = self.cycle;
+            let tracker = ActivityTracker::new(self.tiers.clone());
+            tokio::spawn(async move {
+                poll_partition(client, codes, shard_size, cycle, tracker, tx).await;
+            });
+        }
+        rx
+    }
+}
+
+/// 单连接定时轮询行情，只推送有变化的快照
+///
+/// 每隔 `interval` 请求一次全部代码（超过单次上限时自动分批），与上次推送相比
+/// 现价或总手有变化的行情才会发出，首轮发出全部行情；全部无变化时不推送。
+/// 接收端关闭后任务自动退出；请求失败时跳过本轮。
+pub fn subscribe_quotes(
+    client: Arc<Client>,
+    codes: Vec<String>,
+    interval: Duration,
+) -> mpsc::Receiver<Vec<QuoteInfo>> {
+    let (tx, rx) = mpsc::channel(16);
+
+    tokio::spawn(async move {
+        let mut last: HashMap<String, (i64, i32)> = HashMap::new();
+        let mut ticker = time::interval(interval);
+        ticker.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            if tx.is_closed() {
+                break;
+            }
+
+            let quotes = match client.get_quote_batch(&codes).await {
+                Ok(quotes) => quotes,
+                Err(e) => {
+                    warn!("轮询行情失败: {}", e);
+                    continue;
+                }
+            };
+
+            let changed: Vec<QuoteInfo> = quotes
+                .into_iter()
+                .filter(|q| {
+                    let key = format!("{}{}", q.exchange.as_str(), q.code);
+                    let snapshot = (q.k.close.0, q.total_hand);
+                    last.insert(key, snapshot) != Some(snapshot)
+                })
+                .collect();
+            if !changed.is_empty() && tx.send(changed).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    rx
+}
+
+/// 每次轮询成交时请求的最新成交笔数
+pub const TRADE_POLL_COUNT: u16 = 200;
+
+/// 比对时保留的已推送成交笔数
+const CURSOR_TAIL: usize = 8;
+
+/// 成交位置跟踪
+///
+/// 分时成交只能从最新一笔往前分页，这里记住最近推送过的几笔成交，
+/// 在新取得的成交列表中找到它们的位置，之后的即为新成交。
+#[derive(Debug, Clone, Default)]
+pub struct TradeCursor {
+    tail: Vec<Trade>,
+}
+
+impl TradeCursor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 是否还没有推送过成交
+    pub fn is_empty(&self) -> bool {
+        self.tail.is_empty()
+    }
+
+    /// 清空位置，下次 [`TradeCursor::advance`] 返回全部成交
+    pub fn reset(&mut self) {
+        self.tail.clear();
+    }
+
+    /// 根据按时间升序的最新成交列表前进，返回新成交
+    ///
+    /// 列表中找不到已推送的成交时（新成交超出列表范围或已换日）返回 None，位置不变。
+    pub fn advance(&mut self, trades: &[Trade]) -> Option<Vec<Trade>> {
+        let start = if self.tail.is_empty() {
+            0
+        } else {
+            // 从后往前找最后一处与已推送成交末尾重合的位置（列表开头可能落在末尾几笔中间）
+            (1..=trades.len()).rev().find(|&end| {
+                let k = end.min(self.tail.len());
+                trades[..end].ends_with(&self.tail[self.tail.len() - k..])
+            })?
+        };
+
+        let new = trades[start..].to_vec();
+        self.tail.extend(new.iter().cloned());
+        let excess = self.tail.len().saturating_sub(CURSOR_TAIL);
+        self.tail.drain(..excess);
+        Some(new)
+    }
+}
+
+/// 增量推送逐笔成交
+///
+/// 首轮推送当天全部成交，之后每隔 `interval` 只请求最新的 [`TRADE_POLL_COUNT`] 笔，
+/// 通过 [`TradeCursor`] 找出新成交推送；新成交超出该范围时重新下载全天成交补齐，
+/// 换日后从新一天的第一笔开始。没有新成交时不推送。
+/// 接收端关闭后任务自动退出；请求失败时跳过本轮。
+pub fn stream_trades(
+    client: Arc<Client>,
+    code: String,
+    interval: Duration,
+) -> mpsc::Receiver<Vec<Trade>> {
+    let (tx, rx) = mpsc::channel(16);
+    tokio::spawn(run_trades(client, code, interval, None, tx));
+    rx
+}
+
+/// 增量推送逐笔成交，同步位置保存在 store 中
+///
+/// 与 [`stream_trades`] 相同，但每次推送后保存当天已推送的成交笔数（键为 [`trade_key`]）；
+/// 重启后首轮只推送上次之后的成交，换日后从新一天的第一笔开始。保存失败时记录日志并继续。
+pub fn stream_trades_resumable(
+    client: Arc<Client>,
+    code: String,
+    interval: Duration,
+    store: Arc<dyn CursorStore>,
+) -> mpsc::Receiver<Vec<Trade>> {
+    let (tx, rx) = mpsc::channel(16);
+    tokio::spawn(run_trades(client, code, interval, Some(store), tx));
+    rx
+}
+
+/// 成交所属的交易日 YYYYMMDD（北京时间）
+fn trade_date(trade: &Trade) -> String {
+    let beijing_offset = FixedOffset::east_opt(8 * 3600).unwrap();
+    DateTime::from_timestamp(trade.time, 0)
+        .map(|t| {
+            t.with_timezone(&beijing_offset)
+                .format("%Y%m%d")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+async fn run_trades(
+    client: Arc<Client>,
+    code: String,
+    interval: Duration,
+    store: Option<Arc<dyn CursorStore>>,
+    tx: mpsc::Sender<Vec<Trade>>,
+) {
+    let key = trade_key(&code);
+    let mut state = match store.as_ref().map(|s| s.load(&key)) {
+        Some(Ok(state)) => state.unwrap_or_default(),
+        Some(Err(e)) => {
+            warn!("读取{}同步位置失败: {}", code, e);
+            SyncCursor::default()
+        }
+        None => SyncCursor::default(),
+    };
+    let mut cursor = TradeCursor::new();
+    let mut ticker = time::interval(interval);
+    ticker.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        if tx.is_closed() {
+            break;
+        }
+
+        let latest = if cursor.is_empty() {
+            None
+        } else {
+            match client.get_trade(&code, 0, TRADE_POLL_COUNT).await {
+                Ok(resp) => cursor.advance(&resp.list),
+                Err(e) => {
+                    warn!("获取{}成交失败: {}", code, e);
+                    continue;
+                }
+            }
+        };
+
+        let new = match latest {
+            Some(new) => new,
+            None => match client.get_trade_all(&code).await {
+                Ok(resp) => {
+                    let date = resp.list.first().map(trade_date);
+                    let resume = cursor.is_empty();
+                    let new = cursor.advance(&resp.list).unwrap_or_else(|| {
+                        cursor.reset();
+                        cursor.advance(&resp.list).unwrap_or_default()
+                    });
+                    match date {
+                        // 首轮跳过上次已推送的成交
+                        Some(date) if resume && date == state.trade_date => {
+                            new[state.trade_count.min(new.len())..].to_vec()
+                        }
+                        Some(date) if resume || date != state.trade_date => {
+                            state.trade_date = date;
+                            state.trade_count = 0;
+                            new
+                        }
+                        _ => new,
+                    }
+                }
+                Err(e) => {
+                    warn!("获取{}成交失败: {}", code, e);
+                    continue;
+                }
+            },
+        };
+
+        if new.is_empty() {
+            continue;
+        }
+        state.trade_count += new.len();
+        if tx.send(new).await.is_err() {
+            break;
+        }
+        if let Some(store) = &store {
+            if let Err(e) = store.save(&key, &state) {
+                warn!("保存{}同步位置失败: {}", code, e);
+            }
+        }
+    }
+}
+
+/// 将代码轮流分配到 n 个分区
+pub fn partition(codes: &[String], n: usize) -> Vec<Vec<String>> {
+    let n = n.max(1);
+    let mut parts = vec![Vec::new(); n];
+    for (i, code) in codes.iter().enumerate() {
+        parts[i % n].push(code.clone());
+    }
+    parts
+}
+
+async fn poll_partition(
+    client: Arc<Client>,
+    codes: Vec<String>,
+    shard_size: usize,
+    cycle: Duration,
+    mut tracker: ActivityTracker,
+    tx: mpsc::Sender<Vec<QuoteInfo>>,
+) {
+    for round in 0u64.. {
+        let start = Instant::now();
+        let due = tracker.due(&codes, round);
+        let shards: Vec<&[String]> = due.chunks(shard_size).collect();
+        let n = shards.len() as u32;
+
+        for (i, shard) in shards.iter().enumerate() {
+            // 在周期内均匀分布各批请求
+            time::sleep_until(start + cycle * i as u32 / n).await;
+            match client.get_quote(shard).await {
+                Ok(quotes) => {
+                    tracker.observe(shard, &quotes);
+                    if tx.send(quotes).await.is_err() {
+                        return;
+                    }
+                }
+                Err(e) => warn!("分片行情请求失败: {}", e),
+            }
+        }
+        if tx.is_closed() {
+            return;
+        }
+        time::sleep_until(start + cycle).await;
+    }
+}
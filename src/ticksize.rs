@@ -0,0 +1,83 @@
+//! 最小变动价位（价格档位）
+//!
+//! 沪深北交易所按证券类别规定的最小报价单位：
+//!
+//! - A股（沪深主板、创业板、科创板）和北交所股票：0.01元；
+//! - 基金（ETF、LOF等场内基金）：0.001元；
+//! - 债券（含可转债）：0.001元；
+//! - 上海B股：0.001美元；深圳B股：0.01港元；
+//! - 指数没有报价单位，按 0.001 处理（价格精度）。
+//!
+//! 价格使用 [`Price`]（单位为厘），档位同样以厘表示。
+
+use crate::protocol::{add_prefix, Price};
+
+/// 0.01元
+const TICK_CENT: Price = Price(10);
+/// 0.001元
+const TICK_MILLI: Price = Price(1);
+
+/// 代码对应的最小变动价位
+///
+/// symbol 可带或不带交易所前缀；目前各类别的档位与价格无关，价格参数用于将来按价格分档的规则。
+/// 无法识别的代码按股票（0.01元）处理。
+pub fn tick_for(symbol: &str, _price: Price) -> Price {
+    let code = add_prefix(symbol);
+    let milli = match code.split_at(code.len().min(2)) {
+        ("sh", number) => has_prefix(number, SH_MILLI),
+        ("sz", number) => has_prefix(number, SZ_MILLI),
+        ("bj", number) => number.starts_with("899"),
+        // 无法判断交易所的代码（如债券）按沪深两市的规则匹配
+        _ => has_prefix(&code, SH_MILLI) || has_prefix(&code, SZ_MILLI),
+    };
+    if milli {
+        TICK_MILLI
+    } else {
+        TICK_CENT
+    }
+}
+
+/// 上海按 0.001 报价的代码前缀：基金、债券、指数、B股
+const SH_MILLI: &[&str] = &["50", "51", "52", "56", "58", "11", "000", "900", "999"];
+/// 深圳按 0.001 报价的代码前缀：基金、债券、指数
+const SZ_MILLI: &[&str] = &["15", "16", "18", "12", "399"];
+
+fn has_prefix(number: &str, prefixes: &[&str]) -> bool {
+    prefixes.iter().any(|p| number.starts_with(p))
+}
+
+/// 价格是否落在档位上
+pub fn is_on_tick(price: Price, tick: Price) -> bool {
+    tick.0 <= 0 || price.0 % tick.0 == 0
+}
+
+/// 向下取整到档位
+pub fn floor_to_tick(price: Price, tick: Price) -> Price {
+    if tick.0 <= 0 {
+        return price;
+    }
+    Price(price.0.div_euclid(tick.0) * tick.0)
+}
+
+/// 向上取整到档位
+pub fn ceil_to_tick(price: Price, tick: Price) -> Price {
+    let floor = floor_to_tick(price, tick);
+    if floor == price {
+        price
+    } else {
+        Price(floor.0 + tick.0)
+    }
+}
+
+/// 四舍五入到最近的档位（正好在中间时向上）
+pub fn round_to_tick(price: Price, tick: Price) -> Price {
+    if tick.0 <= 0 {
+        return price;
+    }
+    floor_to_tick(Price(price.0 + tick.0 / 2), tick)
+}
+
+/// 将价格四舍五入到代码的档位上
+pub fn snap_price(symbol: &str, price: Price) -> Price {
+    round_to_tick(price, tick_for(symbol, price))
+}
@@ -0,0 +1,74 @@
+use tdx_rust::adjust::{adjust, factors};
+use tdx_rust::*;
+
+const DAY: i64 = 86400;
+// 2024-01-02 15:00 北京时间
+const T0: i64 = 1704178800;
+
+fn kline(time: i64, close: f64) -> Kline {
+    let p = Price::from_yuan(close);
+    Kline {
+        last: p,
+        open: p,
+        high: p,
+        low: p,
+        close: p,
+        order: 0,
+        volume: 100,
+        amount: Amount(0),
+        time,
+        up_count: 0,
+        down_count: 0,
+    }
+}
+
+fn xrxd(time: i64, c1: f64, c2: f64, c3: f64, c4: f64) -> Gbbq {
+    Gbbq {
+        code: "sz000001".to_string(),
+        time,
+        category: 1,
+        c1,
+        c2,
+        c3,
+        c4,
+    }
+}
+
+#[test]
+fn test_adjust_dividend() {
+    let klines = vec![
+        kline(T0, 10.0),
+        kline(T0 + DAY, 10.0),
+        kline(T0 + 2 * DAY, 9.0),
+    ];
+    // 10派10元，除权日为第三根K线当天 00:00
+    let gbbq = vec![xrxd(T0 + 2 * DAY - 15 * 3600, 10.0, 0.0, 0.0, 0.0)];
+
+    assert_eq!(factors(&klines, &gbbq, Adjustment::None), vec![1.0; 3]);
+
+    let mut forward = klines.clone();
+    adjust(&mut forward, &gbbq, Adjustment::Forward);
+    assert_eq!(forward[0].close, Price(9000));
+    assert_eq!(forward[1].close, Price(9000));
+    assert_eq!(forward[2].close, Price(9000));
+
+    let mut backward = klines.clone();
+    adjust(&mut backward, &gbbq, Adjustment::Backward);
+    assert_eq!(backward[0].close, Price(10000));
+    assert_eq!(backward[2].close, Price(10000));
+}
+
+#[test]
+fn test_adjust_bonus_shares() {
+    let klines = vec![kline(T0, 20.0), kline(T0 + DAY, 10.0)];
+    // 10送10，非除权类记录忽略
+    let mut gbbq = vec![xrxd(T0 + DAY, 0.0, 0.0, 10.0, 0.0)];
+    gbbq.push(Gbbq {
+        category: 5,
+        ..xrxd(T0 + DAY, 1e8, 1e8, 2e8, 2e8)
+    });
+
+    let f = factors(&klines, &gbbq, Adjustment::Forward);
+    assert!((f[0] - 0.5).abs() < 1e-9);
+    assert_eq!(f[1], 1.0);
+}
@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+use tdx_rust::align::{join, Field, FillPolicy};
+use tdx_rust::*;
+
+fn kline(time: i64, close: i64) -> Kline {
+    Kline {
+        last: Price(close),
+        open: Price(close),
+        high: Price(close),
+        low: Price(close),
+        close: Price(close),
+        order: 0,
+        volume: close / 10,
+        amount: Amount(0),
+        time,
+        up_count: 0,
+        down_count: 0,
+    }
+}
+
+fn series() -> BTreeMap<String, Vec<Kline>> {
+    let mut map = BTreeMap::new();
+    map.insert(
+        "sz000001".to_string(),
+        vec![kline(1, 10_000), kline(2, 11_000), kline(3, 12_000)],
+    );
+    // 第2个时间点停牌
+    map.insert(
+        "sh600000".to_string(),
+        vec![kline(1, 8_000), kline(3, 9_000)],
+    );
+    map
+}
+
+#[test]
+fn test_join_policies() {
+    let m = join(&series(), Field::Close, FillPolicy::ForwardFill);
+    assert_eq!(m.symbols, ["sh600000", "sz000001"]);
+    assert_eq!(m.times, [1, 2, 3]);
+    assert_eq!(m.column("sh600000").unwrap(), [8.0, 8.0, 9.0]);
+    assert_eq!(m.row(1), [8.0, 11.0]);
+
+    let m = join(&series(), Field::Close, FillPolicy::Nan);
+    assert!(m.row(1)[0].is_nan());
+    assert_eq!(m.rows(), 3);
+
+    let m = join(&series(), Field::Volume, FillPolicy::Drop);
+    assert_eq!(m.times, [1, 3]);
+    assert_eq!(m.cols(), 2);
+    assert_eq!(m.row(1), [900.0, 1200.0]);
+    assert!(m.column("sz000002").is_none());
+}
@@ -0,0 +1,171 @@
+//! 归档格式测试
+
+use tdx_rust::archive::{Archive, ArchiveError, ArchiveHeader, ArchiveReader, HEADER_LEN};
+use tdx_rust::protocol::*;
+
+fn kline(time: i64, close: i64) -> Kline {
+    Kline {
+        last: Price(close - 10),
+        open: Price(close - 5),
+        high: Price(close + 20),
+        low: Price(close - 30),
+        close: Price(close),
+        order: 0,
+        volume: 123_456,
+        amount: Amount(9_876_543_210),
+        time,
+        up_count: 0,
+        down_count: 0,
+    }
+}
+
+#[test]
+fn test_kline_archive_roundtrip() {
+    let klines: Vec<Kline> = (0..1000)
+        .map(|i| kline(1_700_000_000 + i * 60, 10_000 + i))
+        .collect();
+    let bytes = Archive::encode_with_stride(&klines, 100).unwrap();
+
+    let header = ArchiveHeader::decode(&bytes).unwrap();
+    assert_eq!(header.count, 1000);
+    assert_eq!(header.index_len, 10);
+    assert_eq!(bytes.len(), HEADER_LEN + 10 * 8 + 1000 * 56);
+
+    let decoded: Vec<Kline> = Archive::decode(&bytes).unwrap();
+    assert_eq!(decoded.len(), klines.len());
+    for (a, b) in decoded.iter().zip(klines.iter()) {
+        assert_eq!(a.time, b.time);
+        assert_eq!(a.close, b.close);
+        assert_eq!(a.low, b.low);
+        assert_eq!(a.amount, b.amount);
+        assert_eq!(a.volume, b.volume);
+    }
+}
+
+#[test]
+fn test_trade_archive_rejects_wrong_kind_and_order() {
+    let trades = vec![
+        Trade {
+            time: 2,
+            price: Price(10_000),
+            volume: 5,
+            status: TradeStatus::Sell,
+            number: 3,
+        },
+        Trade {
+            time: 1,
+            price: Price(10_010),
+            volume: 1,
+            status: TradeStatus::Buy,
+            number: 1,
+        },
+    ];
+    assert!(matches!(
+        Archive::encode(&trades),
+        Err(ArchiveError::Unsorted(1))
+    ));
+
+    let bytes = Archive::encode(&trades[..1]).unwrap();
+    let decoded: Vec<Trade> = Archive::decode(&bytes).unwrap();
+    assert_eq!(decoded[0].status, TradeStatus::Sell);
+    assert_eq!(decoded[0].number, 3);
+    assert!(matches!(
+        Archive::decode::<Kline>(&bytes),
+        Err(ArchiveError::KindMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_trade_archive_status_range() {
+    let trade = |status| Trade {
+        time: 1,
+        price: Price(10_000),
+        volume: 1,
+        status,
+        number: 1,
+    };
+
+    // 未识别的状态值原样保存
+    let bytes = Archive::encode(&[trade(TradeStatus::Other(7))]).unwrap();
+    let decoded: Vec<Trade> = Archive::decode(&bytes).unwrap();
+    assert_eq!(decoded[0].status, TradeStatus::Other(7));
+
+    // 单字节存不下的状态值报错，不截断
+    for value in [256, -1] {
+        assert!(matches!(
+            Archive::encode(&[trade(TradeStatus::Other(value))]),
+            Err(ArchiveError::OutOfRange("status"))
+        ));
+    }
+}
+
+#[test]
+fn test_archive_reader_seek_by_time() {
+    let klines: Vec<Kline> = (0..1000)
+        .map(|i| kline(1_700_000_000 + i * 60, 10_000 + i))
+        .collect();
+    let path = std::env::temp_dir().join(format!("tdx_archive_{}.bin", std::process::id()));
+    Archive::write_file(&path, &klines).unwrap();
+
+    let reader = ArchiveReader::<Kline>::open(&path).unwrap();
+    assert_eq!(reader.len(), 1000);
+    assert_eq!(reader.get(999).unwrap().unwrap().close, Price(10_999));
+    assert!(reader.get(1000).unwrap().is_none());
+
+    assert_eq!(reader.lower_bound(0).unwrap(), 0);
+    assert_eq!(reader.lower_bound(1_700_000_000 + 300 * 60).unwrap(), 300);
+    assert_eq!(
+        reader.lower_bound(1_700_000_000 + 300 * 60 + 1).unwrap(),
+        301
+    );
+    assert_eq!(reader.lower_bound(i64::MAX).unwrap(), 1000);
+
+    let range = reader
+        .range(1_700_000_000 + 510 * 60, 1_700_000_000 + 519 * 60)
+        .unwrap();
+    assert_eq!(range.len(), 10);
+    assert_eq!(range[0].close, Price(10_510));
+
+    let all: Vec<Kline> = reader.iter().unwrap().map(|r| r.unwrap()).collect();
+    assert_eq!(all.len(), 1000);
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_archive_reader_interleaved() {
+    // 2000笔成交（约42KB），超过一次缓冲的大小
+    let trades: Vec<Trade> = (0..2000)
+        .map(|i| Trade {
+            time: 1_700_000_000 + i,
+            price: Price(10_000 + i),
+            volume: i as i32,
+            status: TradeStatus::Buy,
+            number: 1,
+        })
+        .collect();
+    let path = std::env::temp_dir().join(format!("tdx_archive_mix_{}.bin", std::process::id()));
+    Archive::write_file(&path, &trades).unwrap();
+    let reader = ArchiveReader::<Trade>::open(&path).unwrap();
+
+    let mut iter = reader.iter().unwrap();
+    let mut seen: Vec<i64> = iter.by_ref().take(10).map(|t| t.unwrap().time).collect();
+    // 迭代途中随机读取、查找并开启第二个迭代器
+    assert_eq!(reader.get(1990).unwrap().unwrap().volume, 1990);
+    assert_eq!(reader.lower_bound(1_700_001_500).unwrap(), 1500);
+    let mut second = reader.iter_from(1000).unwrap();
+    for (i, t) in iter.enumerate() {
+        seen.push(t.unwrap().time);
+        if i % 300 == 0 {
+            assert_eq!(
+                second.next().unwrap().unwrap().volume,
+                1000 + (i / 300) as i32
+            );
+            assert!(reader.get(5).unwrap().is_some());
+        }
+    }
+    let expected: Vec<i64> = trades.iter().map(|t| t.time).collect();
+    assert_eq!(seen, expected);
+
+    std::fs::remove_file(&path).ok();
+}
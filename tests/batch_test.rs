@@ -0,0 +1,46 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tdx_rust::mock::MockServer;
+use tdx_rust::*;
+
+fn codes(list: &[&str]) -> Vec<String> {
+    list.iter().map(|c| c.to_string()).collect()
+}
+
+#[tokio::test]
+async fn test_concurrent_quotes_merged() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let client = Arc::new(server.client().await.unwrap());
+    let batcher = QuoteBatcher::new(client, Duration::from_millis(20));
+
+    let a = codes(&["sz000001"]);
+    let b = codes(&["sh600008", "sz000001"]);
+    let c = codes(&["000001", "sz399001"]);
+    let (ra, rb, rc) = tokio::join!(
+        batcher.get_quote(&a),
+        batcher.get_quote(&b),
+        batcher.get_quote(&c)
+    );
+
+    // 三个请求合并为一次，结果按各自的代码顺序分发
+    assert_eq!(server.count(MessageType::Quote), 1);
+    let names = |quotes: Vec<QuoteInfo>| -> Vec<String> {
+        quotes
+            .iter()
+            .map(|q| format!("{}{}", q.exchange.as_str(), q.code))
+            .collect()
+    };
+    assert_eq!(names(ra.unwrap()), ["sz000001"]);
+    assert_eq!(names(rb.unwrap()), ["sh600008", "sz000001"]);
+    // 服务器未返回的代码被跳过
+    assert_eq!(names(rc.unwrap()), ["sz000001"]);
+
+    // 窗口结束后的请求单独发送
+    batcher.get_quote(&a).await.unwrap();
+    assert_eq!(server.count(MessageType::Quote), 2);
+}
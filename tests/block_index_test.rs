@@ -0,0 +1,62 @@
+use tdx_rust::*;
+
+fn index(blocks: &[(&str, &[&str])]) -> BlockIndex {
+    BlockIndex::new(
+        blocks
+            .iter()
+            .map(|(b, codes)| (b.to_string(), codes.iter().map(|c| c.to_string()))),
+    )
+}
+
+#[test]
+fn test_block_index_lookup_and_update() {
+    let mut idx = index(&[
+        ("银行", &["sz000001", "sh600036"]),
+        ("深证成指", &["sz000001", "sz000002"]),
+    ]);
+    assert_eq!(idx.len(), 2);
+    assert_eq!(idx.blocks_of("sz000001"), ["深证成指", "银行"]);
+    assert_eq!(idx.blocks_of("sh600036"), ["银行"]);
+    assert!(idx.blocks_of("sh600519").is_empty());
+
+    let changes = idx.update(index(&[
+        ("银行", &["sz000001", "sh600036", "sh601398"]),
+        ("白酒", &["sh600519"]),
+    ]));
+    assert_eq!(
+        changes,
+        [
+            BlockChange::Left {
+                code: "sz000001".into(),
+                block: "深证成指".into()
+            },
+            BlockChange::Left {
+                code: "sz000002".into(),
+                block: "深证成指".into()
+            },
+            BlockChange::Joined {
+                code: "sh600519".into(),
+                block: "白酒".into()
+            },
+            BlockChange::Joined {
+                code: "sh601398".into(),
+                block: "银行".into()
+            },
+        ]
+    );
+    assert_eq!(idx.blocks_of("sz000001"), ["银行"]);
+    assert!(idx.blocks_of("sz000002").is_empty());
+}
+
+#[test]
+fn test_block_index_save_load() {
+    let idx = index(&[("银行", &["sz000001", "sh600036"])]);
+    let path = std::env::temp_dir().join(format!("tdx_blocks_{}.json", std::process::id()));
+    idx.save(&path).unwrap();
+    let loaded = BlockIndex::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.block_names().collect::<Vec<_>>(), ["银行"]);
+    assert_eq!(loaded.blocks_of("sh600036"), ["银行"]);
+    assert_eq!(loaded.codes_of("银行").unwrap().len(), 2);
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "blocking")]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use tdx_rust::*;
+
+/// 应答所有请求（空响应），返回收到的消息类型
+fn serve(mut stream: TcpStream) -> Vec<u16> {
+    let mut types = Vec::new();
+    loop {
+        let mut header = [0u8; 10];
+        if stream.read_exact(&mut header).is_err() {
+            return types;
+        }
+        let length = u16::from_le_bytes([header[6], header[7]]) as usize;
+        let mut body = vec![0u8; length];
+        stream.read_exact(&mut body).unwrap();
+        let msg_type = u16::from_le_bytes([body[0], body[1]]);
+        types.push(msg_type);
+
+        let mut frame = PREFIX_RESP.to_be_bytes().to_vec();
+        frame.push(CONTROL_RESP_SUCCESS);
+        frame.extend_from_slice(&header[1..5]);
+        frame.push(0);
+        frame.extend_from_slice(&msg_type.to_le_bytes());
+        // 股票数量响应：2字节数量
+        frame.extend_from_slice(&[2, 0, 2, 0, 42, 0]);
+        stream.write_all(&frame).unwrap();
+    }
+}
+
+#[test]
+fn test_blocking_client() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let server = std::thread::spawn(move || serve(listener.accept().unwrap().0));
+
+    let client = blocking::Client::connect(&addr).unwrap();
+    assert_eq!(client.get_count(Exchange::SZ).unwrap(), 42);
+    drop(client);
+
+    let types = server.join().unwrap();
+    assert_eq!(
+        types,
+        [MessageType::Connect as u16, MessageType::Count as u16]
+    );
+}
@@ -0,0 +1,101 @@
+use tdx_rust::classify::{classify, is_auction_time};
+use tdx_rust::protocol::test_data::TestData;
+use tdx_rust::*;
+
+// 2024-01-02 09:25 北京时间
+const OPEN_AUCTION: i64 = 1704158700;
+const MINUTE: i64 = 60;
+
+fn quote(bid: i64, ask: i64) -> QuoteInfo {
+    let content = std::fs::read_to_string("tdx-test/test-data/quote.json").unwrap();
+    let test_data: TestData = serde_json::from_str(&content).unwrap();
+    let response = ResponseFrame::decode(&test_data.decode_response().unwrap()).unwrap();
+    let mut q = Quote::decode_response(&response.data).unwrap().remove(0);
+    q.buy_level[0].price = Price(bid);
+    q.sell_level[0].price = Price(ask);
+    q
+}
+
+fn trade(time: i64, price: i64, status: TradeStatus) -> Trade {
+    Trade {
+        time,
+        price: Price(price),
+        volume: 1,
+        status,
+        number: 1,
+    }
+}
+
+#[test]
+fn test_auction_time() {
+    assert!(is_auction_time(OPEN_AUCTION));
+    assert!(!is_auction_time(OPEN_AUCTION + 5 * MINUTE));
+    // 15:00
+    assert!(is_auction_time(OPEN_AUCTION + 335 * MINUTE));
+    assert!(!is_auction_time(OPEN_AUCTION + 334 * MINUTE));
+}
+
+#[test]
+fn test_classify() {
+    use TradeStatus::*;
+    let t = OPEN_AUCTION + 10 * MINUTE;
+    let trades = vec![
+        trade(OPEN_AUCTION, 10_000, Neutral),
+        // 无盘口：使用服务器状态，再使用 tick 规则
+        trade(t, 10_000, Sell),
+        trade(t, 10_010, Neutral),
+        trade(t, 10_010, Neutral),
+        // 有盘口：10.00 / 10.02
+        trade(t + MINUTE, 10_020, Sell),
+        trade(t + MINUTE, 10_000, Neutral),
+        trade(t + MINUTE, 10_010, Neutral),
+    ];
+    let quotes = vec![(t + MINUTE, quote(10_000, 10_020))];
+
+    assert_eq!(
+        classify(&trades, &quotes),
+        [
+            InferredSide::Auction,
+            InferredSide::Sell,
+            InferredSide::Buy,
+            InferredSide::Buy,
+            InferredSide::Buy,
+            InferredSide::Sell,
+            InferredSide::Cross,
+        ]
+    );
+}
+
+#[test]
+fn test_classify_auction_status() {
+    // 服务器标记为集合竞价的成交即使时间落在连续竞价时段内，也不计入买卖，也不影响 tick 规则
+    let t = OPEN_AUCTION + 5 * MINUTE;
+    let trades = vec![
+        trade(t, 10_000, TradeStatus::Buy),
+        trade(t, 10_050, TradeStatus::Auction),
+        trade(t + MINUTE, 10_000, TradeStatus::Neutral),
+    ];
+    assert_eq!(
+        classify(&trades, &[]),
+        [InferredSide::Buy, InferredSide::Auction, InferredSide::Buy]
+    );
+}
+
+#[test]
+fn test_classify_minute_trades() {
+    // 分时成交时间为 HH:MM:00，使用同一分钟内收到的快照，而不是上一分钟的旧盘口
+    let t = OPEN_AUCTION + 10 * MINUTE;
+    let trades = vec![
+        trade(t, 10_050, TradeStatus::Neutral),
+        trade(t + MINUTE, 10_070, TradeStatus::Neutral),
+    ];
+    let quotes = vec![
+        (t - 5, quote(10_000, 10_020)),
+        (t + 20, quote(10_050, 10_070)),
+        (t + MINUTE + 59, quote(10_060, 10_080)),
+    ];
+    assert_eq!(
+        classify(&trades, &quotes),
+        [InferredSide::Sell, InferredSide::Cross]
+    );
+}
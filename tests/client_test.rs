@@ -0,0 +1,506 @@
+use std::time::{Duration, Instant};
+use tdx_rust::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// 读取一个请求帧，返回 (消息ID, 消息类型)
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<(u32, u16)> {
+    let mut header = [0u8; 10];
+    stream.read_exact(&mut header).await?;
+    let length = u16::from_le_bytes([header[6], header[7]]) as usize;
+    let mut body = vec![0u8; length];
+    stream.read_exact(&mut body).await?;
+    let msg_id = u32::from_le_bytes([header[1], header[2], header[3], header[4]]);
+    Ok((msg_id, u16::from_le_bytes([body[0], body[1]])))
+}
+
+/// 回复一个未压缩的响应
+async fn write_response(stream: &mut TcpStream, msg_id: u32, msg_type: u16, data: &[u8]) {
+    let mut frame = PREFIX_RESP.to_be_bytes().to_vec();
+    frame.push(CONTROL_RESP_SUCCESS);
+    frame.extend_from_slice(&msg_id.to_le_bytes());
+    frame.push(0);
+    frame.extend_from_slice(&msg_type.to_le_bytes());
+    frame.extend_from_slice(&(data.len() as u16).to_le_bytes());
+    frame.extend_from_slice(&(data.len() as u16).to_le_bytes());
+    frame.extend_from_slice(data);
+    stream.write_all(&frame).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_reconnect_after_disconnect() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    tokio::spawn(async move {
+        // 第一个连接：完成握手后，收到请求即断开
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let (id, ty) = read_request(&mut stream).await.unwrap();
+        write_response(&mut stream, id, ty, &[]).await;
+        read_request(&mut stream).await.unwrap();
+        drop(stream);
+
+        // 第二个连接：正常应答
+        let (mut stream, _) = listener.accept().await.unwrap();
+        while let Ok((id, ty)) = read_request(&mut stream).await {
+            write_response(&mut stream, id, ty, &[]).await;
+        }
+    });
+
+    let client = Client::connect(&addr).await.unwrap();
+    client.send_heartbeat().await.unwrap();
+    assert_eq!(client.addr(), addr);
+    client.send_heartbeat().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_reconnect_replay_rate_limited() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let (id, ty) = read_request(&mut stream).await.unwrap();
+        write_response(&mut stream, id, ty, &[]).await;
+        read_request(&mut stream).await.unwrap();
+        drop(stream);
+
+        let (mut stream, _) = listener.accept().await.unwrap();
+        while let Ok((id, ty)) = read_request(&mut stream).await {
+            write_response(&mut stream, id, ty, &[]).await;
+        }
+    });
+
+    let mut client = Client::connect(&addr).await.unwrap();
+    client.set_rate_limit(2.0);
+    // 断线重发也消耗令牌：两个令牌用完后，下一个请求需要等待约0.5秒
+    client.send_heartbeat().await.unwrap();
+    let start = Instant::now();
+    client.send_heartbeat().await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn test_reconnect_disabled() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let (id, ty) = read_request(&mut stream).await.unwrap();
+        write_response(&mut stream, id, ty, &[]).await;
+        read_request(&mut stream).await.unwrap();
+    });
+
+    let mut client = Client::connect(&addr).await.unwrap();
+    client.set_reconnect(false);
+    let err = client.send_heartbeat().await.unwrap_err();
+    assert!(matches!(err, ClientError::Io(_)), "{:?}", err);
+}
+
+#[tokio::test]
+async fn test_concurrent_requests_out_of_order() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let (id, ty) = read_request(&mut stream).await.unwrap();
+        write_response(&mut stream, id, ty, &[]).await;
+
+        // 收齐两个请求后倒序应答
+        let first = read_request(&mut stream).await.unwrap();
+        let second = read_request(&mut stream).await.unwrap();
+        write_response(&mut stream, second.0, second.1, &[]).await;
+        write_response(&mut stream, first.0, first.1, &[]).await;
+        let _ = read_request(&mut stream).await;
+    });
+
+    let client = Client::connect(&addr).await.unwrap();
+    let (a, b) = tokio::join!(
+        client.send_frame(Heartbeat::request(0)),
+        client.send_frame(Heartbeat::request(0))
+    );
+    let (a, b) = (a.unwrap(), b.unwrap());
+    assert_ne!(a.msg_id, b.msg_id);
+}
+
+#[tokio::test]
+async fn test_get_quote_batch_chunks() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let (id, ty) = read_request(&mut stream).await.unwrap();
+        write_response(&mut stream, id, ty, &[]).await;
+
+        // 每个行情请求回复0条行情
+        let mut requests = 0;
+        while let Ok((id, ty)) = read_request(&mut stream).await {
+            requests += 1;
+            write_response(&mut stream, id, ty, &[0, 0, 0, 0]).await;
+        }
+        requests
+    });
+
+    let client = Client::connect(&addr).await.unwrap();
+    let codes: Vec<String> = (0..170).map(|i| format!("sz{:06}", i)).collect();
+    let quotes = client.get_quote_batch(&codes).await.unwrap();
+    assert!(quotes.is_empty());
+    drop(client);
+    assert_eq!(server.await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn test_send_frame_with_retry() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let (id, ty) = read_request(&mut stream).await.unwrap();
+        write_response(&mut stream, id, ty, &[]).await;
+
+        // 丢弃前两个请求，应答之后的请求
+        read_request(&mut stream).await.unwrap();
+        read_request(&mut stream).await.unwrap();
+        while let Ok((id, ty)) = read_request(&mut stream).await {
+            write_response(&mut stream, id, ty, &[]).await;
+        }
+    });
+
+    let client = Client::connect(&addr).await.unwrap();
+    let options = RequestOptions::new()
+        .timeout(Duration::from_millis(100))
+        .max_retries(1)
+        .backoff(Duration::from_millis(10));
+
+    let no_retry = options.max_retries(0);
+    let err = client
+        .send_frame_with(Heartbeat::request(0), &no_retry)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::Timeout), "{:?}", err);
+
+    // 第一次尝试超时，重试成功
+    client
+        .send_frame_with(Heartbeat::request(0), &options)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn test_request_options_backoff() {
+    let options = RequestOptions::new().backoff(Duration::from_millis(100));
+    assert_eq!(options.backoff_for(1), Duration::from_millis(100));
+    assert_eq!(options.backoff_for(3), Duration::from_millis(400));
+    assert_eq!(options.max_retries, 0);
+}
+
+#[tokio::test]
+async fn test_builder_without_handshake() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let (mut stream, peer) = listener.accept().await.unwrap();
+        // 未握手，第一个请求就是心跳
+        let (id, ty) = read_request(&mut stream).await.unwrap();
+        write_response(&mut stream, id, ty, &[]).await;
+        (ty, peer)
+    });
+
+    let client = Client::builder()
+        .connect_timeout(Duration::from_secs(1))
+        .read_timeout(Duration::from_secs(1))
+        .keepalive(true)
+        .local_addr("127.0.0.1:0".parse().unwrap())
+        .handshake(false)
+        .connect(&addr)
+        .await
+        .unwrap();
+    assert_eq!(client.request_options().timeout, Duration::from_secs(1));
+    client.send_heartbeat().await.unwrap();
+
+    let (ty, peer) = server.await.unwrap();
+    assert_eq!(ty, MessageType::Heart as u16);
+    assert!(peer.ip().is_loopback());
+}
+
+#[tokio::test]
+async fn test_frame_observer() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        while let Ok((id, ty)) = read_request(&mut stream).await {
+            write_response(&mut stream, id, ty, &[0x2a, 0x00]).await;
+        }
+    });
+
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut client = Client::connect(&addr).await.unwrap();
+    let log = seen.clone();
+    client.set_frame_observer(move |req, resp| {
+        log.lock()
+            .unwrap()
+            .push((req.msg_id, resp.msg_id, resp.msg_type, resp.data.clone()));
+    });
+
+    assert_eq!(client.get_count(Exchange::SZ).await.unwrap(), 42);
+    client.send_heartbeat().await.unwrap();
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 2);
+    assert_eq!(seen[0].0, seen[0].1);
+    assert_eq!(seen[0].2, MessageType::Count);
+    assert_eq!(seen[0].3, vec![0x2a, 0x00]);
+    assert_eq!(seen[1].2, MessageType::Heart);
+}
+
+#[tokio::test]
+async fn test_request_id_tracing() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        while let Ok((id, ty)) = read_request(&mut stream).await {
+            write_response(&mut stream, id, ty, &[]).await;
+        }
+    });
+
+    let ids = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut client = Client::connect(&addr).await.unwrap();
+    let log = ids.clone();
+    client.set_frame_observer(move |_, _| log.lock().unwrap().push(current_request_id()));
+
+    // 每次调用单独分配追踪ID
+    client.send_heartbeat().await.unwrap();
+    client.send_heartbeat().await.unwrap();
+    // traced 范围内的多次请求共用外层ID
+    let outer = traced(async {
+        client.send_heartbeat().await.unwrap();
+        client.send_heartbeat().await.unwrap();
+        current_request_id()
+    })
+    .await;
+    assert!(current_request_id().is_none());
+
+    let ids = ids.lock().unwrap();
+    assert!(ids.iter().all(Option::is_some));
+    assert_ne!(ids[0], ids[1]);
+    assert_eq!(ids[2], outer);
+    assert_eq!(ids[3], outer);
+    assert_ne!(ids[1], outer);
+}
+
+#[tokio::test]
+async fn test_connect_info() {
+    use tdx_rust::protocol::test_data::TestData;
+
+    let content = std::fs::read_to_string("tdx-test/test-data/connect.json").unwrap();
+    let test_data: TestData = serde_json::from_str(&content).unwrap();
+    let info = ResponseFrame::decode(&test_data.decode_response().unwrap())
+        .unwrap()
+        .data;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let (id, ty) = read_request(&mut stream).await.unwrap();
+        write_response(&mut stream, id, ty, &info).await;
+        while let Ok((id, ty)) = read_request(&mut stream).await {
+            write_response(&mut stream, id, ty, &[]).await;
+        }
+    });
+
+    let client = Client::connect(&addr).await.unwrap();
+    let info = client.connect_info().unwrap();
+    assert_eq!(info.name, "上海双线主站14");
+    assert_eq!(info.trading_hours.len(), 2);
+
+    // 不握手时没有服务器信息
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        let _ = listener.accept().await;
+    });
+    let client = Client::builder()
+        .handshake(false)
+        .connect(&addr)
+        .await
+        .unwrap();
+    assert!(client.connect_info().is_none());
+}
+
+#[tokio::test]
+async fn test_lifecycle_events() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    tokio::spawn(async move {
+        // 第一个连接：完成握手后，收到请求即断开
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let (id, ty) = read_request(&mut stream).await.unwrap();
+        write_response(&mut stream, id, ty, &[]).await;
+        read_request(&mut stream).await.unwrap();
+        drop(stream);
+
+        // 第二个连接：应答握手和一次心跳后断开，且不再接受连接
+        let (mut stream, _) = listener.accept().await.unwrap();
+        for _ in 0..2 {
+            let (id, ty) = read_request(&mut stream).await.unwrap();
+            write_response(&mut stream, id, ty, &[]).await;
+        }
+        read_request(&mut stream).await.unwrap();
+    });
+
+    let events = events::channel();
+    let mut rx = events.subscribe();
+    let mut client = Client::builder()
+        .events(events)
+        .connect(&addr)
+        .await
+        .unwrap();
+    client.send_heartbeat().await.unwrap();
+    client.set_reconnect(false);
+    assert!(client.send_heartbeat().await.is_err());
+    drop(client);
+
+    let mut received = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        received.push(event);
+    }
+    assert!(
+        matches!(
+            received.as_slice(),
+            [
+                LifecycleEvent::Connected { addr: a },
+                LifecycleEvent::Closed { .. },
+                LifecycleEvent::Reconnected { addr: b },
+                LifecycleEvent::Closed { .. },
+                LifecycleEvent::HeartbeatMissed { .. },
+            ] if *a == addr && *b == addr
+        ),
+        "{:?}",
+        received
+    );
+}
+
+#[tokio::test]
+async fn test_closed_event_on_drop() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        while let Ok((id, ty)) = read_request(&mut stream).await {
+            write_response(&mut stream, id, ty, &[]).await;
+        }
+    });
+
+    let client = Client::connect(&addr).await.unwrap();
+    let mut rx = client.subscribe_events();
+    drop(client);
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        LifecycleEvent::Closed {
+            addr,
+            reason: "客户端已释放".to_string(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_response_split_across_segments() {
+    use tdx_rust::protocol::test_data::TestData;
+
+    // 真实的行情响应，每7字节一段写出，每段之间暂停
+    let content = std::fs::read_to_string("tdx-test/test-data/quote.json").unwrap();
+    let test_data: TestData = serde_json::from_str(&content).unwrap();
+    let response = test_data.decode_response().unwrap();
+    let expected = Quote::decode_response(&ResponseFrame::decode(&response).unwrap().data)
+        .unwrap()
+        .len();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let (id, ty) = read_request(&mut stream).await.unwrap();
+        write_response(&mut stream, id, ty, &[]).await;
+
+        let (id, _) = read_request(&mut stream).await.unwrap();
+        let mut frame = response.clone();
+        frame[5..9].copy_from_slice(&id.to_le_bytes());
+        for chunk in frame.chunks(7) {
+            stream.write_all(chunk).await.unwrap();
+            stream.flush().await.unwrap();
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        let _ = read_request(&mut stream).await;
+    });
+
+    let client = Client::connect(&addr).await.unwrap();
+    let codes = vec!["sz000001".to_string(), "sh600008".to_string()];
+    assert_eq!(client.get_quote(&codes).await.unwrap().len(), expected);
+}
+
+#[tokio::test]
+async fn test_corrupt_frame_keeps_connection() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let accepted = Arc::new(AtomicUsize::new(0));
+    let counter = accepted.clone();
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let first = counter.fetch_add(1, Ordering::SeqCst) == 0;
+            tokio::spawn(async move {
+                let (id, ty) = read_request(&mut stream).await.unwrap();
+                write_response(&mut stream, id, ty, &[]).await;
+                if first {
+                    // 两个请求同时等待：第一个的响应无法解压，第二个正常
+                    let (a, ty_a) = read_request(&mut stream).await.unwrap();
+                    let (b, ty_b) = read_request(&mut stream).await.unwrap();
+                    let mut frame = PREFIX_RESP.to_be_bytes().to_vec();
+                    frame.push(CONTROL_RESP_SUCCESS);
+                    frame.extend_from_slice(&a.to_le_bytes());
+                    frame.push(0);
+                    frame.extend_from_slice(&ty_a.to_le_bytes());
+                    frame.extend_from_slice(&4u16.to_le_bytes());
+                    frame.extend_from_slice(&10u16.to_le_bytes());
+                    frame.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+                    stream.write_all(&frame).await.unwrap();
+                    write_response(&mut stream, b, ty_b, &[5, 0]).await;
+                }
+                while let Ok((id, ty)) = read_request(&mut stream).await {
+                    write_response(&mut stream, id, ty, &[7, 0]).await;
+                }
+            });
+        }
+    });
+
+    let client = Client::connect(&addr).await.unwrap();
+    let mut events = client.subscribe_events();
+    let (a, b) = tokio::join!(
+        client.get_count(Exchange::SZ),
+        client.get_count(Exchange::SH)
+    );
+    // 哪个请求先发出不确定，恰好一个失败
+    let mut results = [a, b];
+    results.sort_by_key(|r| r.is_ok());
+    assert!(matches!(results[0], Err(ClientError::Protocol(_))));
+    assert_eq!(*results[1].as_ref().unwrap(), 5);
+
+    // 连接仍然可用，没有断开重连
+    assert_eq!(client.get_count(Exchange::SZ).await.unwrap(), 7);
+    assert_eq!(accepted.load(Ordering::SeqCst), 1);
+    assert!(events.try_recv().is_err());
+}
@@ -0,0 +1,43 @@
+use chrono::{Duration, TimeZone, Utc};
+use std::sync::Arc;
+use tdx_rust::mock::MockServer;
+use tdx_rust::*;
+
+#[test]
+fn test_fixed_clock() {
+    // 2024-01-02 16:30 UTC = 2024-01-03 00:30 北京时间
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 1, 2, 16, 30, 0).unwrap());
+    assert_eq!(clock.today(), "20240103");
+
+    clock.advance(Duration::hours(-1));
+    assert_eq!(clock.today(), "20240102");
+
+    clock.set(Utc.with_ymd_and_hms(2025, 6, 30, 1, 0, 0).unwrap());
+    assert_eq!(clock.today(), "20250630");
+    assert!(SystemClock.today().len() == 8);
+}
+
+#[tokio::test]
+async fn test_builder_clock() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let clock = Arc::new(FixedClock::new(
+        Utc.with_ymd_and_hms(2024, 10, 16, 2, 0, 0).unwrap(),
+    ));
+    let client = Arc::new(
+        Client::builder()
+            .clock(clock.clone())
+            .connect(server.addr())
+            .await
+            .unwrap(),
+    );
+    assert_eq!(client.clock().today(), "20241016");
+
+    // 共享的是同一个时钟
+    clock.advance(Duration::days(1));
+    assert_eq!(client.clock().today(), "20241017");
+}
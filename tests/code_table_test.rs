@@ -0,0 +1,40 @@
+use tdx_rust::*;
+
+fn stock(code: &str, name: &str) -> StockCode {
+    StockCode {
+        name: name.to_string(),
+        code: code.to_string(),
+        multiple: 100,
+        decimal: 2,
+        last_price: 0.0,
+    }
+}
+
+#[test]
+fn test_code_table_update() {
+    let mut table = CodeTable::new(
+        Exchange::SZ,
+        vec![stock("000001", "平安银行"), stock("000002", "万科A")],
+    );
+    assert_eq!(table.len(), 2);
+
+    let events = table.update(vec![
+        stock("000001", "平安银行"),
+        stock("000002", "万科B"),
+        stock("000003", "新股"),
+    ]);
+    assert_eq!(events.len(), 2);
+    assert!(matches!(
+        &events[0],
+        CodeTableEvent::Renamed { code, old_name, new_name, .. }
+            if code == "000002" && old_name == "万科A" && new_name == "万科B"
+    ));
+    assert!(matches!(&events[1], CodeTableEvent::Added(Exchange::SZ, c) if c.code == "000003"));
+
+    let events = table.update(vec![stock("000003", "新股")]);
+    assert_eq!(events.len(), 2);
+    assert!(matches!(&events[0], CodeTableEvent::Removed(_, c) if c.code == "000001"));
+    assert!(matches!(&events[1], CodeTableEvent::Removed(_, c) if c.code == "000002"));
+    assert_eq!(table.len(), 1);
+    assert_eq!(table.get("000003").unwrap().name, "新股");
+}
@@ -0,0 +1,166 @@
+use chrono::{TimeZone, Utc};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tdx_rust::cursor::{kline_key, trade_key};
+use tdx_rust::mock::MockServer;
+use tdx_rust::*;
+
+#[test]
+fn test_file_cursor_store() {
+    let path = std::env::temp_dir().join(format!("tdx_cursor_{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    // 文件不存在时从空白开始
+    let store = FileCursorStore::open(&path).unwrap();
+    assert_eq!(store.load(&trade_key("000001")).unwrap(), None);
+
+    let cursor = SyncCursor {
+        last_bar_time: Some(1729062000),
+        trade_date: "20241016".to_string(),
+        trade_count: 42,
+    };
+    store.save(&trade_key("000001"), &cursor).unwrap();
+    store
+        .save(
+            &kline_key(KlineType::Day, "sh600008"),
+            &SyncCursor::default(),
+        )
+        .unwrap();
+
+    let reopened = FileCursorStore::open(&path).unwrap();
+    assert_eq!(
+        reopened.load("sz000001/trade").unwrap(),
+        Some(cursor.clone())
+    );
+    assert_eq!(
+        reopened.load("sh600008/kline9").unwrap(),
+        Some(SyncCursor::default())
+    );
+    assert_eq!(reopened.load("sz000002/trade").unwrap(), None);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_sync_klines() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+    let store = MemoryCursorStore::new();
+
+    // 首次同步下载全部K线
+    let all = sync_klines(&client, &store, KlineType::Day, "sz000001")
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 10);
+    let key = kline_key(KlineType::Day, "sz000001");
+    assert_eq!(
+        store.load(&key).unwrap().unwrap().last_bar_time,
+        Some(all[9].time)
+    );
+
+    // 没有新K线
+    let none = sync_klines(&client, &store, KlineType::Day, "sz000001")
+        .await
+        .unwrap();
+    assert!(none.is_empty());
+
+    // 从第7根之后继续
+    let cursor = SyncCursor {
+        last_bar_time: Some(all[6].time),
+        ..Default::default()
+    };
+    store.save(&key, &cursor).unwrap();
+    let rest = sync_klines(&client, &store, KlineType::Day, "sz000001")
+        .await
+        .unwrap();
+    let times: Vec<i64> = rest.iter().map(|k| k.time).collect();
+    assert_eq!(times, [all[7].time, all[8].time, all[9].time]);
+
+    // 服务器已没有上次同步的K线：报错且不移动同步位置
+    let cursor = SyncCursor {
+        last_bar_time: Some(all[0].time - 24 * 3600),
+        ..Default::default()
+    };
+    store.save(&key, &cursor).unwrap();
+    let err = sync_klines(&client, &store, KlineType::Day, "sz000001")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, CursorError::KlineGap(t) if t == all[0].time - 24 * 3600));
+    assert_eq!(store.load(&key).unwrap().unwrap(), cursor);
+}
+
+/// 分时成交响应：(分钟, 价格（分）, 成交量（手）)
+fn trade_page(trades: &[(u16, i32, i32)]) -> Vec<u8> {
+    let mut data = (trades.len() as u16).to_le_bytes().to_vec();
+    let mut last = 0;
+    for &(time, price, volume) in trades {
+        data.extend_from_slice(&time.to_le_bytes());
+        for v in [price - last, volume, 1, 0, 0] {
+            data.extend_from_slice(&encode_varint(v));
+        }
+        last = price;
+    }
+    data
+}
+
+#[tokio::test]
+async fn test_stream_trades_resumable() {
+    let trades: Arc<Mutex<Vec<(u16, i32, i32)>>> = Arc::new(Mutex::new(
+        (0..5).map(|i| (9 * 60 + 30 + i, 1000, 10)).collect(),
+    ));
+    let list = trades.clone();
+    let server = MockServer::builder()
+        .handle(MessageType::MinuteTrade, move |req| {
+            // start 为距最新一笔的偏移
+            let start = u16::from_le_bytes([req[8], req[9]]) as usize;
+            let count = u16::from_le_bytes([req[10], req[11]]) as usize;
+            let list = list.lock().unwrap();
+            let end = list.len().saturating_sub(start);
+            trade_page(&list[end.saturating_sub(count)..end])
+        })
+        .start()
+        .await
+        .unwrap();
+    let connect = || async {
+        let mut client = server.client().await.unwrap();
+        client.set_clock(Arc::new(FixedClock::new(
+            Utc.with_ymd_and_hms(2024, 10, 16, 2, 0, 0).unwrap(),
+        )));
+        Arc::new(client)
+    };
+    let store: Arc<dyn CursorStore> = Arc::new(MemoryCursorStore::new());
+    let interval = Duration::from_millis(10);
+
+    let mut rx = stream_trades_resumable(
+        connect().await,
+        "000001".to_string(),
+        interval,
+        store.clone(),
+    );
+    assert_eq!(rx.recv().await.unwrap().len(), 5);
+    drop(rx);
+    // 推送后才保存同步位置
+    let saved = loop {
+        match store.load(&trade_key("sz000001")).unwrap() {
+            Some(cursor) => break cursor,
+            None => tokio::time::sleep(interval).await,
+        }
+    };
+    assert_eq!(saved.trade_date, "20241016");
+    assert_eq!(saved.trade_count, 5);
+
+    // 重启期间又成交了3笔，恢复后只推送这3笔
+    trades
+        .lock()
+        .unwrap()
+        .extend((5..8).map(|i| (9 * 60 + 30 + i, 1001, 20)));
+    let mut rx = stream_trades_resumable(connect().await, "000001".to_string(), interval, store);
+    let resumed = rx.recv().await.unwrap();
+    assert_eq!(resumed.len(), 3);
+    assert!(resumed.iter().all(|t| t.volume == 20));
+}
@@ -0,0 +1,61 @@
+use tdx_rust::protocol::test_data::TestData;
+use tdx_rust::*;
+
+fn quotes() -> Vec<QuoteInfo> {
+    let content = std::fs::read_to_string("tdx-test/test-data/quote.json").unwrap();
+    let test_data: TestData = serde_json::from_str(&content).unwrap();
+    let response = ResponseFrame::decode(&test_data.decode_response().unwrap()).unwrap();
+    Quote::decode_response(&response.data).unwrap()
+}
+
+fn json(q: &QuoteInfo) -> serde_json::Value {
+    serde_json::to_value(q).unwrap()
+}
+
+#[test]
+fn test_quote_delta_roundtrip() {
+    let base = quotes();
+    assert!(!base.is_empty());
+
+    // 模拟连续三个快照：第二个只有现价和一档变化，第三个不变
+    let mut snapshots = vec![base.clone()];
+    let mut next = base.clone();
+    next[0].k.close = Price(next[0].k.close.0 + 10);
+    next[0].buy_level[0].number += 7;
+    next[0].amount = Amount(next[0].amount.0 + 1_000_000);
+    snapshots.push(next.clone());
+    snapshots.push(next);
+
+    let mut encoder = QuoteDeltaEncoder::new();
+    let mut sizes = Vec::new();
+    let mut buf = Vec::new();
+    for snapshot in &snapshots {
+        let before = buf.len();
+        for q in snapshot {
+            encoder.encode(q, &mut buf).unwrap();
+        }
+        sizes.push(buf.len() - before);
+    }
+    // 后续快照只保存变化的字段
+    assert!(sizes[1] < sizes[0] / 2);
+    assert!(sizes[2] < sizes[1]);
+
+    let decoded = QuoteDeltaDecoder::new().decode_all(&buf).unwrap();
+    let expected: Vec<&QuoteInfo> = snapshots.iter().flatten().collect();
+    assert_eq!(decoded.len(), expected.len());
+    for (a, b) in decoded.iter().zip(expected) {
+        assert_eq!(json(a), json(b));
+    }
+}
+
+#[test]
+fn test_quote_delta_truncated() {
+    let mut buf = Vec::new();
+    QuoteDeltaEncoder::new()
+        .encode(&quotes()[0], &mut buf)
+        .unwrap();
+    let err = QuoteDeltaDecoder::new()
+        .decode(&buf[..buf.len() - 1])
+        .unwrap_err();
+    assert!(matches!(err, DeltaError::Truncated));
+}
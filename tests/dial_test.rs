@@ -0,0 +1,98 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use tdx_rust::mock::MockServer;
+use tdx_rust::*;
+use tokio::net::TcpListener;
+
+/// 绑定后立即释放，得到一个无人监听的端口
+async fn closed_port(ip: &str) -> u16 {
+    let listener = TcpListener::bind((ip, 0)).await.unwrap();
+    listener.local_addr().unwrap().port()
+}
+
+fn port_of(addr: &str) -> u16 {
+    addr.rsplit_once(':').unwrap().1.parse().unwrap()
+}
+
+#[test]
+fn test_default_port() {
+    assert_eq!(default_port(Exchange::SZ), DEFAULT_PORT);
+    assert_eq!(default_port(Exchange::SH), 7709);
+    assert_eq!(default_port(Exchange::BJ), 7709);
+    assert_eq!(ALTERNATE_PORTS[0], DEFAULT_PORT);
+}
+
+#[test]
+fn test_choose_host_seeded() {
+    let hosts = ["10.0.0.1", "10.0.0.2", "10.0.0.3", "10.0.0.4"];
+    let pick = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..16)
+            .map(|_| choose_host(&hosts, &mut rng).unwrap())
+            .collect::<Vec<_>>()
+    };
+    // 相同种子选择顺序相同，不同种子不同
+    assert_eq!(pick(42), pick(42));
+    assert_ne!(pick(42), pick(43));
+
+    let mut rng = StdRng::seed_from_u64(1);
+    let host = choose_host(&[], &mut rng).unwrap();
+    assert!(dial::DEFAULT_HOSTS.contains(&host));
+}
+
+#[tokio::test]
+async fn test_dial_ports() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let open = port_of(server.addr());
+    let closed = closed_port("127.0.0.1").await;
+
+    // 第一个端口不可用，使用第二个端口完成握手
+    let (client, port) = dial_ports("127.0.0.1", &[closed, open]).await.unwrap();
+    assert_eq!(port, open);
+    assert_eq!(client.addr(), server.addr());
+
+    // 地址中已指定端口时只尝试该端口
+    let addr = format!("127.0.0.1:{}", closed);
+    assert!(dial_ports(&addr, &[open]).await.is_err());
+    assert!(dial_ports("127.0.0.1", &[closed]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_fast_hosts_ports() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let open = listener.local_addr().unwrap().port();
+    let closed = closed_port("127.0.0.1").await;
+
+    let results = fast_hosts_ports(&["127.0.0.1"], &[closed, open]).await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].port, open);
+    assert_eq!(results[0].addr(), format!("127.0.0.1:{}", open));
+
+    // 地址中指定了不可用的端口时不探测其他端口
+    let addr = format!("127.0.0.1:{}", closed);
+    assert!(fast_hosts_ports(&[&addr], &[open]).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_fast_hosts_ipv6() {
+    let Ok(listener) = TcpListener::bind("[::1]:0").await else {
+        // 环境不支持 IPv6
+        return;
+    };
+    let open = listener.local_addr().unwrap().port();
+
+    // 不带方括号的 IPv6 地址视为未指定端口
+    let results = fast_hosts_ports(&["::1"], &[open]).await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].addr(), format!("[::1]:{}", open));
+
+    let addr = format!("[::1]:{}", open);
+    let results = fast_hosts_ports(&[&addr], &[DEFAULT_PORT]).await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].port, open);
+}
@@ -0,0 +1,116 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tdx_rust::protocol::test_data::TestData;
+use tdx_rust::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// 应答K线请求的模拟服务器，返回 (地址, 已应答的K线请求数)
+async fn kline_server() -> (String, Arc<AtomicUsize>) {
+    let content = std::fs::read_to_string("tdx-test/test-data/kline.json").unwrap();
+    let test_data: TestData = serde_json::from_str(&content).unwrap();
+    let klines = hex::decode(test_data.response_data.unwrap()).unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let served = Arc::new(AtomicUsize::new(0));
+    let counter = served.clone();
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        loop {
+            let mut header = [0u8; 10];
+            if stream.read_exact(&mut header).await.is_err() {
+                break;
+            }
+            let length = u16::from_le_bytes([header[6], header[7]]) as usize;
+            let mut body = vec![0u8; length];
+            stream.read_exact(&mut body).await.unwrap();
+            let msg_type = u16::from_le_bytes([body[0], body[1]]);
+            let data: &[u8] = if msg_type == MessageType::Kline as u16 {
+                counter.fetch_add(1, Ordering::SeqCst);
+                &klines
+            } else {
+                &[]
+            };
+
+            let mut frame = PREFIX_RESP.to_be_bytes().to_vec();
+            frame.push(CONTROL_RESP_SUCCESS);
+            frame.extend_from_slice(&header[1..5]);
+            frame.push(0);
+            frame.extend_from_slice(&msg_type.to_le_bytes());
+            frame.extend_from_slice(&(data.len() as u16).to_le_bytes());
+            frame.extend_from_slice(&(data.len() as u16).to_le_bytes());
+            frame.extend_from_slice(data);
+            stream.write_all(&frame).await.unwrap();
+        }
+    });
+    (addr, served)
+}
+
+/// 每次写入都很慢的 sink，写入时检查下载没有远远领先于写入
+struct SlowSink {
+    served: Arc<AtomicUsize>,
+    limit: usize,
+    written: Vec<(String, usize)>,
+    flushed: bool,
+}
+
+impl Sink for SlowSink {
+    fn write_quotes(&mut self, _: &[QuoteInfo]) -> Result<(), SinkError> {
+        Ok(())
+    }
+
+    fn write_klines(&mut self, code: &str, klines: &[Kline]) -> Result<(), SinkError> {
+        std::thread::sleep(Duration::from_millis(20));
+        let ahead = self.served.load(Ordering::SeqCst) - self.written.len();
+        assert!(ahead <= self.limit, "下载领先写入 {} 个代码", ahead);
+        self.written.push((code.to_string(), klines.len()));
+        Ok(())
+    }
+
+    fn write_trades(&mut self, _: &str, _: &[Trade]) -> Result<(), SinkError> {
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), SinkError> {
+        self.flushed = true;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_export_klines_backpressure() {
+    let (addr, served) = kline_server().await;
+    let client = Arc::new(Client::connect(&addr).await.unwrap());
+
+    let codes: Vec<String> = (1..=12).map(|i| format!("sz{:06}", i)).collect();
+    let config = ExportConfig::new()
+        .with_concurrency(2)
+        .with_buffer(1)
+        .with_transform(|_, mut klines| {
+            klines.truncate(3);
+            klines
+        });
+    let sink = SlowSink {
+        served: served.clone(),
+        // 正在写入的1个 + 通道中1个 + 每个下载任务手上1个
+        limit: 4,
+        written: Vec::new(),
+        flushed: false,
+    };
+
+    let (sink, report) = export_klines(client, codes.clone(), KlineType::Day, sink, config)
+        .await
+        .unwrap();
+    assert_eq!(report.codes, 12);
+    assert_eq!(report.bars, 36);
+    assert!(report.failed.is_empty());
+    assert!(sink.flushed);
+
+    let mut written: Vec<String> = sink.written.iter().map(|(c, _)| c.clone()).collect();
+    written.sort();
+    assert_eq!(written, codes);
+    assert!(sink.written.iter().all(|(_, n)| *n == 3));
+}
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+use tdx_rust::align::Matrix;
+use tdx_rust::factors::{neutralize_by_block, rank, rank_row, winsorize_row, zscore};
+
+fn matrix() -> Matrix {
+    Matrix {
+        times: vec![1, 2],
+        symbols: vec!["a".into(), "b".into(), "c".into(), "d".into()],
+        values: vec![vec![1.0, 2.0, 3.0, 4.0], vec![4.0, f64::NAN, 4.0, 1.0]],
+    }
+}
+
+#[test]
+fn test_rank_and_zscore() {
+    let r = rank(&matrix());
+    assert_eq!(r.row(0), [0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0]);
+    // 并列取平均名次
+    assert_eq!(r.row(1)[0], 0.75);
+    assert!(r.row(1)[1].is_nan());
+    assert_eq!(r.row(1)[3], 0.0);
+    assert_eq!(rank_row(&[5.0]), [0.5]);
+
+    let z = zscore(&matrix());
+    let row = z.row(0);
+    assert!((row.iter().sum::<f64>()).abs() < 1e-12);
+    assert!((row[3] - 1.5 / 1.25f64.sqrt()).abs() < 1e-12);
+}
+
+#[test]
+fn test_winsorize_and_neutralize() {
+    let row = [0.0, 0.0, 0.0, 0.0, 100.0];
+    let w = winsorize_row(&row, 1.0);
+    // 均值20，标准差40
+    assert_eq!(w[4], 60.0);
+    assert_eq!(w[0], 0.0);
+
+    let mut blocks = HashMap::new();
+    blocks.insert("a".to_string(), "银行".to_string());
+    blocks.insert("b".to_string(), "银行".to_string());
+    blocks.insert("c".to_string(), "券商".to_string());
+    let n = neutralize_by_block(&matrix(), &blocks);
+    assert_eq!(&n.row(0)[..3], [-0.5, 0.5, 0.0]);
+    assert!(n.row(0)[3].is_nan());
+}
@@ -0,0 +1,105 @@
+use chrono::{TimeZone, Utc};
+use std::sync::Arc;
+use tdx_rust::mock::MockServer;
+use tdx_rust::*;
+
+/// 历史成交响应：(分钟, 价格（分）, 成交量（手）, 状态)
+fn history_trades(trades: &[(u16, i32, i32, i32)]) -> Vec<u8> {
+    let mut data = (trades.len() as u16).to_le_bytes().to_vec();
+    data.extend_from_slice(&[0; 4]);
+    let mut last = 0;
+    for &(time, price, volume, status) in trades {
+        data.extend_from_slice(&time.to_le_bytes());
+        for v in [price - last, volume, status, 0] {
+            data.extend_from_slice(&encode_varint(v));
+        }
+        last = price;
+    }
+    data
+}
+
+#[test]
+fn test_daily_flow() {
+    let data = history_trades(&[
+        (9 * 60 + 25, 1000, 500, 2), // 集合竞价
+        (10 * 60, 1001, 300, 0),     // 买入 30.03 万，大单
+        (10 * 60 + 1, 1000, 10, 1),  // 卖出 1 万
+        (10 * 60 + 2, 1000, 50, 2),  // 中性，沿用上一笔方向：卖出 5 万
+    ]);
+    let cache = TradeCache {
+        date: "20241010".to_string(),
+        code: "sz000001".to_string(),
+    };
+    let trades = HistoryTradeMsg::decode_response(&data, &cache).unwrap();
+    let flow = DailyFlow::from_trades("20241010", &trades.list, 200_000.0);
+
+    assert_eq!(flow.trades, 4);
+    assert!((flow.buy_amount - 300_300.0).abs() < 1e-6);
+    assert!((flow.sell_amount - 60_000.0).abs() < 1e-6);
+    assert!((flow.main_net_amount() - 300_300.0).abs() < 1e-6);
+    assert!((flow.net_amount() - 240_300.0).abs() < 1e-6);
+}
+
+#[tokio::test]
+async fn test_flow_history_cached() {
+    let server = MockServer::builder()
+        .handle(MessageType::HistoryMinuteTrade, |req| {
+            let date = u32::from_le_bytes([req[0], req[1], req[2], req[3]]);
+            // 每天一笔买入，成交量为日期的最后两位
+            history_trades(&[(10 * 60, 1000, (date % 100) as i32, 0)])
+        })
+        .start()
+        .await
+        .unwrap();
+    let mut client = server.client().await.unwrap();
+    client.set_clock(Arc::new(FixedClock::new(
+        Utc.with_ymd_and_hms(2024, 10, 11, 2, 0, 0).unwrap(),
+    )));
+
+    let dates: Vec<String> = ["20241009", "20241010", "20241011"]
+        .iter()
+        .map(|d| d.to_string())
+        .collect();
+    let mut history = FlowHistory::new();
+    let series = history.history(&client, "000001", &dates).await.unwrap();
+    let buys: Vec<f64> = series.iter().map(|f| f.buy_amount).collect();
+    assert_eq!(buys, [9_000.0, 10_000.0, 11_000.0]);
+    assert_eq!(server.count(MessageType::HistoryMinuteTrade), 3);
+    // 当天未收盘，不缓存
+    assert_eq!(history.len(), 2);
+
+    history.history(&client, "sz000001", &dates).await.unwrap();
+    assert_eq!(server.count(MessageType::HistoryMinuteTrade), 4);
+}
+
+#[tokio::test]
+async fn test_flow_history_code_large_order() {
+    let server = MockServer::builder()
+        .handle(MessageType::HistoryMinuteTrade, |_| {
+            // 买入 10 万
+            history_trades(&[(10 * 60, 1000, 100, 0)])
+        })
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+    let dates = vec!["20241010".to_string()];
+
+    let mut history = FlowHistory::new()
+        .with_large_order(50_000.0)
+        .with_code_large_order("600008", 500_000.0);
+    assert_eq!(history.large_order("sh600008"), 500_000.0);
+    assert_eq!(history.large_order("000001"), 50_000.0);
+
+    let flow = &history.history(&client, "000001", &dates).await.unwrap()[0];
+    assert_eq!(flow.large_buy_amount, 100_000.0);
+    let flow = &history.history(&client, "sh600008", &dates).await.unwrap()[0];
+    assert_eq!(flow.large_buy_amount, 0.0);
+    assert_eq!(history.len(), 2);
+
+    // 修改阈值只清空该代码的缓存
+    let mut history = history.with_code_large_order("000001", 500_000.0);
+    assert_eq!(history.len(), 1);
+    let flow = &history.history(&client, "000001", &dates).await.unwrap()[0];
+    assert_eq!(flow.large_buy_amount, 0.0);
+}
@@ -0,0 +1,76 @@
+use chrono::{Duration, NaiveDate, TimeZone, Timelike, Utc};
+use std::sync::Arc;
+use tdx_rust::freshness::parse_server_time;
+use tdx_rust::mock::MockServer;
+use tdx_rust::*;
+
+#[test]
+fn test_parse_server_time() {
+    let date = NaiveDate::from_ymd_opt(2024, 10, 11).unwrap();
+    let t = parse_server_time("13252999", date).unwrap();
+    assert_eq!((t.hour(), t.minute(), t.second()), (13, 25, 17));
+    assert_eq!(t.timestamp_subsec_millis(), 994);
+    assert!(parse_server_time("", date).is_none());
+    assert!(parse_server_time("25000000", date).is_none());
+}
+
+#[tokio::test]
+async fn test_quote_freshness() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let mut client = server.client().await.unwrap();
+    // 北京时间 2024-10-11 13:25:40
+    let clock = Arc::new(FixedClock::new(
+        Utc.with_ymd_and_hms(2024, 10, 11, 5, 25, 40).unwrap(),
+    ));
+    client.set_clock(clock);
+
+    let codes = vec!["sz000001".to_string(), "sh600008".to_string()];
+    let (quotes, freshness) = client.get_quote_fresh(&codes).await.unwrap();
+    assert_eq!(quotes.len(), 2);
+
+    // 两条行情的服务器时间为 13:25:17.994 和 13:25:21.486，取较新的一条
+    let server_time = freshness.server_time.unwrap();
+    assert_eq!((server_time.minute(), server_time.second()), (25, 21));
+    assert_eq!(freshness.latency, Some(Duration::milliseconds(18_514)));
+    assert!(freshness.is_stale(Duration::seconds(10)));
+    assert!(!freshness.is_stale(Duration::seconds(30)));
+
+    // 没有服务器时间时不认为过时
+    let unknown = DataFreshness::new(None, Utc::now());
+    assert!(!unknown.is_stale(Duration::zero()));
+}
+
+#[tokio::test]
+async fn test_snapshot_consistent() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+    let codes = vec!["sz000001".to_string(), "sh600008".to_string()];
+
+    // 两条行情的服务器时间相差 3.492 秒
+    let quotes = client
+        .snapshot_consistent(&codes, std::time::Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(quotes.len(), 2);
+    assert_eq!(server.count(MessageType::Quote), 1);
+
+    let err = client
+        .snapshot_consistent(&codes, std::time::Duration::from_secs(1))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::InconsistentSnapshot(3492)));
+    assert_eq!(
+        server.count(MessageType::Quote),
+        1 + SNAPSHOT_ATTEMPTS as usize
+    );
+}
@@ -0,0 +1,125 @@
+//! 解码结果回归测试
+//!
+//! 解码每个带响应数据的测试数据文件，与 tdx-test/golden/ 下保存的 JSON 逐字段比较。
+//! 解码逻辑有意修改时，设置环境变量 `TDX_UPDATE_GOLDEN=1` 重新生成：
+//!
+//! ```text
+//! TDX_UPDATE_GOLDEN=1 cargo test --features test-data --test golden_test
+//! ```
+
+use serde_json::Value;
+use std::fs;
+use tdx_rust::protocol::test_data::TestData;
+use tdx_rust::protocol::*;
+
+const UPDATE_ENV: &str = "TDX_UPDATE_GOLDEN";
+
+fn load_test_data(filename: &str) -> TestData {
+    let path = format!("tdx-test/test-data/{}.json", filename);
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// 完整响应帧的数据域
+fn response_data(test_data: &TestData) -> Vec<u8> {
+    let bytes = test_data.decode_response().unwrap();
+    ResponseFrame::decode(&bytes).unwrap().data
+}
+
+/// 逐字段比较，收集不一致的路径
+fn diff(path: &str, expected: &Value, actual: &Value, out: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(e), Value::Object(a)) => {
+            for (k, ev) in e {
+                let p = format!("{}.{}", path, k);
+                match a.get(k) {
+                    Some(av) => diff(&p, ev, av, out),
+                    None => out.push(format!("{}: 缺少字段", p)),
+                }
+            }
+            for k in a.keys().filter(|k| !e.contains_key(*k)) {
+                out.push(format!("{}.{}: 多出字段", path, k));
+            }
+        }
+        (Value::Array(e), Value::Array(a)) if e.len() == a.len() => {
+            for (i, (ev, av)) in e.iter().zip(a).enumerate() {
+                diff(&format!("{}[{}]", path, i), ev, av, out);
+            }
+        }
+        _ if expected != actual => {
+            out.push(format!("{}: 期望 {}, 得到 {}", path, expected, actual))
+        }
+        _ => {}
+    }
+}
+
+fn check_golden<T: serde::Serialize>(name: &str, decoded: &T) {
+    let path = format!("tdx-test/golden/{}.json", name);
+    let actual = serde_json::to_value(decoded).unwrap();
+
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        fs::create_dir_all("tdx-test/golden").unwrap();
+        fs::write(&path, serde_json::to_string_pretty(&actual).unwrap() + "\n").unwrap();
+        return;
+    }
+
+    let content = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("缺少 {}，设置 {}=1 生成", path, UPDATE_ENV));
+    let expected: Value = serde_json::from_str(&content).unwrap();
+
+    let mut out = Vec::new();
+    diff(name, &expected, &actual, &mut out);
+    assert!(
+        out.is_empty(),
+        "{} 解码结果不一致:\n{}",
+        name,
+        out.join("\n")
+    );
+}
+
+#[test]
+fn golden_connect() {
+    let data = response_data(&load_test_data("connect"));
+    check_golden("connect", &Connect::decode_response(&data).unwrap());
+}
+
+#[test]
+fn golden_count() {
+    let data = response_data(&load_test_data("count"));
+    check_golden("count", &Count::decode_response(&data).unwrap());
+}
+
+#[test]
+fn golden_quote() {
+    let data = response_data(&load_test_data("quote"));
+    check_golden("quote", &Quote::decode_response(&data).unwrap());
+}
+
+#[test]
+fn golden_kline() {
+    // 完整响应帧缺少压缩数据，使用记录的解压后数据域
+    let test_data = load_test_data("kline");
+    let data = hex::decode(test_data.response_data.unwrap()).unwrap();
+    let cache = KlineCache {
+        kline_type: KlineType::Day as u8,
+        kind: InstrumentKind::Stock,
+    };
+    check_golden(
+        "kline",
+        &KlineMsg::decode_response(&data, cache).unwrap().list,
+    );
+}
+
+#[test]
+fn test_diff_reports_field_path() {
+    let expected = serde_json::json!({"k": {"close": 100}, "list": [1, 2]});
+    let actual = serde_json::json!({"k": {"close": 101}, "list": [1, 3]});
+    let mut out = Vec::new();
+    diff("quote", &expected, &actual, &mut out);
+    assert_eq!(
+        out,
+        [
+            "quote.k.close: 期望 100, 得到 101",
+            "quote.list[1]: 期望 2, 得到 3"
+        ]
+    );
+}
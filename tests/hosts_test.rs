@@ -0,0 +1,95 @@
+use tdx_rust::hosts::CAP_BJ;
+use tdx_rust::*;
+
+const HOSTS_JSON: &str = r#"[
+    {"host": "10.0.0.1", "region": "华北", "isp": "联通"},
+    {"host": "10.0.0.2", "port": 7727, "region": "华东", "isp": "电信", "capabilities": ["bj"]},
+    {"host": "10.0.0.3", "region": "华东", "isp": "联通", "capabilities": ["BJ", "history"]},
+    {"host": "10.0.0.4"}
+]"#;
+
+#[test]
+fn test_host_list_select() {
+    let list = HostList::from_json(HOSTS_JSON).unwrap();
+    assert_eq!(list.hosts.len(), 4);
+    assert_eq!(list.hosts[1].addr(), "10.0.0.2:7727");
+    assert_eq!(list.hosts[3].region, None);
+
+    // 默认策略保持原有顺序
+    let all = list.select(&SelectPolicy::default());
+    assert_eq!(all, ["10.0.0.1", "10.0.0.2:7727", "10.0.0.3", "10.0.0.4"]);
+
+    // 同地区优先，其次同运营商
+    let policy = SelectPolicy::default()
+        .prefer_region("华东")
+        .prefer_isp("联通");
+    let hosts = list.select(&policy);
+    assert_eq!(hosts, ["10.0.0.3", "10.0.0.2:7727", "10.0.0.1", "10.0.0.4"]);
+
+    // 要求支持北交所
+    let bj = list.select(&SelectPolicy::default().require(CAP_BJ));
+    assert_eq!(bj, ["10.0.0.2:7727", "10.0.0.3"]);
+}
+
+#[test]
+fn test_host_list_errors() {
+    assert!(matches!(
+        HostList::from_json("{"),
+        Err(HostListError::Parse(_))
+    ));
+    assert!(matches!(
+        HostList::load("/nonexistent/hosts.json"),
+        Err(HostListError::Io(_))
+    ));
+}
+
+#[test]
+fn test_builtin_metadata() {
+    let builtin = HostList::builtin();
+    let hosts: Vec<&str> = builtin.hosts.iter().map(|h| h.host.as_str()).collect();
+    assert_eq!(hosts, dial::DEFAULT_HOSTS);
+    assert_eq!(builtin.regions(), ["华东"]);
+
+    // 优先同地区的服务器，未标注地区的排在后面
+    let selected = builtin.select(&SelectPolicy::default().prefer_region("华东"));
+    assert_eq!(selected.len(), dial::DEFAULT_HOSTS.len());
+    assert_eq!(selected.last().unwrap(), "111.229.247.189");
+    assert!(builtin
+        .hosts
+        .iter()
+        .filter(|h| h.region.is_some())
+        .all(|h| h.isp.as_deref() == Some("双线")));
+}
+
+#[tokio::test]
+async fn test_select_by_region() {
+    use tokio::net::TcpListener;
+
+    let east = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let north = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    // 绑定后立即释放，得到一个无人监听的端口
+    let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed_port = closed.local_addr().unwrap().port();
+    drop(closed);
+
+    let east_port = east.local_addr().unwrap().port();
+    let json = format!(
+        r#"[
+            {{"host": "127.0.0.1", "port": {}, "region": "华北"}},
+            {{"host": "127.0.0.1", "port": {}, "region": "华东"}},
+            {{"host": "127.0.0.1", "port": {}, "region": "华东"}}
+        ]"#,
+        north.local_addr().unwrap().port(),
+        closed_port,
+        east_port
+    );
+    let list = HostList::from_json(&json).unwrap();
+    assert_eq!(list.regions(), ["华北", "华东"]);
+
+    // 只测试华东的服务器，不可连接的被排除
+    let hosts = list.select_by_region("华东").await;
+    assert_eq!(hosts, [format!("127.0.0.1:{}", east_port)]);
+
+    // 没有该地区时测试全部服务器
+    assert_eq!(list.select_by_region("华南").await.len(), 2);
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::fs;
+use tdx_rust::protocol::layout::{describe, layout, layouts, Encoding, Field};
+use tdx_rust::protocol::test_data::TestData;
+use tdx_rust::protocol::*;
+
+#[test]
+fn test_layout_covers_all_messages() {
+    assert_eq!(layouts().len(), 12);
+    for l in layouts() {
+        assert!(MessageType::from_u16(l.msg_type.as_u16()).is_some());
+    }
+
+    // 代码列表单条记录固定29字节
+    let code = layout(MessageType::Code).unwrap();
+    let size: usize = code
+        .response_record
+        .iter()
+        .map(|f| f.encoding.size().unwrap())
+        .sum();
+    assert_eq!(size, 29);
+
+    // 集合竞价单条记录固定16字节
+    let auction = layout(MessageType::CallAuction).unwrap();
+    let size: usize = auction
+        .response_record
+        .iter()
+        .map(|f| f.encoding.size().unwrap())
+        .sum();
+    assert_eq!(size, 16);
+    assert_eq!(Encoding::Varint.size(), None);
+}
+
+#[test]
+fn test_describe() {
+    let text = describe(MessageType::Kline);
+    assert!(text.starts_with("0x052D Kline K线"));
+    assert!(text.contains("kline_type"));
+    // 变长字段之后的偏移未知
+    assert!(text.contains("   ? close"));
+}
+
+#[test]
+fn test_connect_layout() {
+    let connect = layout(MessageType::Connect).unwrap();
+    let mut offset = 0;
+    let mut offsets = HashMap::new();
+    for f in connect.response_header {
+        offsets.insert(f.name, offset);
+        offset += f.encoding.size().unwrap_or(0);
+    }
+    // 与 Connect::decode_info 的偏移一致，二进制字段共68字节
+    assert_eq!(offsets["open1"], 9);
+    assert_eq!(offsets["date"], 42);
+    assert_eq!(offsets["time"], 46);
+    assert_eq!(offsets["info"], 68);
+}
+
+/// 按字段表依次读取固定长度字段，遇到变长字段时停止，返回 字段名 → 字节
+///
+/// 数据不足或 ASCII 字段含非 ASCII 字节时失败。
+fn walk<'a>(what: &str, fields: &[Field], data: &'a [u8]) -> HashMap<&'static str, &'a [u8]> {
+    let mut offset = 0;
+    let mut values = HashMap::new();
+    for f in fields {
+        let Some(size) = f.encoding.size() else {
+            break;
+        };
+        assert!(
+            offset + size <= data.len(),
+            "{}: 字段 {} 在偏移 {} 处超出数据长度 {}",
+            what,
+            f.name,
+            offset,
+            data.len()
+        );
+        let bytes = &data[offset..offset + size];
+        if let Encoding::Ascii(_) = f.encoding {
+            assert!(bytes.is_ascii(), "{}: 字段 {} 不是 ASCII", what, f.name);
+        }
+        values.insert(f.name, bytes);
+        offset += size;
+    }
+    values
+}
+
+/// 字段表的固定长度（含变长字段时为 None）
+fn fixed_size(fields: &[Field]) -> Option<usize> {
+    fields.iter().map(|f| f.encoding.size()).sum()
+}
+
+fn u16_of(bytes: &[u8]) -> u16 {
+    bytes_to_u16_le(bytes)
+}
+
+fn u32_of(bytes: &[u8]) -> u32 {
+    bytes_to_u32_le(bytes)
+}
+
+/// 抓取的响应数据域（完整响应帧未记录时使用解压后的数据域）
+fn fixture_response(name: &str) -> Vec<u8> {
+    let path = format!("tdx-test/test-data/{}.json", name);
+    let test_data: TestData = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    match test_data.decode_response() {
+        Ok(bytes) => ResponseFrame::decode(&bytes).unwrap().data,
+        Err(_) => test_data.decode_response_data().unwrap().unwrap(),
+    }
+}
+
+#[test]
+fn test_layout_matches_requests() {
+    let code = "sz000001";
+    let requests = [
+        Connect::request(1),
+        Heartbeat::request(1),
+        Count::request(1, Exchange::SZ),
+        Code::request(1, Exchange::SZ, 0),
+        Quote::request(1, &[code.to_string(), "sh600008".to_string()]).unwrap(),
+        MinuteMsg::request(1, code).unwrap(),
+        HistoryMinuteMsg::request(1, "20241010", code).unwrap(),
+        CallAuctionMsg::request(1, code).unwrap(),
+        TradeMsg::request(1, code, 0, 10).unwrap(),
+        HistoryTradeMsg::request(1, "20241010", code, 0, 10).unwrap(),
+        KlineMsg::request(1, KlineType::Day, code, 0, 10).unwrap(),
+        GbbqMsg::request(1, code).unwrap(),
+    ];
+    assert_eq!(requests.len(), layouts().len());
+
+    for frame in &requests {
+        let l = layout(frame.msg_type).unwrap();
+        let what = format!("{:?} 请求", frame.msg_type);
+        let header = walk(&what, l.request_header, &frame.data);
+        let header_size = fixed_size(l.request_header).unwrap();
+
+        let mut expected = header_size;
+        if !l.request_record.is_empty() {
+            let count = u16_of(header["count"]) as usize;
+            let record_size = fixed_size(l.request_record).unwrap();
+            for i in 0..count {
+                let start = header_size + i * record_size;
+                walk(&what, l.request_record, &frame.data[start..]);
+            }
+            expected += count * record_size;
+        }
+        assert_eq!(frame.data.len(), expected, "{}: 数据长度与字段表不符", what);
+        if let Some(code) = header.get("code") {
+            assert_eq!(*code, b"000001", "{}: code 偏移错误", what);
+        }
+    }
+}
+
+#[test]
+fn test_layout_matches_fixtures() {
+    // 建立连接：交易时段、日期、时间与 decode_info 一致
+    let data = fixture_response("connect");
+    let l = layout(MessageType::Connect).unwrap();
+    let header = walk("Connect 响应", l.response_header, &data);
+    let info = Connect::decode_info(&data).unwrap();
+    assert_eq!(u16_of(header["open1"]), info.trading_hours[0].0);
+    assert_eq!(u16_of(header["close1"]), info.trading_hours[0].1);
+    assert_eq!(u32_of(header["date"]), info.date);
+    assert_eq!(u32_of(header["time"]), info.time);
+
+    // 股票数量：响应只有 count 字段
+    let data = fixture_response("count");
+    let l = layout(MessageType::Count).unwrap();
+    let header = walk("Count 响应", l.response_header, &data);
+    assert_eq!(fixed_size(l.response_header), Some(data.len()));
+    assert_eq!(
+        u16_of(header["count"]),
+        Count::decode_response(&data).unwrap()
+    );
+
+    // 行情：两只股票，第一条记录的固定字段紧跟在头部之后
+    let data = fixture_response("quote");
+    let l = layout(MessageType::Quote).unwrap();
+    let header = walk("Quote 响应", l.response_header, &data);
+    assert_eq!(u16_of(header["count"]), 2);
+    let header_size = fixed_size(l.response_header).unwrap();
+    let record = walk("Quote 记录", l.response_record, &data[header_size..]);
+    assert_eq!(record["exchange"], [Exchange::SZ.as_u8()]);
+    assert_eq!(record["code"], b"000001");
+
+    // K线：10根日线，第一根的时间为 YYYYMMDD
+    let data = fixture_response("kline");
+    let l = layout(MessageType::Kline).unwrap();
+    let header = walk("Kline 响应", l.response_header, &data);
+    assert_eq!(u16_of(header["count"]), 10);
+    let header_size = fixed_size(l.response_header).unwrap();
+    let record = walk("Kline 记录", l.response_record, &data[header_size..]);
+    assert_eq!(u32_of(record["time"]), 20241016);
+}
@@ -0,0 +1,349 @@
+//! 示例程序的主要流程（市场代码统计、全部K线、股本变迁）在模拟服务器上的集成测试
+
+use tdx_rust::mock::MockServer;
+use tdx_rust::protocol::test_data::TestData;
+use tdx_rust::*;
+
+/// 深圳市场的模拟代码表：600只股票、300只ETF、300个指数
+fn sz_codes() -> Vec<String> {
+    let stocks = (1..=600).map(|i| format!("{:06}", i));
+    let etfs = (1..=300).map(|i| format!("159{:03}", i));
+    let indexes = (1..=300).map(|i| format!("399{:03}", i));
+    stocks.chain(etfs).chain(indexes).collect()
+}
+
+/// 代码列表响应：每页最多1000条，每条29字节
+fn code_page(codes: &[String], start: usize) -> Vec<u8> {
+    let page: Vec<&String> = codes.iter().skip(start).take(1000).collect();
+    let mut data = (page.len() as u16).to_le_bytes().to_vec();
+    for code in page {
+        let mut record = [0u8; 29];
+        record[0..6].copy_from_slice(code.as_bytes());
+        record[6..8].copy_from_slice(&100u16.to_le_bytes());
+        record[8..14].copy_from_slice(code.as_bytes());
+        record[20] = 2;
+        data.extend_from_slice(&record);
+    }
+    data
+}
+
+fn fixture_klines() -> Vec<u8> {
+    let content = std::fs::read_to_string("tdx-test/test-data/kline.json").unwrap();
+    let test_data: TestData = serde_json::from_str(&content).unwrap();
+    hex::decode(test_data.response_data.unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_market_counts() {
+    let codes = sz_codes();
+    let server = MockServer::builder()
+        .handle(MessageType::Code, move |req| {
+            let start = u16::from_le_bytes([req[2], req[3]]) as usize;
+            code_page(&codes, start)
+        })
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+
+    assert_eq!(
+        client.get_market_stocks(Exchange::SZ).await.unwrap().len(),
+        600
+    );
+    assert_eq!(
+        client.get_market_etfs(Exchange::SZ).await.unwrap().len(),
+        300
+    );
+    assert_eq!(
+        client.get_market_indexes(Exchange::SZ).await.unwrap().len(),
+        300
+    );
+    // 每次统计分两页获取代码表
+    assert_eq!(server.count(MessageType::Code), 6);
+    let starts: Vec<u16> = server
+        .requests()
+        .iter()
+        .filter(|(t, _)| *t == MessageType::Code)
+        .map(|(_, req)| u16::from_le_bytes([req[2], req[3]]))
+        .collect();
+    assert_eq!(starts, [0, 1000, 0, 1000, 0, 1000]);
+}
+
+#[tokio::test]
+async fn test_count_and_quote_fixtures() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+
+    assert!(client.get_count(Exchange::SZ).await.unwrap() > 0);
+    let quotes = client.get_quote(&["sz000001".to_string()]).await.unwrap();
+    assert!(!quotes.is_empty());
+    assert_eq!(client.connect_info().unwrap().name, "上海双线主站14");
+}
+
+#[tokio::test]
+async fn test_quote_duplicate_codes() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+
+    // 同一代码请求两次，两次都返回
+    let codes = vec!["sz000001".to_string(), "sz000001".to_string()];
+    let quotes = client.get_quote_batch(&codes).await.unwrap();
+    assert_eq!(quotes.len(), 2);
+    assert!(quotes.iter().all(|q| q.code == "000001"));
+
+    let ordered = client.get_quote_ordered(&codes).await.unwrap();
+    assert!(ordered.is_complete());
+}
+
+#[tokio::test]
+async fn test_kline_all_pagination() {
+    // 第一页（最新）为800条：把10条样本的K线数据重复80次；第二页为原始的10条
+    let sample = fixture_klines();
+    let bars = sample[2..].to_vec();
+    let server = MockServer::builder()
+        .handle(MessageType::Kline, move |req| {
+            let start = u16::from_le_bytes([req[12], req[13]]);
+            if start == 0 {
+                let mut page = 800u16.to_le_bytes().to_vec();
+                for _ in 0..80 {
+                    page.extend_from_slice(&bars);
+                }
+                page
+            } else {
+                sample.clone()
+            }
+        })
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+
+    let all = client
+        .get_kline_all(KlineType::Day, "sz000001")
+        .await
+        .unwrap();
+    assert_eq!(server.count(MessageType::Kline), 2);
+    assert_eq!(all.count, 810);
+    assert_eq!(all.list.len(), 810);
+
+    // 较旧的一页在前
+    let oldest = client
+        .get_kline(KlineType::Day, "sz000001", 800, 800)
+        .await
+        .unwrap();
+    for (a, b) in all.list.iter().zip(&oldest.list) {
+        assert_eq!(a.time, b.time);
+        assert_eq!(a.close, b.close);
+    }
+}
+
+#[tokio::test]
+async fn test_gbbq() {
+    // 9字节头 + 数量 + 1条除权除息记录
+    let mut data = vec![0u8; 9];
+    data.extend_from_slice(&1u16.to_le_bytes());
+    let mut record = [0u8; 29];
+    record[0] = Exchange::SZ.as_u8();
+    record[1..7].copy_from_slice(b"000001");
+    record[8..12].copy_from_slice(&20240614u32.to_le_bytes());
+    record[12] = 1;
+    record[13..17].copy_from_slice(&7.19f32.to_le_bytes());
+    data.extend_from_slice(&record);
+
+    let server = MockServer::builder()
+        .respond(MessageType::Gbbq, data)
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+
+    let resp = client.get_gbbq("000001").await.unwrap();
+    assert_eq!(resp.count, 1);
+    let item = &resp.list[0];
+    assert_eq!(item.code, "sz000001");
+    assert_eq!(item.category_name(), "除权除息");
+    assert!((item.c1 - 7.19).abs() < 1e-6);
+
+    // 缓存版本只请求一次
+    client.get_gbbq_cached("000001").await.unwrap();
+    client.get_gbbq_cached("000001").await.unwrap();
+    assert_eq!(server.count(MessageType::Gbbq), 2);
+}
+
+#[tokio::test]
+async fn test_send_raw() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+
+    let payload = Count::request(0, Exchange::SZ).data;
+    let data = client
+        .send_raw(MessageType::Count.as_u16(), payload)
+        .await
+        .unwrap();
+    assert_eq!(
+        Count::decode_response(&data).unwrap(),
+        client.get_count(Exchange::SZ).await.unwrap()
+    );
+
+    // 未定义的消息类型：模拟服务器返回空数据，连接仍可继续使用
+    let data = client.send_raw(0x1234, vec![1, 2, 3]).await.unwrap();
+    assert!(data.is_empty());
+    assert!(client.get_count(Exchange::SZ).await.unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_kline_range() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+    let date = |d| chrono::NaiveDate::from_ymd_opt(2024, 10, d).unwrap();
+
+    // 样本为 2024-10-16 至 2024-10-29 的10根日线，首尾两天都包含在内
+    let resp = client
+        .get_kline_range(KlineType::Day, "sz000001", date(17), date(24))
+        .await
+        .unwrap();
+    assert_eq!(resp.count, 6);
+    assert_eq!(resp.list.len(), 6);
+    assert!(resp.list.windows(2).all(|w| w[0].time < w[1].time));
+    // 不足一页，说明已到最早的K线，不再翻页
+    assert_eq!(server.count(MessageType::Kline), 1);
+
+    let empty = client
+        .get_kline_range(KlineType::Day, "sz000001", date(1), date(15))
+        .await
+        .unwrap();
+    assert!(empty.list.is_empty());
+}
+
+#[tokio::test]
+async fn test_kline_range_too_long() {
+    // 每页都是800根未越过起点的日线，翻到分页偏移上限仍到不了起点
+    let mut page = 800u16.to_le_bytes().to_vec();
+    for _ in 0..800 {
+        page.extend_from_slice(&20241029u32.to_le_bytes());
+        for _ in 0..4 {
+            page.extend_from_slice(&encode_varint(0));
+        }
+        page.extend_from_slice(&[0; 8]);
+    }
+    let server = MockServer::builder()
+        .respond(MessageType::Kline, page)
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+    let date = |d| chrono::NaiveDate::from_ymd_opt(2024, 10, d).unwrap();
+
+    let res = client
+        .get_kline_range(KlineType::Day, "sz000001", date(1), date(31))
+        .await;
+    assert!(matches!(res, Err(ClientError::KlineRangeTooLong)));
+    // 偏移 0, 800, ..., 64800 共82页
+    assert_eq!(server.count(MessageType::Kline), 82);
+}
+
+#[tokio::test]
+async fn test_quote_split() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+
+    let mut codes: Vec<String> = (0..100).map(|i| format!("sz{:06}", 300000 + i)).collect();
+    codes[5] = "sh600008".to_string();
+    codes[95] = "sz000001".to_string();
+    assert!(matches!(
+        Quote::request(0, &codes),
+        Err(MessageError::TooManyCodes(100))
+    ));
+
+    // 分成两个请求，结果按输入顺序合并
+    let quotes = client.get_quote(&codes).await.unwrap();
+    assert_eq!(server.count(MessageType::Quote), 2);
+    let names: Vec<String> = quotes
+        .iter()
+        .map(|q| format!("{}{}", q.exchange.as_str(), q.code))
+        .collect();
+    assert_eq!(names, ["sh600008", "sz000001"]);
+}
+
+#[tokio::test]
+async fn test_server_error() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .reject(MessageType::Quote, "代码不存在")
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+
+    // 错误响应不按行情解码，直接返回服务器的错误信息
+    match client.get_quote(&["sz000001".to_string()]).await {
+        Err(ClientError::Server { code, message }) => {
+            assert_eq!(code, CONTROL_RESP_ERROR);
+            assert_eq!(message, "代码不存在");
+        }
+        other => panic!("unexpected result: {:?}", other.map(|q| q.len())),
+    }
+    // 原始请求同样检查控制码
+    let payload = Quote::request(0, &["sz000001".to_string()]).unwrap().data;
+    assert!(matches!(
+        client.send_raw(MessageType::Quote.as_u16(), payload).await,
+        Err(ClientError::Server { .. })
+    ));
+    // 连接仍可继续使用
+    assert!(client.get_count(Exchange::SZ).await.unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_etf_kline_opt_in() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let client = server.client().await.unwrap();
+
+    // get_kline 对ETF代码仍按股票精度解码，只有 get_etf_kline 按基金精度
+    let stock = client
+        .get_kline(KlineType::Day, "sz000001", 0, 10)
+        .await
+        .unwrap();
+    let etf = client
+        .get_kline(KlineType::Day, "sh510300", 0, 10)
+        .await
+        .unwrap();
+    let fund = client
+        .get_etf_kline(KlineType::Day, "sh510300", 0, 10)
+        .await
+        .unwrap();
+    for ((s, e), f) in stock.list.iter().zip(&etf.list).zip(&fund.list) {
+        assert_eq!(e.close, s.close);
+        assert_eq!(f.close.0, s.close.0 / 10);
+    }
+}
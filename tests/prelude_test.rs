@@ -0,0 +1,24 @@
+//! prelude 只依赖稳定接口即可完成常见操作
+
+use tdx_rust::mock::MockServer;
+use tdx_rust::prelude::*;
+
+#[tokio::test]
+async fn test_prelude_usage() {
+    let server = MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap();
+    let client: Client = dial(server.addr()).await.unwrap();
+
+    let count = client.get_count(Exchange::SZ).await.unwrap();
+    assert!(count > 0);
+    let resp: KlineResponse = client
+        .get_kline(KlineType::Day, &add_prefix("000001"), 0, 10)
+        .await
+        .unwrap();
+    let closes: Vec<Price> = resp.list.iter().map(|k: &Kline| k.close).collect();
+    assert_eq!(closes.len(), 10);
+}
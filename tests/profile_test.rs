@@ -0,0 +1,48 @@
+use tdx_rust::profile::*;
+use tdx_rust::*;
+
+fn trade(price: i64, volume: i32) -> Trade {
+    Trade {
+        time: 1_700_000_000,
+        price: Price(price),
+        volume,
+        status: TradeStatus::Buy,
+        number: 1,
+    }
+}
+
+#[test]
+fn test_volume_by_price() {
+    let trades = [
+        trade(10_000, 10),
+        trade(10_010, 40),
+        trade(10_020, 100),
+        trade(10_025, 20),
+        trade(10_030, 50),
+        trade(10_040, 30),
+        trade(10_050, 5),
+    ];
+    let profile = volume_by_price(&trades, Price(10));
+    assert_eq!(profile.total, 255);
+    assert_eq!(profile.levels.len(), 6);
+    assert_eq!(profile.levels[2], (Price(10_020), 120));
+    assert_eq!(profile.poc(), Some(Price(10_020)));
+
+    // 120 → +50(上) 170 → +40(下) 210 ≥ 255×0.7
+    assert_eq!(
+        profile.value_area(DEFAULT_VALUE_AREA),
+        Some((Price(10_010), Price(10_040)))
+    );
+    assert_eq!(
+        profile.value_area(1.0),
+        Some((Price(10_000), Price(10_060)))
+    );
+}
+
+#[test]
+fn test_volume_by_price_empty() {
+    let profile = volume_by_price(&[], Price(10));
+    assert_eq!(profile.total, 0);
+    assert!(profile.poc().is_none());
+    assert!(profile.value_area(DEFAULT_VALUE_AREA).is_none());
+}
@@ -51,6 +51,14 @@ fn test_connect_response() {
     let info = Connect::decode_response(&response.data).unwrap();
     assert!(!info.is_empty());
     println!("连接响应信息: {}", info);
+
+    let server = Connect::decode_info(&response.data).unwrap();
+    assert_eq!(server.name, "上海双线主站14");
+    // 只有客户端标识 #通达信，没有公告
+    assert_eq!(server.announcement, None);
+    assert_eq!(server.trading_hours, [(570, 690), (780, 900)]);
+    assert_eq!(server.date, 20241011);
+    assert_eq!(server.time, 84506);
 }
 
 #[test]
@@ -222,7 +230,7 @@ fn test_frame_decode_all() {
             if let Ok(response_bytes) = test_data.decode_response() {
                 if response_bytes.len() >= 16 {
                     if let Ok(response) = ResponseFrame::decode(&response_bytes) {
-                        assert!(response.is_success() || response.control == 0x0C);
+                        assert_ne!(response.kind(), ResponseKind::Unknown(response.control));
                         println!("✓ {} 响应帧解析成功", filename);
                     } else {
                         panic!("{} 响应帧解析失败", filename);
@@ -232,3 +240,290 @@ fn test_frame_decode_all() {
         }
     }
 }
+
+#[test]
+fn test_minute_after_hours_session() {
+    // 240条常规记录 + 2条盘后记录，价格/成交量均为0
+    let count = 242u16;
+    let mut data = count.to_le_bytes().to_vec();
+    data.extend_from_slice(&[0; 4]);
+    data.resize(6 + count as usize * 3, 0);
+
+    let resp = MinuteMsg::decode_response(&data, "20240102").unwrap();
+    assert_eq!(resp.list.len(), 242);
+    assert_eq!(resp.list[0].session, Session::Regular);
+    assert_eq!(resp.list[239].session, Session::Regular);
+    assert_eq!(resp.list[240].session, Session::AfterHours);
+
+    // 09:31 开始，11:30 之后接 13:01，15:00 之后接 15:06
+    assert_eq!(resp.list[119].time - resp.list[0].time, 119 * 60);
+    assert_eq!(resp.list[120].time - resp.list[119].time, 91 * 60);
+    assert_eq!(resp.list[240].time - resp.list[239].time, 6 * 60);
+    assert_eq!(resp.list[241].time - resp.list[240].time, 60);
+}
+
+#[test]
+fn test_amount_wide() {
+    // 超过 i64 厘范围的金额（约 1.2e16 元）
+    let big = Amount::from_yuan(1.2e16);
+    assert!(big.0 > i64::MAX as i128);
+    assert!(Price::try_from(big).is_err());
+
+    let small = Amount::from(Price(123_456));
+    assert_eq!(small.to_yuan(), 123.456);
+    assert_eq!(Price::try_from(small).unwrap(), Price(123_456));
+    assert_eq!([small, small].into_iter().sum::<Amount>(), Amount(246_912));
+    assert_eq!(serde_json::to_string(&small).unwrap(), "123456");
+
+    let json = serde_json::to_string(&big).unwrap();
+    assert_eq!(serde_json::from_str::<Amount>(&json).unwrap(), big);
+}
+
+#[test]
+fn test_response_kind() {
+    assert_eq!(
+        ResponseKind::from_control(CONTROL_RESP_SUCCESS),
+        ResponseKind::Success
+    );
+    assert_eq!(
+        ResponseKind::from_control(CONTROL_RESP_ERROR),
+        ResponseKind::Error
+    );
+    assert_eq!(ResponseKind::from_control(0x10), ResponseKind::Success);
+    assert_eq!(
+        ResponseKind::from_control(0x00),
+        ResponseKind::Unknown(0x00)
+    );
+}
+
+#[test]
+fn test_quote_align() {
+    let test_data = load_test_data("quote").unwrap();
+    let response = ResponseFrame::decode(&test_data.decode_response().unwrap()).unwrap();
+    let quotes = Quote::decode_response(&response.data).unwrap();
+    let first = format!("{}{}", quotes[0].exchange.as_str(), quotes[0].code);
+
+    // 请求中包含服务器未返回的代码，且顺序与响应不同
+    let codes = vec!["sz399999".to_string(), first.to_uppercase()];
+    let ordered = Quote::align(&codes, quotes);
+    assert_eq!(ordered.list.len(), 2);
+    assert!(ordered.list[0].is_none());
+    assert_eq!(ordered.list[1].as_ref().unwrap().code, first[2..]);
+    assert_eq!(ordered.missing, ["sz399999"]);
+    assert!(!ordered.is_complete());
+}
+
+#[test]
+fn test_quote_align_duplicate() {
+    let test_data = load_test_data("quote").unwrap();
+    let response = ResponseFrame::decode(&test_data.decode_response().unwrap()).unwrap();
+    let quotes = Quote::decode_response(&response.data).unwrap();
+    let first = format!("{}{}", quotes[0].exchange.as_str(), quotes[0].code);
+
+    // 同一代码请求两次，两个位置都有行情
+    let codes = vec![first.clone(), first.clone()];
+    let ordered = Quote::align(&codes, quotes);
+    assert!(ordered.is_complete());
+    assert!(ordered.missing.is_empty());
+    for quote in &ordered.list {
+        assert_eq!(quote.as_ref().unwrap().code, first[2..]);
+    }
+}
+
+#[test]
+fn test_call_auction_date() {
+    // 1条记录：09:25:30 价格10.5 匹配100 未匹配-5（卖）
+    let mut data = 1u16.to_le_bytes().to_vec();
+    data.extend_from_slice(&(9u16 * 60 + 25).to_le_bytes());
+    data.extend_from_slice(&10.5f32.to_le_bytes());
+    data.extend_from_slice(&100u32.to_le_bytes());
+    data.extend_from_slice(&(-5i16).to_le_bytes());
+    data.extend_from_slice(&[0, 0, 0, 30]);
+
+    let auction = CallAuctionMsg::decode_response_on(&data, "20240102").unwrap();
+    assert_eq!(auction.list.len(), 1);
+    let a = &auction.list[0];
+    // 2024-01-02 09:25:30 北京时间
+    assert_eq!(a.time, 1704158730);
+    assert_eq!(a.price, Price(10_500));
+    assert_eq!(a.unmatched, 5);
+    assert_eq!(a.flag, -1);
+}
+
+#[test]
+fn test_kline_decode_filtered() {
+    let test_data = load_test_data("kline").unwrap();
+    let data = hex::decode(test_data.response_data.unwrap()).unwrap();
+    let cache = KlineCache {
+        kline_type: KlineType::Day as u8,
+        kind: InstrumentKind::Stock,
+    };
+
+    let all = KlineMsg::decode_response(&data, cache).unwrap();
+    let cutoff = all.list[6].time;
+    let recent = KlineMsg::decode_response_filtered(&data, cache, |k| k.time >= cutoff).unwrap();
+
+    // 条数仍为服务器返回的条数，列表只包含满足条件的记录，价格与完整解码一致
+    assert_eq!(recent.count, all.count);
+    assert_eq!(recent.list.len(), all.list.len() - 6);
+    for (a, b) in recent.list.iter().zip(&all.list[6..]) {
+        assert_eq!(a.time, b.time);
+        assert_eq!(a.close, b.close);
+    }
+}
+
+#[test]
+fn test_kline_decode_fund() {
+    let test_data = load_test_data("kline").unwrap();
+    let data = hex::decode(test_data.response_data.unwrap()).unwrap();
+    let decode = |kind| {
+        let cache = KlineCache {
+            kline_type: KlineType::Day as u8,
+            kind,
+        };
+        KlineMsg::decode_response(&data, cache).unwrap()
+    };
+
+    // 显式按基金解码（get_etf_kline）时价格多一位小数，成交量和成交额不变；
+    // 该规则没有真实基金响应可以验证，这里只覆盖显式解码路径
+    let stock = decode(InstrumentKind::Stock);
+    let fund = decode(InstrumentKind::Fund);
+    for (s, f) in stock.list.iter().zip(&fund.list) {
+        assert_eq!(f.close.0, s.close.0 / 10);
+        assert_eq!(f.high.0, s.high.0 / 10);
+        assert_eq!(f.volume, s.volume);
+        assert_eq!(f.amount, s.amount);
+    }
+
+    assert_eq!(InstrumentKind::from_code("sh510300"), InstrumentKind::Fund);
+    assert_eq!(InstrumentKind::from_code("sh000001"), InstrumentKind::Index);
+    assert_eq!(InstrumentKind::from_code("000001"), InstrumentKind::Stock);
+}
+
+#[test]
+fn test_tick_detail_decode() {
+    // 2条记录：时间、价格差值、成交量、单数、状态、未知字段
+    let mut data = 2u16.to_le_bytes().to_vec();
+    for (time, price, volume, number, status, unknown) in
+        [(570u16, 1050, 12, 3, 1, 7), (571, -2, 5, 1, 2, 0)]
+    {
+        data.extend_from_slice(&time.to_le_bytes());
+        for v in [price, volume, number, status, unknown] {
+            data.extend_from_slice(&encode_varint(v));
+        }
+    }
+    let cache = TradeCache {
+        date: "20241011".to_string(),
+        code: "sz000001".to_string(),
+    };
+
+    let ticks = TickDetailMsg::decode_response(&data, &cache).unwrap();
+    assert_eq!(ticks.count, 2);
+    let first = &ticks.list[0];
+    assert_eq!(first.price, Price(10500));
+    assert_eq!((first.volume, first.order_count), (12, 3));
+    assert!(first.is_sell() && !first.is_buy());
+    assert_eq!(first.unknown, 7);
+    let second = &ticks.list[1];
+    assert_eq!(second.price, Price(10480));
+    assert_eq!(second.status(), TradeStatus::Neutral);
+    assert_eq!(second.time - first.time, 60);
+
+    // 分时交易的解码结果与明细一致
+    let trades = TradeMsg::decode_response(&data, &cache).unwrap();
+    for (t, d) in trades.list.iter().zip(&ticks.list) {
+        assert_eq!(*t, Trade::from(d));
+    }
+}
+
+#[test]
+fn test_quote_decode_fund() {
+    let test_data = load_test_data("quote").unwrap();
+    let response = ResponseFrame::decode(&test_data.decode_response().unwrap()).unwrap();
+    let stock = Quote::decode_response(&response.data).unwrap();
+
+    // 把第一条记录的代码改为LOF基金 sz161725：默认解码不按基金精度换算
+    let mut data = response.data.clone();
+    assert_eq!(&data[5..11], b"000001");
+    data[5..11].copy_from_slice(b"161725");
+    let decoded = Quote::decode_response(&data).unwrap();
+    assert_eq!(decoded[0].code, "161725");
+    assert_eq!(decoded[0].k.close, stock[0].k.close);
+
+    // 显式换算（get_fund_quote）时价格多一位小数，成交量不变
+    let fund = decoded[0].clone().with_fund_precision();
+    assert_eq!(fund.k.close.0, stock[0].k.close.0 / 10);
+    assert_eq!(fund.k.last.0, stock[0].k.last.0 / 10);
+    assert_eq!(
+        fund.buy_level[0].price.0,
+        stock[0].buy_level[0].price.0 / 10
+    );
+    assert_eq!(fund.total_hand, stock[0].total_hand);
+
+    assert!(is_fund("sz161725") && !is_etf("sz161725"));
+    assert!(is_fund("sh501018") && is_fund("sh510300") && is_fund("sz159915"));
+    assert!(!is_fund("sz000001") && !is_fund("sh600000"));
+    assert_eq!(InstrumentKind::from_code("sz161725"), InstrumentKind::Fund);
+}
+
+#[test]
+fn test_quote_reserved_and_ratios() {
+    let test_data = load_test_data("quote").unwrap();
+    let response = ResponseFrame::decode(&test_data.decode_response().unwrap()).unwrap();
+    let quote = &Quote::decode_response(&response.data).unwrap()[0];
+
+    // ReversedBytes1 为负的现价（分）
+    assert_eq!(quote.raw_reserved[0] as i64, -quote.k.close.0 / 10);
+    assert_eq!(quote.raw_reserved[3], 397);
+
+    let amplitude = quote.amplitude().unwrap();
+    let expected = (quote.k.high.0 - quote.k.low.0) as f64 / quote.k.last.0 as f64 * 100.0;
+    assert!((amplitude - expected).abs() < 1e-9);
+    let ratio = quote.order_ratio().unwrap();
+    assert!((-100.0..=100.0).contains(&ratio));
+}
+
+#[test]
+fn test_kline_decode_variants() {
+    let test_data = load_test_data("kline").unwrap();
+    let data = hex::decode(test_data.response_data.unwrap()).unwrap();
+    let decode = |data: &[u8], kline_type: KlineType| {
+        let cache = KlineCache {
+            kline_type: kline_type as u8,
+            kind: InstrumentKind::Stock,
+        };
+        KlineMsg::decode_response(data, cache).unwrap()
+    };
+
+    // Day2 与日线格式相同：日期为 YYYYMMDD，成交量不换算
+    let day = decode(&data, KlineType::Day);
+    let day2 = decode(&data, KlineType::Day2);
+    for (a, b) in day.list.iter().zip(&day2.list) {
+        assert_eq!(a.time, b.time);
+        assert_eq!(a.volume, b.volume);
+        assert_eq!(a.close, b.close);
+    }
+
+    // 把第一条记录的时间改为分钟格式：2024-10-16 09:31
+    let mut minute_data = data.clone();
+    let ymd = ((2024 - 2004) << 11) | 1016u16;
+    minute_data[2..4].copy_from_slice(&ymd.to_le_bytes());
+    minute_data[4..6].copy_from_slice(&(9 * 60 + 31u16).to_le_bytes());
+    let first = |kline_type| decode(&minute_data, kline_type).list[0].clone();
+    let (minute, minute2) = (first(KlineType::Minute), first(KlineType::Minute2));
+    assert_eq!(minute.time, 1729042260);
+    assert_eq!(minute2.time, minute.time);
+    assert_eq!(minute2.volume, day.list[0].volume / 100);
+    assert_eq!(minute2.volume, minute.volume);
+    assert_eq!(minute2.close, day.list[0].close);
+}
+
+#[test]
+fn test_trade_status_values() {
+    assert_eq!(TradeStatus::from_value(4), TradeStatus::Auction);
+    assert_eq!(TradeStatus::from_value(7), TradeStatus::Other(7));
+    for v in 0..8 {
+        assert_eq!(TradeStatus::from_value(v).value(), v);
+    }
+    assert_eq!(format!("{:?}", TradeStatus::Auction), "竞");
+}
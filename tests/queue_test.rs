@@ -0,0 +1,116 @@
+use tdx_rust::protocol::test_data::TestData;
+use tdx_rust::queue::{estimate, Side};
+use tdx_rust::*;
+
+fn quote() -> QuoteInfo {
+    let content = std::fs::read_to_string("tdx-test/test-data/quote.json").unwrap();
+    let test_data: TestData = serde_json::from_str(&content).unwrap();
+    let response = ResponseFrame::decode(&test_data.decode_response().unwrap()).unwrap();
+    Quote::decode_response(&response.data).unwrap().remove(0)
+}
+
+fn with_book(bids: [(i64, i32); 5], asks: [(i64, i32); 5]) -> QuoteInfo {
+    let mut q = quote();
+    for i in 0..5 {
+        q.buy_level[i].price = Price(bids[i].0);
+        q.buy_level[i].number = bids[i].1;
+        q.sell_level[i].price = Price(asks[i].0);
+        q.sell_level[i].number = asks[i].1;
+    }
+    q
+}
+
+fn trade(time: i64, price: i64, volume: i32) -> Trade {
+    Trade {
+        time,
+        price: Price(price),
+        volume,
+        status: TradeStatus::Neutral,
+        number: 1,
+    }
+}
+
+// 2024-01-02 10:00 北京时间
+const BASE: i64 = 1704160800;
+const MINUTE: i64 = 60;
+
+#[test]
+fn test_queue_estimate() {
+    let asks = [
+        (10_010, 100),
+        (10_020, 100),
+        (10_030, 100),
+        (10_040, 100),
+        (10_050, 100),
+    ];
+    let q0 = with_book(
+        [
+            (10_000, 600),
+            (9_990, 200),
+            (9_980, 200),
+            (9_970, 200),
+            (9_960, 200),
+        ],
+        asks,
+    );
+    // 买一被成交300手、撤单100手
+    let q1 = with_book(
+        [
+            (10_000, 200),
+            (9_990, 200),
+            (9_980, 200),
+            (9_970, 200),
+            (9_960, 200),
+        ],
+        asks,
+    );
+    // 买一被吃完，价格下移
+    let q2 = with_book(
+        [
+            (9_990, 150),
+            (9_980, 200),
+            (9_970, 200),
+            (9_960, 200),
+            (9_950, 200),
+        ],
+        asks,
+    );
+    // 分时成交只精确到分钟，同一分钟内只使用最后一次快照
+    let quotes = vec![
+        (BASE + 20, q1.clone()),
+        (BASE + 50, q0),
+        (BASE + MINUTE + 50, q1),
+        (BASE + 2 * MINUTE + 10, q2.clone()),
+        (BASE + 2 * MINUTE + 50, q2),
+    ];
+    let trades = vec![
+        trade(BASE, 10_000, 100),
+        trade(BASE + MINUTE, 10_000, 250),
+        trade(BASE + MINUTE, 10_000, 50),
+        trade(BASE + MINUTE, 10_010, 30),
+        trade(BASE + 2 * MINUTE, 10_000, 200),
+    ];
+
+    let est = estimate("sz000001", &quotes, &trades);
+    assert_eq!(est.len(), 4);
+
+    let bid = &est[0];
+    assert_eq!(bid.side, Side::Bid);
+    assert_eq!(bid.time, BASE + MINUTE + 50);
+    assert_eq!(bid.traded, 300);
+    assert_eq!(bid.end, Some(200));
+    assert_eq!(bid.cancelled, Some(100));
+    assert_eq!(bid.rate, 5.0);
+    assert_eq!(bid.eta_secs, Some(40.0));
+
+    let ask = &est[1];
+    assert_eq!(ask.side, Side::Ask);
+    assert_eq!(ask.traded, 30);
+    assert_eq!(ask.cancelled, Some(-30));
+
+    let bid = &est[2];
+    assert_eq!(bid.time, BASE + 2 * MINUTE + 50);
+    assert_eq!(bid.traded, 200);
+    assert_eq!(bid.end, Some(0));
+    assert_eq!(bid.cancelled, Some(0));
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use tdx_rust::protocol::test_data::TestData;
+use tdx_rust::rank::*;
+use tdx_rust::*;
+
+fn load_quotes() -> Vec<QuoteInfo> {
+    let content = std::fs::read_to_string("tdx-test/test-data/quote.json").unwrap();
+    let test_data: TestData = serde_json::from_str(&content).unwrap();
+    let response = ResponseFrame::decode(&test_data.decode_response().unwrap()).unwrap();
+    Quote::decode_response(&response.data).unwrap()
+}
+
+#[test]
+fn test_ranking_by_return() {
+    let mut quotes = load_quotes();
+    assert!(quotes.len() >= 2);
+    quotes.truncate(2);
+    for (q, pct) in quotes.iter_mut().zip([1, 5]) {
+        q.k.last = Price(10_000);
+        q.k.close = Price(10_000 + pct * 100);
+    }
+
+    let mut ranking = Ranking::new(RankKey::Return);
+    ranking.update(&quotes);
+    let top = ranking.top(5);
+    assert_eq!(top.len(), 2);
+    assert_eq!(
+        top[0].code,
+        format!("{}{}", quotes[1].exchange.as_str(), quotes[1].code)
+    );
+    assert!((top[0].change_pct - 5.0).abs() < 1e-9);
+    assert!((top[1].change_pct - 1.0).abs() < 1e-9);
+    assert_eq!(ranking.top(1).len(), 1);
+}
+
+#[test]
+fn test_ranking_by_volume_ratio() {
+    let mut quotes = load_quotes();
+    quotes.truncate(2);
+    let codes: Vec<String> = quotes
+        .iter()
+        .map(|q| format!("{}{}", q.exchange.as_str(), q.code))
+        .collect();
+    quotes[0].total_hand = 300;
+    quotes[1].total_hand = 300;
+
+    // 只有第二个代码设置了基准成交量，第一个排在后面
+    let baseline = HashMap::from([(codes[1].clone(), 100)]);
+    let mut ranking = Ranking::new(RankKey::VolumeRatio).with_baseline(baseline);
+    ranking.update(&quotes);
+    let top = ranking.top(2);
+    assert_eq!(top[0].code, codes[1]);
+    assert_eq!(top[0].volume_ratio, Some(3.0));
+    assert_eq!(top[1].volume_ratio, None);
+}
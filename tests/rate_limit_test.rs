@@ -0,0 +1,37 @@
+use std::time::{Duration, Instant};
+use tdx_rust::RateLimiter;
+
+#[tokio::test]
+async fn test_rate_limiter_spaces_requests() {
+    let limiter = RateLimiter::with_burst(100.0, 1.0);
+    let start = Instant::now();
+    for _ in 0..11 {
+        limiter.acquire().await;
+    }
+    // 第一个令牌立即可用，之后每个间隔10毫秒
+    assert!(start.elapsed() >= Duration::from_millis(95));
+}
+
+#[tokio::test]
+async fn test_rate_limiter_burst() {
+    let limiter = RateLimiter::new(5.0);
+    assert_eq!(limiter.rate(), 5.0);
+    let start = Instant::now();
+    for _ in 0..5 {
+        limiter.acquire().await;
+    }
+    assert!(start.elapsed() < Duration::from_millis(100));
+
+    limiter.acquire().await;
+    assert!(start.elapsed() >= Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn test_rate_limiter_tiny_rate() {
+    // 极小的速率不会因等待时间溢出而 panic
+    let limiter = RateLimiter::new(1e-310);
+    assert_eq!(limiter.rate(), 1e-6);
+    limiter.acquire().await;
+    let wait = tokio::time::timeout(Duration::from_millis(10), limiter.acquire()).await;
+    assert!(wait.is_err());
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+use tdx_rust::mock::MockServer;
+use tdx_rust::registry::{client_for, duplicates, evict};
+use tdx_rust::*;
+
+async fn server() -> MockServer {
+    MockServer::builder()
+        .with_fixtures()
+        .unwrap()
+        .start()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_client_for_reuse() {
+    let server = server().await;
+
+    let (a, b) = tokio::join!(client_for(server.addr()), client_for(server.addr()));
+    let (a, b) = (a.unwrap(), b.unwrap());
+    assert!(Arc::ptr_eq(&a, &b));
+    assert_eq!(server.count(MessageType::Connect), 1);
+
+    let c = client_for(server.addr()).await.unwrap();
+    assert!(Arc::ptr_eq(&a, &c));
+    assert_eq!(server.count(MessageType::Connect), 1);
+    assert!(duplicates().iter().all(|(addr, _)| addr != server.addr()));
+}
+
+#[tokio::test]
+async fn test_client_for_after_drop() {
+    let server = server().await;
+
+    let client = client_for(server.addr()).await.unwrap();
+    drop(client);
+
+    // 所有使用者释放后重新连接
+    let client = client_for(server.addr()).await.unwrap();
+    assert_eq!(server.count(MessageType::Connect), 2);
+    assert!(client.get_count(Exchange::SZ).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_evict() {
+    let server = server().await;
+
+    let old = client_for(server.addr()).await.unwrap();
+    evict(server.addr());
+
+    let new = client_for(server.addr()).await.unwrap();
+    assert!(!Arc::ptr_eq(&old, &new));
+    assert_eq!(server.count(MessageType::Connect), 2);
+
+    // 新客户端已登记，继续复用
+    let again = client_for(server.addr()).await.unwrap();
+    assert!(Arc::ptr_eq(&new, &again));
+}
@@ -0,0 +1,74 @@
+use tdx_rust::resample::{daily_to_monthly, daily_to_weekly, resample_adjusted, Period};
+use tdx_rust::*;
+
+const DAY: i64 = 86400;
+// 2024-01-02（周二）15:00 北京时间
+const T0: i64 = 1704178800;
+
+fn kline(day: i64, open: i64, close: i64) -> Kline {
+    Kline {
+        last: Price(open),
+        open: Price(open),
+        high: Price(open.max(close) + 100),
+        low: Price(open.min(close) - 100),
+        close: Price(close),
+        order: 1,
+        volume: 10,
+        amount: Amount(1000),
+        time: T0 + day * DAY,
+        up_count: 0,
+        down_count: 0,
+    }
+}
+
+fn daily() -> Vec<Kline> {
+    vec![
+        kline(0, 10_000, 10_100),  // 01-02 周二
+        kline(3, 10_100, 10_500),  // 01-05 周五
+        kline(6, 10_500, 10_200),  // 01-08 周一
+        kline(29, 10_200, 10_300), // 01-31 周三
+        kline(30, 10_300, 10_400), // 02-01 周四
+    ]
+}
+
+#[test]
+fn test_daily_to_weekly() {
+    let weekly = daily_to_weekly(&daily());
+    assert_eq!(weekly.len(), 3);
+    assert_eq!(weekly[0].open, Price(10_000));
+    assert_eq!(weekly[0].close, Price(10_500));
+    assert_eq!(weekly[0].high, Price(10_600));
+    assert_eq!(weekly[0].low, Price(9_900));
+    assert_eq!(weekly[0].volume, 20);
+    assert_eq!(weekly[0].amount, Amount(2000));
+    assert_eq!(weekly[0].time, T0 + 3 * DAY);
+    assert_eq!(weekly[2].volume, 20);
+}
+
+#[test]
+fn test_daily_to_monthly() {
+    let monthly = daily_to_monthly(&daily());
+    assert_eq!(monthly.len(), 2);
+    assert_eq!(monthly[0].close, Price(10_300));
+    assert_eq!(monthly[0].order, 4);
+    assert_eq!(monthly[1].open, Price(10_300));
+    assert!(daily_to_monthly(&[]).is_empty());
+}
+
+#[test]
+fn test_resample_adjusted() {
+    // 01-08 除权：10派10元
+    let gbbq = vec![Gbbq {
+        code: "sz000001".to_string(),
+        time: T0 + 6 * DAY - 15 * 3600,
+        category: 1,
+        c1: 10.0,
+        c2: 0.0,
+        c3: 0.0,
+        c4: 0.0,
+    }];
+    let weekly = resample_adjusted(&daily(), Period::Week, &gbbq, Adjustment::Forward);
+    // 第一周收盘 10.5 前复权为 9.5
+    assert_eq!(weekly[0].close, Price(9_500));
+    assert_eq!(weekly[1].close, Price(10_200));
+}
@@ -0,0 +1,75 @@
+use tdx_rust::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// 应答请求（空响应）；broken 为 true 时握手之后的请求直接断开
+async fn handle(mut stream: TcpStream, broken: bool) {
+    let mut header = [0u8; 10];
+    while stream.read_exact(&mut header).await.is_ok() {
+        let length = u16::from_le_bytes([header[6], header[7]]) as usize;
+        let mut body = vec![0u8; length];
+        if stream.read_exact(&mut body).await.is_err() {
+            return;
+        }
+        if broken && body[0..2] != (MessageType::Connect as u16).to_le_bytes() {
+            return;
+        }
+
+        let mut frame = PREFIX_RESP.to_be_bytes().to_vec();
+        frame.push(CONTROL_RESP_SUCCESS);
+        frame.extend_from_slice(&header[1..5]);
+        frame.push(0);
+        frame.extend_from_slice(&body[0..2]);
+        frame.extend_from_slice(&[0, 0, 0, 0]);
+        stream.write_all(&frame).await.unwrap();
+    }
+}
+
+fn serve(listener: TcpListener, broken: bool) {
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(handle(stream, broken));
+        }
+    });
+}
+
+#[tokio::test]
+async fn test_failover_to_next_host() {
+    let broken = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let broken_addr = broken.local_addr().unwrap().to_string();
+    serve(broken, true);
+
+    // 备用服务器在初次连接时还不可用，保证先连上失效的服务器
+    let spare = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let spare_addr = spare.local_addr().unwrap().to_string();
+    drop(spare);
+
+    let client = ResilientClient::connect(&[&broken_addr, &spare_addr])
+        .await
+        .unwrap();
+    assert_eq!(client.current().await.addr(), broken_addr);
+
+    serve(TcpListener::bind(&spare_addr).await.unwrap(), false);
+    client
+        .run(|c| async move { c.send_heartbeat().await })
+        .await
+        .unwrap();
+    assert_eq!(client.current().await.addr(), spare_addr);
+}
+
+#[tokio::test]
+async fn test_no_failover_when_disabled() {
+    let broken = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let broken_addr = broken.local_addr().unwrap().to_string();
+    serve(broken, true);
+
+    let client = ResilientClient::connect(&[&broken_addr])
+        .await
+        .unwrap()
+        .with_max_failovers(0);
+    let err = client
+        .run(|c| async move { c.send_heartbeat().await })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::Io(_)), "{:?}", err);
+}
@@ -0,0 +1,75 @@
+//! 数据落地测试
+
+use tdx_rust::adjust::Adjustment;
+use tdx_rust::protocol::*;
+use tdx_rust::sink::{CsvSink, JsonLinesSink, Sink};
+
+fn trades() -> Vec<Trade> {
+    vec![
+        Trade {
+            time: 1_700_000_000,
+            price: Price(10_230),
+            volume: 12,
+            status: TradeStatus::Buy,
+            number: 3,
+        },
+        Trade {
+            time: 1_700_000_060,
+            price: Price(10_240),
+            volume: 5,
+            status: TradeStatus::Sell,
+            number: 1,
+        },
+    ]
+}
+
+#[test]
+fn test_json_lines_sink() {
+    let mut sink = JsonLinesSink::new(Vec::new());
+    sink.write_trades("sz000001", &trades()).unwrap();
+    sink.flush().unwrap();
+
+    let output = String::from_utf8(sink.into_inner()).unwrap();
+    let lines: Vec<serde_json::Value> = output
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["type"], "trade");
+    assert_eq!(lines[0]["code"], "sz000001");
+    assert_eq!(lines[0]["price"], 10_230);
+    assert_eq!(lines[1]["status"], "Sell");
+}
+
+#[test]
+fn test_csv_sink() {
+    let dir = std::env::temp_dir().join(format!("tdx_sink_{}", std::process::id()));
+    let mut sink = CsvSink::new(&dir).unwrap();
+    sink.write_trades("sz000001", &trades()).unwrap();
+    sink.flush().unwrap();
+
+    let content = std::fs::read_to_string(dir.join("trades.csv")).unwrap();
+    let (notes, lines): (Vec<&str>, Vec<&str>) = content.lines().partition(|l| l.starts_with('#'));
+    assert_eq!(notes.len(), 2);
+    assert!(notes[0].contains("price=厘") && notes[0].contains("volume=手"));
+    assert!(notes[1].contains("UTC+8"));
+    assert!(content.starts_with('#'));
+    assert_eq!(lines[0], "code,time,price,volume,status,number");
+    assert_eq!(lines[1], "sz000001,1700000000,10230,12,0,3");
+    assert_eq!(lines.len(), 3);
+    assert!(!dir.join("klines.csv").exists());
+
+    let mut sink = CsvSink::new(&dir)
+        .unwrap()
+        .with_adjustment(Adjustment::Forward);
+    sink.write_klines("sz000001", &[]).unwrap();
+    sink.flush().unwrap();
+    let content = std::fs::read_to_string(dir.join("klines.csv")).unwrap();
+    assert!(content.lines().any(|l| l == "# 复权: 前复权"));
+    assert_eq!(
+        content.lines().last().unwrap(),
+        "code,time,last,open,high,low,close,volume,amount,order,up_count,down_count"
+    );
+
+    std::fs::remove_dir_all(&dir).ok();
+}
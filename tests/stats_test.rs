@@ -0,0 +1,40 @@
+use tdx_rust::*;
+
+fn trade(price: i64, volume: i32) -> Trade {
+    Trade {
+        time: 1_700_000_000,
+        price: Price(price),
+        volume,
+        status: TradeStatus::Buy,
+        number: 1,
+    }
+}
+
+#[test]
+fn test_symbol_stats() {
+    let mut stats = SymbolStats::new("sz000001", Price(50), 2);
+    assert!(stats.vwap().is_none());
+
+    stats.update_trade(&trade(10_000, 10));
+    stats.update_trade(&trade(10_120, 30));
+    stats.update_trade(&trade(9_980, 10));
+
+    let s = stats.snapshot();
+    assert_eq!(s.open, Some(Price(10_000)));
+    assert_eq!(s.high, Some(Price(10_120)));
+    assert_eq!(s.low, Some(Price(9_980)));
+    assert_eq!(s.last, Some(Price(9_980)));
+    assert_eq!(s.volume, 50);
+    assert_eq!(s.trades, 3);
+    // (10000×10 + 10120×30 + 9980×10) / 50
+    assert_eq!(s.vwap, Some(Price(10_068)));
+    assert_eq!(
+        s.profile,
+        [(Price(9_950), 10), (Price(10_000), 10), (Price(10_100), 30)]
+    );
+    assert_eq!(s.recent.len(), 2);
+    assert_eq!(s.recent[1].price, Price(9_980));
+
+    let json = serde_json::to_value(&s).unwrap();
+    assert_eq!(json["volume"], 50);
+}
@@ -0,0 +1,206 @@
+use tdx_rust::subscribe::partition;
+
+#[test]
+fn test_partition() {
+    let codes: Vec<String> = (0..7).map(|i| format!("sz00000{}", i)).collect();
+    let parts = partition(&codes, 3);
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[0], ["sz000000", "sz000003", "sz000006"]);
+    assert_eq!(parts[2], ["sz000002", "sz000005"]);
+
+    // 没有连接时全部放在一个分区
+    assert_eq!(partition(&codes, 0), vec![codes]);
+}
+
+#[test]
+fn test_activity_tiers() {
+    use tdx_rust::protocol::test_data::TestData;
+    use tdx_rust::subscribe::{ActivityTracker, PollTier};
+    use tdx_rust::*;
+
+    let content = std::fs::read_to_string("tdx-test/test-data/quote.json").unwrap();
+    let test_data: TestData = serde_json::from_str(&content).unwrap();
+    let response = ResponseFrame::decode(&test_data.decode_response().unwrap()).unwrap();
+    let mut quotes = Quote::decode_response(&response.data).unwrap();
+    quotes.truncate(1);
+    let active = format!("{}{}", quotes[0].exchange.as_str(), quotes[0].code);
+    let codes = vec![active.clone(), "sz399999".to_string()];
+
+    let mut tracker = ActivityTracker::new(vec![PollTier::new(2, 3)]);
+    assert_eq!(tracker.due(&codes, 0), codes);
+
+    for _ in 0..3 {
+        tracker.observe(&codes, &quotes);
+        quotes[0].total_hand += 1;
+    }
+    // 活跃代码每个周期都轮询，未返回的代码降为每3个周期一次
+    assert_eq!(tracker.every(&active), 1);
+    assert_eq!(tracker.every("sz399999"), 3);
+    let due: Vec<usize> = (0..6).map(|c| tracker.due(&codes, c).len()).collect();
+    assert_eq!(due, [1, 1, 2, 1, 1, 2]);
+
+    // 不分层时每次都轮询
+    assert_eq!(ActivityTracker::default().every("sz399999"), 1);
+}
+
+#[tokio::test]
+async fn test_subscribe_quotes_dedup() {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tdx_rust::protocol::test_data::TestData;
+    use tdx_rust::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    let content = std::fs::read_to_string("tdx-test/test-data/quote.json").unwrap();
+    let test_data: TestData = serde_json::from_str(&content).unwrap();
+    let payload = ResponseFrame::decode(&test_data.decode_response().unwrap())
+        .unwrap()
+        .data;
+    let codes: Vec<String> = Quote::decode_response(&payload)
+        .unwrap()
+        .iter()
+        .map(|q| format!("{}{}", q.exchange.as_str(), q.code))
+        .collect();
+
+    // 每个请求都回复同一份行情
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut header = [0u8; 10];
+        while stream.read_exact(&mut header).await.is_ok() {
+            let length = u16::from_le_bytes([header[6], header[7]]) as usize;
+            let mut body = vec![0u8; length];
+            stream.read_exact(&mut body).await.unwrap();
+
+            let mut frame = PREFIX_RESP.to_be_bytes().to_vec();
+            frame.push(CONTROL_RESP_SUCCESS);
+            frame.extend_from_slice(&header[1..5]);
+            frame.push(0);
+            frame.extend_from_slice(&body[0..2]);
+            frame.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+            frame.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+            frame.extend_from_slice(&payload);
+            stream.write_all(&frame).await.unwrap();
+        }
+    });
+
+    let client = Arc::new(Client::connect(&addr).await.unwrap());
+    let mut rx = subscribe_quotes(client, codes.clone(), Duration::from_millis(20));
+
+    let first = rx.recv().await.unwrap();
+    assert_eq!(first.len(), codes.len());
+
+    // 行情不变，之后的轮询不再推送
+    let next = tokio::time::timeout(Duration::from_millis(150), rx.recv()).await;
+    assert!(next.is_err());
+}
+
+#[test]
+fn test_trade_cursor() {
+    use tdx_rust::*;
+
+    let trade = |i: i64| Trade {
+        time: 1_700_000_000 + i * 60,
+        price: Price(10_000 + i),
+        volume: 1,
+        status: TradeStatus::Buy,
+        number: 1,
+    };
+    let day: Vec<Trade> = (0..20).map(trade).collect();
+
+    let mut cursor = TradeCursor::new();
+    assert!(cursor.is_empty());
+    assert_eq!(cursor.advance(&day[..10]).unwrap().len(), 10);
+
+    // 最新的一页与已推送部分重叠，只返回新成交
+    let new = cursor.advance(&day[5..15]).unwrap();
+    assert_eq!(new, &day[10..15]);
+    assert!(cursor.advance(&day[5..15]).unwrap().is_empty());
+
+    // 新成交超出范围时找不到重合位置
+    assert!(cursor.advance(&day[16..20]).is_none());
+    assert_eq!(cursor.advance(&day).unwrap(), &day[15..]);
+}
+
+#[tokio::test]
+async fn test_sharded_subscriber() {
+    use std::collections::BTreeSet;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tdx_rust::mock::MockServer;
+    use tdx_rust::subscribe::ShardedSubscriber;
+    use tdx_rust::*;
+
+    /// 行情请求中的代码
+    fn requested(server: &MockServer) -> Vec<Vec<String>> {
+        server
+            .requests()
+            .into_iter()
+            .filter(|(t, _)| *t == MessageType::Quote)
+            .map(|(_, data)| {
+                let count = u16::from_le_bytes([data[8], data[9]]) as usize;
+                data[10..10 + count * 7]
+                    .chunks(7)
+                    .map(|c| {
+                        let exchange = Exchange::from_u8(c[0]).unwrap();
+                        format!("{}{}", exchange.as_str(), String::from_utf8_lossy(&c[1..]))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // 每个连接一个模拟服务器，便于区分各连接请求的代码
+    let mut servers = Vec::new();
+    let mut pool = Vec::new();
+    for _ in 0..2 {
+        let server = MockServer::builder()
+            .with_fixtures()
+            .unwrap()
+            .start()
+            .await
+            .unwrap();
+        pool.push(Arc::new(server.client().await.unwrap()));
+        servers.push(server);
+    }
+    let codes: Vec<String> = ["sz000001", "sh600008", "sz000002", "sh600000", "sz000004"]
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    let subscriber = ShardedSubscriber::new(pool, codes, 2).with_cycle(Duration::from_millis(20));
+    let partitions = subscriber.partitions();
+    assert_eq!(partitions[0], ["sz000001", "sz000002", "sz000004"]);
+    assert_eq!(partitions[1], ["sh600008", "sh600000"]);
+
+    let mut rx = subscriber.spawn();
+    // 两个周期：第一个连接每周期2批，第二个连接每周期1批
+    for _ in 0..6 {
+        let quotes = rx.recv().await.unwrap();
+        assert_eq!(quotes.len(), 2);
+    }
+    drop(rx);
+
+    for (server, partition) in servers.iter().zip(&partitions) {
+        let batches = requested(server);
+        assert!(batches.len() >= 2);
+        // 每批不超过分片大小，只请求本连接负责的代码，首个周期覆盖全部代码
+        assert!(batches.iter().all(|b| b.len() <= 2));
+        let seen: BTreeSet<&String> = batches.iter().flatten().collect();
+        assert_eq!(seen, partition.iter().collect());
+    }
+
+    // 接收端关闭后任务退出，不再请求
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let counts: Vec<usize> = servers
+        .iter()
+        .map(|s| s.count(MessageType::Quote))
+        .collect();
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let after: Vec<usize> = servers
+        .iter()
+        .map(|s| s.count(MessageType::Quote))
+        .collect();
+    assert_eq!(counts, after);
+}
@@ -0,0 +1,38 @@
+use tdx_rust::ticksize::*;
+use tdx_rust::Price;
+
+#[test]
+fn test_tick_for() {
+    let p = Price::from_yuan(10.0);
+    // 股票
+    assert_eq!(tick_for("600519", p), Price(10));
+    assert_eq!(tick_for("sz000001", p), Price(10));
+    assert_eq!(tick_for("300750", p), Price(10));
+    assert_eq!(tick_for("688981", p), Price(10));
+    assert_eq!(tick_for("bj920001", p), Price(10));
+    // 基金
+    assert_eq!(tick_for("510300", p), Price(1));
+    assert_eq!(tick_for("sz159915", p), Price(1));
+    assert_eq!(tick_for("sz161725", p), Price(1));
+    // 可转债
+    assert_eq!(tick_for("113050", p), Price(1));
+    assert_eq!(tick_for("sz123001", p), Price(1));
+    // 指数
+    assert_eq!(tick_for("sh000001", p), Price(1));
+    assert_eq!(tick_for("399001", p), Price(1));
+}
+
+#[test]
+fn test_snap_to_tick() {
+    let tick = Price(10);
+    assert_eq!(floor_to_tick(Price(12345), tick), Price(12340));
+    assert_eq!(ceil_to_tick(Price(12341), tick), Price(12350));
+    assert_eq!(ceil_to_tick(Price(12340), tick), Price(12340));
+    assert_eq!(round_to_tick(Price(12344), tick), Price(12340));
+    assert_eq!(round_to_tick(Price(12345), tick), Price(12350));
+    assert!(is_on_tick(Price(12340), tick));
+    assert!(!is_on_tick(Price(12345), tick));
+
+    assert_eq!(snap_price("600519", Price(1688886)), Price(1688890));
+    assert_eq!(snap_price("510300", Price(3987)), Price(3987));
+}